@@ -0,0 +1,166 @@
+// Player side helpers so inputs can be sanity checked without spending SOL on a failed transaction.
+use nug_wager_protocol::{
+    compute_commitment, compute_payout, verify_commitment_offchain, PAYOUT_MULTIPLIER_LUT,
+};
+use std::env;
+use std::process::ExitCode;
+use url::form_urlencoded::byte_serialize;
+use url::Url;
+
+mod validate;
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+const USAGE: &str = "usage:
+  mcnuggies-cli compute-commitment <bet_value> <salt>
+  mcnuggies-cli verify-commitment <commitment_hex> <bet_value> <salt>
+  mcnuggies-cli simulate-payout <bet_value> <result> <bet_amount_lamports>
+  mcnuggies-cli generate-payment-link <transaction_request_url> <bet_value> <salt> <amount_sol>
+  mcnuggies-cli validate-game-state <rpc_url>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let outcome = match args.as_slice() {
+        ["compute-commitment", bet_value, salt] => compute(bet_value, salt),
+        ["verify-commitment", commitment, bet_value, salt] => verify(commitment, bet_value, salt),
+        ["simulate-payout", bet_value, result, bet_amount] => {
+            simulate(bet_value, result, bet_amount)
+        }
+        ["generate-payment-link", base_url, bet_value, salt, amount] => {
+            payment_link(base_url, bet_value, salt, amount)
+        }
+        ["validate-game-state", rpc_url] => validate::validate_game_state(rpc_url),
+        _ => Err(USAGE.to_string()),
+    };
+    match outcome {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn compute(bet_value: &str, salt: &str) -> Result<bool, String> {
+    let bet_value = parse_bet_value(bet_value)?;
+    let salt = parse::<u64>(salt, "salt")?;
+    println!("{}", to_hex(&compute_commitment(bet_value, salt)));
+    Ok(true)
+}
+
+// exits non-zero on a mismatch so it can be used in scripts
+fn verify(commitment: &str, bet_value: &str, salt: &str) -> Result<bool, String> {
+    let commitment = from_hex(commitment)?;
+    let bet_value = parse_bet_value(bet_value)?;
+    let salt = parse::<u64>(salt, "salt")?;
+    let matches = verify_commitment_offchain(commitment, bet_value, salt);
+    if matches {
+        println!("commitment matches, safe to reveal");
+    } else {
+        println!(
+            "commitment does NOT match, reveal would fail with CommitmentMismatch (expected {})",
+            to_hex(&compute_commitment(bet_value, salt))
+        );
+    }
+    Ok(matches)
+}
+
+// same payout rules as reveal_and_claim, ignoring host liquidity and disputes
+fn simulate(bet_value: &str, result: &str, bet_amount: &str) -> Result<bool, String> {
+    let bet_value = parse_bet_value(bet_value)?;
+    let result = parse_bet_value(result)?;
+    let bet_amount = parse::<u64>(bet_amount, "bet amount")?;
+    if bet_value > result {
+        println!("loss: bet {bet_value} is over the result {result}, payout 0");
+        return Ok(true);
+    }
+    let scaled_multiplier = PAYOUT_MULTIPLIER_LUT[(result - bet_value) as usize];
+    let payout = compute_payout(bet_amount, scaled_multiplier).map_err(|err| err.to_string())?;
+    println!("win: payout {payout} lamports (scaled multiplier {scaled_multiplier})");
+    Ok(true)
+}
+
+// Solana Pay transaction request for commit_bet, scan it with a mobile wallet to commit.
+// only the commitment goes in the link, the bet value and salt never leave this machine.
+// the server at transaction_request_url builds the commit_bet transaction (PDAs and all) for
+// whichever wallet account POSTs to it, from the commitment and amount in the query
+fn payment_link(base_url: &str, bet_value: &str, salt: &str, amount: &str) -> Result<bool, String> {
+    let bet_value = parse_bet_value(bet_value)?;
+    let salt = parse::<u64>(salt, "salt")?;
+    let lamports = parse_sol(amount)?;
+    let commitment = to_hex(&compute_commitment(bet_value, salt));
+
+    let mut link = Url::parse(base_url).map_err(|err| format!("invalid url {base_url}: {err}"))?;
+    if link.scheme() != "https" {
+        return Err(format!(
+            "transaction request url must be https, got {base_url}"
+        ));
+    }
+    link.query_pairs_mut()
+        .append_pair("commitment", &commitment)
+        .append_pair("amount", amount)
+        .append_pair("label", "McNuggies wager")
+        .append_pair("message", &format!("Commit a {amount} SOL bet"))
+        .append_pair("memo", &format!("nug-wager commit {}", &commitment[..16]));
+    // the spec wants the link url encoded whenever it has query parameters of its own
+    let encoded_link: String = byte_serialize(link.as_str().as_bytes()).collect();
+    println!("solana:{encoded_link}");
+    println!(
+        "commitment {commitment} for {lamports} lamports, keep the bet value and salt to reveal"
+    );
+    Ok(true)
+}
+
+// decimal SOL to lamports without going through a float
+fn parse_sol(value: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid SOL amount: {value}");
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > 9 || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let whole = if whole.is_empty() {
+        0
+    } else {
+        whole.parse::<u64>().map_err(|_| invalid())?
+    };
+    let fraction = format!("{fraction:0<9}")
+        .parse::<u64>()
+        .map_err(|_| invalid())?;
+    whole
+        .checked_mul(LAMPORTS_PER_SOL)
+        .and_then(|lamports| lamports.checked_add(fraction))
+        .filter(|lamports| *lamports > 0)
+        .ok_or_else(invalid)
+}
+
+fn parse<T: std::str::FromStr>(value: &str, name: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid {name}: {value}"))
+}
+
+fn parse_bet_value(value: &str) -> Result<u8, String> {
+    match parse::<u8>(value, "bet value")? {
+        bet_value @ 0..=100 => Ok(bet_value),
+        _ => Err(format!("bet value must be between 0 and 100, got {value}")),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Result<[u8; 32], String> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(format!("commitment must be 64 hex characters, got {hex}"));
+    }
+    let mut bytes = [0u8; 32];
+    for (byte, chunk) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        let pair = std::str::from_utf8(chunk).map_err(|err| err.to_string())?;
+        *byte = u8::from_str_radix(pair, 16).map_err(|_| format!("invalid hex: {hex}"))?;
+    }
+    Ok(bytes)
+}
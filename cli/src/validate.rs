@@ -0,0 +1,197 @@
+// Pre-reveal health check for the authority, read only. Loads the game, every bet over plain JSON-RPC
+// and checks the books add up before anyone starts revealing.
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use nug_wager_protocol::{
+    worst_case_remaining_payout_obligation, BetCommitment, BetEscrow, Game, GLOBAL_GAME_SEED,
+};
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+// what the game's total_player_pot should hold for this bet: the stake until it's settled,
+// plus anything held back on a dispute or still vesting once it is
+fn owed_from_pot(bet: &BetCommitment) -> u64 {
+    let stake = if bet.is_claimed { 0 } else { bet.amount };
+    let vesting = if bet.is_claimed {
+        bet.pending_payout_amount
+    } else {
+        0
+    };
+    stake + bet.held_payout + vesting
+}
+
+pub fn validate_game_state(rpc_url: &str) -> Result<bool, String> {
+    let program_id = nug_wager_protocol::ID;
+    let (game_key, _) = Pubkey::find_program_address(&[GLOBAL_GAME_SEED], &program_id);
+    let (treasury_key, _) =
+        Pubkey::find_program_address(&[b"treasury", game_key.as_ref()], &program_id);
+
+    let (_, game_data) = get_account(rpc_url, &game_key)?
+        .ok_or_else(|| format!("game account {game_key} not found"))?;
+    let game = Game::try_deserialize(&mut game_data.as_slice())
+        .map_err(|err| format!("game account {game_key} isn't the current layout: {err}"))?;
+    let treasury_balance = get_account(rpc_url, &treasury_key)?
+        .map(|(lamports, _)| lamports)
+        .unwrap_or(0);
+
+    let mut issues = Vec::new();
+    let mut commitment_count = 0u64;
+    let mut unsettled_count = 0u64;
+    let mut frozen_count = 0u64;
+    let mut expected_pot = 0u64;
+    for (address, data) in program_accounts(rpc_url, BetCommitment::DISCRIMINATOR)? {
+        let bet = match BetCommitment::try_deserialize(&mut data.as_slice()) {
+            Ok(bet) => bet,
+            Err(err) => {
+                issues.push(format!("commitment {address} doesn't deserialize: {err}"));
+                continue;
+            }
+        };
+        if bet.game != game_key {
+            issues.push(format!("commitment {address} references game {}", bet.game));
+            continue;
+        }
+        let (expected_address, _) = Pubkey::find_program_address(
+            &[
+                b"commitment",
+                game_key.as_ref(),
+                bet.original_player.as_ref(),
+            ],
+            &program_id,
+        );
+        if address != expected_address {
+            issues.push(format!(
+                "commitment {address} isn't at its PDA {expected_address}"
+            ));
+        }
+        commitment_count += 1;
+        unsettled_count += u64::from(!bet.is_claimed);
+        frozen_count += u64::from(bet.is_frozen);
+        expected_pot += owed_from_pot(&bet);
+    }
+    let mut escrow_count = 0u64;
+    for (address, data) in program_accounts(rpc_url, BetEscrow::DISCRIMINATOR)? {
+        let escrow = match BetEscrow::try_deserialize(&mut data.as_slice()) {
+            Ok(escrow) => escrow,
+            Err(err) => {
+                issues.push(format!("escrow {address} doesn't deserialize: {err}"));
+                continue;
+            }
+        };
+        if escrow.bet.game != game_key {
+            issues.push(format!(
+                "escrow {address} references game {}",
+                escrow.bet.game
+            ));
+            continue;
+        }
+        escrow_count += 1;
+        unsettled_count += u64::from(!escrow.bet.is_claimed);
+        expected_pot += owed_from_pot(&escrow.bet);
+    }
+
+    if commitment_count + escrow_count != game.bet_count {
+        issues.push(format!(
+            "game.bet_count is {} but found {} commitments and {} escrows",
+            game.bet_count, commitment_count, escrow_count
+        ));
+    }
+    if expected_pot != game.total_player_pot {
+        issues.push(format!(
+            "game.total_player_pot is {} lamports but the bets add up to {}",
+            game.total_player_pot, expected_pot
+        ));
+    }
+    let worst_case = worst_case_remaining_payout_obligation(&game);
+    let payable = treasury_balance.saturating_sub(game.treasury_rent_reserve);
+    if payable < worst_case {
+        issues.push(format!(
+            "treasury holds {payable} lamports over rent, short of the {worst_case} worst case payout"
+        ));
+    }
+
+    println!("game {game_key}");
+    println!(
+        "  phase: {:?}",
+        game.phase().map_err(|err| err.to_string())?
+    );
+    println!(
+        "  bets: {} ({} commitments, {} escrows)",
+        game.bet_count, commitment_count, escrow_count
+    );
+    println!("  unsettled: {unsettled_count}, frozen: {frozen_count}");
+    println!("  total_player_pot: {} lamports", game.total_player_pot);
+    println!("  treasury {treasury_key}: {treasury_balance} lamports");
+    println!("  worst case payout: {worst_case} lamports");
+    if issues.is_empty() {
+        println!("healthy");
+    } else {
+        println!("{} issue(s):", issues.len());
+        for issue in &issues {
+            println!("  - {issue}");
+        }
+    }
+    Ok(issues.is_empty())
+}
+
+fn rpc(rpc_url: &str, method: &str, params: Value) -> Result<Value, String> {
+    let response: Value = ureq::post(rpc_url)
+        .send_json(json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+        .map_err(|err| format!("{method} request to {rpc_url} failed: {err}"))?
+        .into_json()
+        .map_err(|err| format!("{method} returned invalid json: {err}"))?;
+    if let Some(error) = response.get("error") {
+        return Err(format!("{method} failed: {error}"));
+    }
+    Ok(response["result"].clone())
+}
+
+// base64 data of an account as returned with "encoding": "base64"
+fn decode_data(account: &Value) -> Result<Vec<u8>, String> {
+    let encoded = account["data"][0]
+        .as_str()
+        .ok_or("account data missing from the rpc response")?;
+    BASE64
+        .decode(encoded)
+        .map_err(|err| format!("invalid account data: {err}"))
+}
+
+// lamports and data, None if the account doesn't exist
+fn get_account(rpc_url: &str, address: &Pubkey) -> Result<Option<(u64, Vec<u8>)>, String> {
+    let result = rpc(
+        rpc_url,
+        "getAccountInfo",
+        json!([address.to_string(), { "encoding": "base64" }]),
+    )?;
+    let account = &result["value"];
+    if account.is_null() {
+        return Ok(None);
+    }
+    let lamports = account["lamports"]
+        .as_u64()
+        .ok_or("account lamports missing from the rpc response")?;
+    Ok(Some((lamports, decode_data(account)?)))
+}
+
+// every program account starting with the anchor discriminator
+fn program_accounts(rpc_url: &str, discriminator: &[u8]) -> Result<Vec<(Pubkey, Vec<u8>)>, String> {
+    let filter = json!({ "memcmp": { "offset": 0, "bytes": BASE64.encode(discriminator), "encoding": "base64" } });
+    let result = rpc(
+        rpc_url,
+        "getProgramAccounts",
+        json!([nug_wager_protocol::ID.to_string(), { "encoding": "base64", "filters": [filter] }]),
+    )?;
+    result
+        .as_array()
+        .ok_or("getProgramAccounts didn't return a list")?
+        .iter()
+        .map(|entry| {
+            let pubkey = entry["pubkey"].as_str().ok_or("account pubkey missing")?;
+            let address = Pubkey::from_str(pubkey)
+                .map_err(|err| format!("invalid pubkey {pubkey}: {err}"))?;
+            Ok((address, decode_data(&entry["account"])?))
+        })
+        .collect()
+}
@@ -0,0 +1,101 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nug_wager_protocol::{
+    settle_reveal_amounts, RevealAmounts, RevealOutcome, BPS_DENOMINATOR,
+    DISPUTED_PAYOUT_HELD_PERCENT, PAYOUT_SCALE,
+};
+
+// Runs the same arithmetic settle_reveal does: it must never panic, the stake leaves the pot exactly once
+// when the reveal settles, and a paid reveal never dips into the other players' stakes.
+fuzz_target!(|input: (
+    (u64, Option<u32>, u16, u64, u16, u64),
+    (u64, u64, u64, u64, bool)
+)| {
+    let (
+        (bet_amount, scaled_multiplier, early_bird_bonus_bps, highest_bet_bonus, late_reveal_penalty_bps, min_payout_threshold),
+        (total_player_pot, treasury_balance, treasury_rent_reserve, treasury_critical_threshold, is_disputed),
+    ) = input;
+    // initialize_game rejects anything over 100%
+    let early_bird_bonus_bps = early_bird_bonus_bps % (BPS_DENOMINATOR as u16 + 1);
+    let late_reveal_penalty_bps = late_reveal_penalty_bps % (BPS_DENOMINATOR as u16 + 1);
+    let settlement = settle_reveal_amounts(&RevealAmounts {
+        bet_amount,
+        scaled_multiplier,
+        early_bird_bonus_bps,
+        highest_bet_bonus,
+        late_reveal_penalty_bps,
+        min_payout_threshold,
+        total_player_pot,
+        treasury_balance,
+        treasury_rent_reserve,
+        treasury_critical_threshold,
+        is_disputed,
+    });
+
+    // upper bound on the payout before the late penalty, worked out in u128 so it can't overflow itself
+    let base_payout =
+        bet_amount as u128 * scaled_multiplier.unwrap_or(0) as u128 / PAYOUT_SCALE as u128;
+    let max_payout = base_payout
+        + base_payout * early_bird_bonus_bps as u128 / BPS_DENOMINATOR as u128
+        + if base_payout > 0 { highest_bet_bonus as u128 } else { 0 };
+
+    let Ok(settlement) = settlement else {
+        // only a drifted pot or treasury, or a payout too big to hold back a share of, may error
+        assert!(
+            total_player_pot < bet_amount
+                || (treasury_balance as u128) < total_player_pot as u128 + treasury_rent_reserve as u128
+                || max_payout * DISPUTED_PAYOUT_HELD_PERCENT as u128 > u64::MAX as u128
+        );
+        return;
+    };
+    let pot_without_stake = total_player_pot - bet_amount;
+
+    match settlement.outcome {
+        RevealOutcome::Loss => {
+            assert_eq!(base_payout, 0);
+            assert_eq!(settlement.payout_amount, 0);
+            assert_eq!(settlement.total_player_pot, pot_without_stake);
+        }
+        RevealOutcome::BelowThreshold => {
+            assert!(settlement.payout_amount < min_payout_threshold);
+            assert_eq!(settlement.total_player_pot, pot_without_stake);
+        }
+        RevealOutcome::Unpaid => {
+            assert!(
+                settlement.host_liquidity < treasury_critical_threshold
+                    || settlement.payout_amount > settlement.host_liquidity
+            );
+            // the stake stays in the pot for withdraw_unpaid_bet to take out
+            assert_eq!(settlement.total_player_pot, total_player_pot);
+        }
+        RevealOutcome::Paid => {
+            assert!(settlement.payout_amount >= min_payout_threshold);
+            assert!(settlement.host_liquidity >= treasury_critical_threshold);
+            let held_payout = if is_disputed {
+                settlement.payout_amount * DISPUTED_PAYOUT_HELD_PERCENT / 100
+            } else {
+                0
+            };
+            assert_eq!(settlement.held_payout, held_payout);
+            assert_eq!(settlement.total_player_pot, pot_without_stake + held_payout);
+            // what's sent out leaves the remaining pot and the rent reserve fully backed
+            let transferred = settlement.payout_amount - held_payout;
+            assert!(
+                treasury_balance as u128 - transferred as u128
+                    >= settlement.total_player_pot as u128 + treasury_rent_reserve as u128
+            );
+        }
+    }
+    if settlement.outcome != RevealOutcome::Loss {
+        // the late penalty only ever takes away from the winnings and bonuses
+        assert_eq!(
+            settlement.payout_amount as u128 + settlement.late_reveal_penalty as u128,
+            max_payout
+        );
+        assert!(
+            settlement.late_reveal_penalty as u128
+                <= max_payout * late_reveal_penalty_bps as u128 / BPS_DENOMINATOR as u128
+        );
+    }
+});
@@ -0,0 +1,174 @@
+use crate::BetCommitment;
+use crate::Game;
+use crate::GameError;
+use crate::ANTI_SYBIL_VAULT_SEED;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+// Pays amount out of the treasury, returning the amount that was actually transferred
+pub fn withdraw_from_treasury_to_player<'info>(
+    game: &Account<'info, Game>,
+    game_treasury: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    player: &impl ToAccountInfo<'info>,
+    amount: u64,
+) -> Result<u64> {
+    let amount = dry_run_withdraw(game, game_treasury, amount)?;
+    transfer_from_treasury(game, game_treasury, system_program, player, amount)?;
+    Ok(amount)
+}
+
+// Runs the checks withdraw_from_treasury_to_player would without transferring anything,
+// so callers can validate before touching any state
+pub fn dry_run_withdraw(
+    game: &Account<'_, Game>,
+    game_treasury: &SystemAccount<'_>,
+    amount: u64,
+) -> Result<u64> {
+    // never dip into the rent reserve, otherwise the treasury can be garbage collected mid game.
+    // only close_empty_treasury is allowed to take it to zero
+    let remaining_balance = game_treasury
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(GameError::InsufficientTreasuryForReclaim)?;
+    require!(
+        remaining_balance >= game.treasury_rent_reserve,
+        GameError::TreasuryBelowRentReserve
+    );
+    Ok(amount)
+}
+
+pub(crate) fn transfer_from_treasury<'info>(
+    game: &Account<'info, Game>,
+    game_treasury: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    recipient: &impl ToAccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let game_key = game.key();
+    let bump = [game.treasury_bump];
+    let seeds = treasury_seeds(&game_key, &bump);
+    let signer_seeds = &[&seeds[..]];
+    transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Transfer {
+                from: game_treasury.to_account_info(),
+                to: recipient.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+// signer seeds for the game's treasury PDA, the same b"treasury" seeds the account constraints derive it from
+pub(crate) fn treasury_seeds<'a>(game_key: &'a Pubkey, bump: &'a [u8; 1]) -> [&'a [u8]; 3] {
+    [b"treasury", game_key.as_ref(), bump]
+}
+
+// Pays a bet's anti sybil stake out of the game's vault to recipient, returning the amount.
+// Nothing to do for bets that didn't put one down
+pub(crate) fn release_anti_sybil_stake<'info>(
+    game_key: &Pubkey,
+    bet_commitment: &mut BetCommitment,
+    anti_sybil_vault: Option<&SystemAccount<'info>>,
+    vault_bump: Option<u8>,
+    system_program: &Program<'info, System>,
+    recipient: &impl ToAccountInfo<'info>,
+) -> Result<u64> {
+    let amount = bet_commitment.anti_sybil_stake;
+    if amount == 0 {
+        return Ok(0);
+    }
+    let anti_sybil_vault = anti_sybil_vault.ok_or(GameError::AntiSybilVaultRequired)?;
+    let vault_bump = vault_bump.ok_or(GameError::AntiSybilVaultRequired)?;
+    let seeds = &[ANTI_SYBIL_VAULT_SEED, game_key.as_ref(), &[vault_bump]];
+    let signer_seeds = &[&seeds[..]];
+    transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Transfer {
+                from: anti_sybil_vault.to_account_info(),
+                to: recipient.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+    bet_commitment.anti_sybil_stake = 0;
+    Ok(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{
+        default_test_game, test_game, test_system_program, test_treasury, test_wallet,
+    };
+
+    #[test]
+    fn treasury_seeds_derive_the_treasury_pda() {
+        for _ in 0..32 {
+            let game_key = Pubkey::new_unique();
+            let (treasury, bump) =
+                Pubkey::find_program_address(&[b"treasury", game_key.as_ref()], &crate::ID);
+            let bump = [bump];
+            let signer =
+                Pubkey::create_program_address(&treasury_seeds(&game_key, &bump), &crate::ID)
+                    .unwrap();
+            assert_eq!(signer, treasury);
+        }
+    }
+
+    #[test]
+    fn withdraw_moves_exactly_the_requested_lamports() {
+        let mut game = default_test_game();
+        game.treasury_rent_reserve = 1_000;
+        let game = test_game(game);
+        let treasury = test_treasury(&game.key(), 10_000);
+        let player = test_wallet(500);
+        let paid = withdraw_from_treasury_to_player(
+            &game,
+            &treasury,
+            &test_system_program(),
+            &player,
+            9_000,
+        )
+        .unwrap();
+        assert_eq!(paid, 9_000);
+        assert_eq!(treasury.lamports(), 1_000);
+        assert_eq!(player.lamports(), 9_500);
+    }
+
+    #[test]
+    fn treasury_transfer_needs_the_right_bump() {
+        let mut game = test_game(default_test_game());
+        game.treasury_bump = game.treasury_bump.wrapping_sub(1);
+        let treasury = test_treasury(&game.key(), 10_000);
+        let player = test_wallet(0);
+        assert!(
+            transfer_from_treasury(&game, &treasury, &test_system_program(), &player, 1_000)
+                .is_err()
+        );
+        assert_eq!(treasury.lamports(), 10_000);
+        assert_eq!(player.lamports(), 0);
+    }
+
+    #[test]
+    fn withdraw_never_dips_into_the_rent_reserve() {
+        let mut game = default_test_game();
+        game.treasury_rent_reserve = 1_000;
+        let game = test_game(game);
+        let treasury = test_treasury(&game.key(), 10_000);
+        assert_eq!(dry_run_withdraw(&game, &treasury, 9_000).unwrap(), 9_000);
+        assert_eq!(
+            dry_run_withdraw(&game, &treasury, 9_001).unwrap_err(),
+            GameError::TreasuryBelowRentReserve.into()
+        );
+        assert_eq!(
+            dry_run_withdraw(&game, &treasury, 10_001).unwrap_err(),
+            GameError::InsufficientTreasuryForReclaim.into()
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use crate::current_clock;
+use crate::require_with_context;
+use crate::AcceptAuthorityTransfer;
+use crate::AuthorityTransferred;
+use crate::GameError;
+use crate::AUTHORITY_TRANSFER_TIMEOUT_SECONDS;
+use anchor_lang::prelude::*;
+
+pub fn accept_authority_transfer(ctx: Context<AcceptAuthorityTransfer>) -> Result<()> {
+    let clock = current_clock()?;
+    let game = &mut ctx.accounts.game;
+    let expires_at = game
+        .pending_authority_proposed_at
+        .saturating_add(AUTHORITY_TRANSFER_TIMEOUT_SECONDS);
+    require_with_context!(
+        clock.unix_timestamp <= expires_at,
+        GameError::AuthorityTransferExpired,
+        expected = format!("time at most {}", expires_at),
+        got = clock.unix_timestamp,
+    );
+
+    let old_authority = game.authority;
+    // the context already checked pending_authority is the signer
+    let new_authority = game
+        .pending_authority
+        .take()
+        .ok_or(GameError::NoPendingAuthority)?;
+    game.authority = new_authority;
+
+    emit!(AuthorityTransferred {
+        game: game.key(),
+        old_authority,
+        new_authority,
+    });
+    msg!(
+        "Authority transfer from {} to {} accepted",
+        old_authority,
+        new_authority
+    );
+    Ok(())
+}
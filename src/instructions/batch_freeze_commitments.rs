@@ -0,0 +1,105 @@
+use crate::current_clock;
+use crate::require_with_context;
+use crate::AuditEntry;
+use crate::AuditInstruction;
+use crate::BatchFreezeCommitments;
+use crate::BetCommitment;
+use crate::BulkCommitmentsFrozen;
+use crate::CommitmentAuditLog;
+use crate::GameError;
+use crate::AUDIT_SEED;
+use crate::MAX_BATCH_FREEZE_COMMITMENTS;
+use anchor_lang::prelude::*;
+
+pub fn batch_freeze_commitments<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchFreezeCommitments<'info>>,
+    players: Vec<Pubkey>,
+) -> Result<()> {
+    let count = set_commitments_frozen(&ctx, &players, true)?;
+    emit!(BulkCommitmentsFrozen {
+        count,
+        game: ctx.accounts.game.key(),
+    });
+    msg!("Froze {} commitments", count);
+    Ok(())
+}
+
+// remaining_accounts holds each player's BetCommitment in the same order as players, each followed by
+// its CommitmentAuditLog when the game keeps audit logs. every account is checked against the PDA derived
+// from the player so a different account can't be slipped in
+pub(crate) fn set_commitments_frozen<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, BatchFreezeCommitments<'info>>,
+    players: &[Pubkey],
+    is_frozen: bool,
+) -> Result<u8> {
+    require_with_context!(
+        players.len() <= MAX_BATCH_FREEZE_COMMITMENTS,
+        GameError::TooManyCommitments,
+        expected = format!("at most {} players", MAX_BATCH_FREEZE_COMMITMENTS),
+        got = players.len(),
+    );
+    let game = &ctx.accounts.game;
+    let game_key = game.key();
+    let accounts_per_player = if game.audit_log_enabled { 2 } else { 1 };
+    require_with_context!(
+        ctx.remaining_accounts.len() == players.len() * accounts_per_player,
+        GameError::InvalidRemainingAccounts,
+        expected = format!("{} remaining accounts", players.len() * accounts_per_player),
+        got = ctx.remaining_accounts.len(),
+    );
+    let now = current_clock()?.unix_timestamp;
+
+    for (player, accounts) in players
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(accounts_per_player))
+    {
+        let (commitment_key, _) = Pubkey::find_program_address(
+            &[b"commitment", game_key.as_ref(), player.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(
+            accounts[0].key(),
+            commitment_key,
+            GameError::InvalidRemainingAccounts
+        );
+        let mut bet_commitment = Account::<BetCommitment>::try_from(&accounts[0])?;
+        require!(
+            bet_commitment.game == game_key,
+            GameError::InvalidGameReference
+        );
+        if is_frozen {
+            require!(!bet_commitment.is_claimed, GameError::BetAlreadySettled);
+        }
+        bet_commitment.is_frozen = is_frozen;
+        bet_commitment.exit(ctx.program_id)?;
+
+        let mut audit_log = match accounts.get(1) {
+            Some(audit_log_info) => {
+                let (audit_log_key, _) = Pubkey::find_program_address(
+                    &[AUDIT_SEED, commitment_key.as_ref()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(
+                    audit_log_info.key(),
+                    audit_log_key,
+                    GameError::InvalidRemainingAccounts
+                );
+                Some(Account::<CommitmentAuditLog>::try_from(audit_log_info)?)
+            }
+            None => None,
+        };
+        let instruction = if is_frozen {
+            AuditInstruction::Freeze
+        } else {
+            AuditInstruction::Unfreeze
+        };
+        game.record_audit(
+            audit_log.as_mut(),
+            AuditEntry::new(instruction, ctx.accounts.authority.key(), 0, now),
+        )?;
+        if let Some(audit_log) = audit_log {
+            audit_log.exit(ctx.program_id)?;
+        }
+    }
+    Ok(players.len() as u8)
+}
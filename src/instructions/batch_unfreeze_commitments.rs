@@ -0,0 +1,17 @@
+use crate::instructions::set_commitments_frozen;
+use crate::BatchFreezeCommitments;
+use crate::BulkCommitmentsUnfrozen;
+use anchor_lang::prelude::*;
+
+pub fn batch_unfreeze_commitments<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchFreezeCommitments<'info>>,
+    players: Vec<Pubkey>,
+) -> Result<()> {
+    let count = set_commitments_frozen(&ctx, &players, false)?;
+    emit!(BulkCommitmentsUnfrozen {
+        count,
+        game: ctx.accounts.game.key(),
+    });
+    msg!("Unfroze {} commitments", count);
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use crate::CancelAuthorityTransfer;
+use crate::GameError;
+use anchor_lang::prelude::*;
+
+pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let pending_authority = game
+        .pending_authority
+        .take()
+        .ok_or(GameError::NoPendingAuthority)?;
+    msg!("Authority transfer to {} cancelled", pending_authority);
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::CheckAndExtendRevealDeadline;
+use crate::GameError;
+use crate::MAX_FINAL_CLAIM_DEADLINE_EXTENSIONS;
+use anchor_lang::prelude::*;
+
+pub fn check_and_extend_reveal_deadline(ctx: Context<CheckAndExtendRevealDeadline>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    if game.bet_count == 0 {
+        msg!("No bets, nothing to extend.");
+        return Ok(());
+    }
+    let reveal_pct = game
+        .revealed_bet_count
+        .checked_mul(100)
+        .ok_or(GameError::Overflow)?
+        / game.bet_count;
+    if reveal_pct >= game.min_reveal_pct_for_no_extension as u64 {
+        msg!(
+            "{}% of bets revealed, at least {}%. Reveal deadline not extended.",
+            reveal_pct,
+            game.min_reveal_pct_for_no_extension
+        );
+        return Ok(());
+    }
+
+    let extend = |deadline: i64| {
+        deadline
+            .checked_add(game.reveal_extension_seconds)
+            .ok_or(GameError::Overflow)
+    };
+    let reveal_deadline = extend(game.reveal_deadline.ok_or(GameError::DeadlineNotSet)?)?;
+    // keep the withdraw_unpaid_bet window after the reveal deadline intact
+    let final_claim_deadline = game.final_claim_deadline.map(extend).transpose()?;
+    let scheduled_final_claim_deadline = extend(game.scheduled_final_claim_deadline)?;
+    game.reveal_deadline = Some(reveal_deadline);
+    game.final_claim_deadline = final_claim_deadline;
+    game.scheduled_final_claim_deadline = scheduled_final_claim_deadline;
+    game.reveal_deadline_extended = true;
+    game.final_claim_deadline_extension_count = game
+        .final_claim_deadline_extension_count
+        .checked_add(1)
+        .ok_or(GameError::Overflow)?;
+    msg!(
+        "Only {}% of bets revealed, below {}%. Reveal deadline extended to {}, final claim deadline extended {} of {} times",
+        reveal_pct,
+        game.min_reveal_pct_for_no_extension,
+        reveal_deadline,
+        game.final_claim_deadline_extension_count,
+        MAX_FINAL_CLAIM_DEADLINE_EXTENSIONS
+    );
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::ClaimBiggestLoserPrize;
+use crate::GameError;
+use anchor_lang::prelude::*;
+
+pub fn claim_biggest_loser_prize(ctx: Context<ClaimBiggestLoserPrize>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player = *ctx.accounts.player.key;
+    let prize = game.biggest_loser_prize_lamports;
+
+    // the prize is the host's money, it can't come out of stakes still owed to players
+    let host_liquidity = ctx
+        .accounts
+        .game_treasury
+        .lamports()
+        .saturating_sub(game.treasury_rent_reserve)
+        .saturating_sub(game.total_player_pot)
+        .saturating_sub(game.undistributed_charity_amount());
+    require!(
+        host_liquidity >= prize,
+        GameError::InsufficientHostLiquidityForPrize
+    );
+
+    game.biggest_loser_prize_claimed = true;
+    let transferred = withdraw_from_treasury_to_player(
+        game,
+        &ctx.accounts.game_treasury,
+        &ctx.accounts.system_program,
+        &ctx.accounts.player,
+        prize,
+    )?;
+    game.total_paid_out = game
+        .total_paid_out
+        .checked_add(transferred)
+        .ok_or(GameError::PayoutAmountOverflow)?;
+    msg!(
+        "Paid biggest loser prize of {} to player {}, who bet {} above the result",
+        transferred,
+        player,
+        game.biggest_loss_gap
+    );
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::current_clock;
+use crate::AuditEntry;
+use crate::AuditInstruction;
+use crate::ClaimHeldPayout;
+use crate::GameError;
+use anchor_lang::prelude::*;
+
+pub fn claim_held_payout(ctx: Context<ClaimHeldPayout>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let commitment = &mut ctx.accounts.bet_commitment;
+    let player = *ctx.accounts.player.key;
+
+    let held_payout = commitment.held_payout;
+    // held payout was tracked as a player obligation, release it from the pot now it's being paid out
+    game.total_player_pot = game
+        .total_player_pot
+        .checked_sub(held_payout)
+        .ok_or(GameError::PlayerPotUnderflow)?;
+    game.total_held_payout = game
+        .total_held_payout
+        .checked_sub(held_payout)
+        .ok_or(GameError::PlayerPotUnderflow)?;
+    commitment.held_payout = 0;
+
+    let transferred = withdraw_from_treasury_to_player(
+        game,
+        &ctx.accounts.game_treasury,
+        &ctx.accounts.system_program,
+        &ctx.accounts.player,
+        held_payout,
+    )?;
+    game.total_paid_out = game
+        .total_paid_out
+        .checked_add(transferred)
+        .ok_or(GameError::PayoutAmountOverflow)?;
+    game.record_audit(
+        ctx.accounts.audit_log.as_mut(),
+        AuditEntry::new(
+            AuditInstruction::ClaimHeldPayout,
+            player,
+            -(transferred as i64),
+            current_clock()?.unix_timestamp,
+        ),
+    )?;
+    msg!(
+        "Transferred held payout {} to player {}.",
+        held_payout,
+        player
+    );
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::ClaimRemainingTreasury;
+use crate::GameError;
+use crate::TreasuryClaimedToEscrow;
+use anchor_lang::prelude::*;
+
+pub fn claim_host_liquidity_only(
+    ctx: Context<ClaimRemainingTreasury>,
+    amount: Option<u64>,
+) -> Result<()> {
+    let recipient = ctx.accounts.host_claim_recipient()?;
+    let game = &mut ctx.accounts.game;
+    let authority = *ctx.accounts.authority.key;
+    let game_treasury = &ctx.accounts.game_treasury;
+    // only what's above the player pot and the rent reserve, so every outstanding bet stays fully backed.
+    // losing stakes owed to charity aren't host liquidity either
+    let withdrawable = game_treasury
+        .to_account_info()
+        .lamports()
+        .saturating_sub(game.treasury_rent_reserve)
+        .saturating_sub(game.total_player_pot)
+        .saturating_sub(game.undistributed_charity_amount());
+    let claim_amount = amount.unwrap_or(withdrawable).min(withdrawable);
+    if claim_amount != 0 {
+        withdraw_from_treasury_to_player(
+            game,
+            game_treasury,
+            &ctx.accounts.system_program,
+            &recipient,
+            claim_amount,
+        )?;
+        if let Some(escrow_address) = game.host_fee_escrow {
+            emit!(TreasuryClaimedToEscrow {
+                game: game.key(),
+                escrow_address,
+                amount: claim_amount,
+            });
+        }
+        game.total_claimed_by_authority = game
+            .total_claimed_by_authority
+            .checked_add(claim_amount)
+            .ok_or(GameError::Overflow)?;
+        msg!(
+            "Claimed {} of {} lamports host liquidity for authority {}. Player pot of {} left in treasury.",
+            claim_amount,
+            withdrawable,
+            authority,
+            game.total_player_pot
+        );
+    } else {
+        msg!("No host liquidity above the player pot, nothing to claim.");
+    }
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use crate::require_with_context;
+use crate::ClaimInsurance;
+use crate::GameError;
+use crate::InsuranceClaimPaid;
+use anchor_lang::prelude::*;
+
+pub fn claim_insurance(ctx: Context<ClaimInsurance>) -> Result<()> {
+    let bet_commitment = &mut ctx.accounts.bet_commitment;
+    // insurance covers the winnings on top of the stake, the stake is still in the treasury for withdraw_unpaid_bet
+    let amount = bet_commitment
+        .pending_payout_amount
+        .saturating_sub(bet_commitment.amount);
+    require!(amount > 0, GameError::NothingToInsure);
+
+    // the fund is program owned, so it's debited directly. it has to stay rent exempt
+    let fund_info = ctx.accounts.insurance_fund.to_account_info();
+    let available = fund_info
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(fund_info.data_len()));
+    require_with_context!(
+        amount <= available,
+        GameError::InsufficientInsuranceFund,
+        expected = format!("claim at most {} lamports", available),
+        got = amount,
+    );
+    fund_info.sub_lamports(amount)?;
+    ctx.accounts.player.add_lamports(amount)?;
+
+    // withdraw_unpaid_bet only returns the stake from here on, so the winnings can't be paid twice
+    bet_commitment.pending_payout_amount = 0;
+    let insurance_fund = &mut ctx.accounts.insurance_fund;
+    insurance_fund.total_paid = insurance_fund
+        .total_paid
+        .checked_add(amount)
+        .ok_or(GameError::Overflow)?;
+
+    emit!(InsuranceClaimPaid {
+        player: ctx.accounts.player.key(),
+        game: ctx.accounts.game.key(),
+        amount,
+    });
+    msg!(
+        "Insurance paid {} lamports of unpaid winnings to player {}",
+        amount,
+        ctx.accounts.player.key()
+    );
+    Ok(())
+}
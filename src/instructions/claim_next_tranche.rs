@@ -0,0 +1,44 @@
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::current_clock;
+use crate::ClaimNextTranche;
+use crate::GameError;
+use anchor_lang::prelude::*;
+
+pub fn claim_next_tranche(ctx: Context<ClaimNextTranche>) -> Result<()> {
+    let clock = current_clock()?;
+    let game = &mut ctx.accounts.game;
+    let commitment = &mut ctx.accounts.bet_commitment;
+    let player = *ctx.accounts.player.key;
+
+    // whatever has unlocked since reveal or the last claim, tranches that opened in between come together
+    let already_paid = commitment.vesting_payout - commitment.pending_payout_amount;
+    let claimable = game
+        .unlocked_payout(commitment.vesting_payout, clock.unix_timestamp)
+        .saturating_sub(already_paid);
+    require!(claimable > 0, GameError::TrancheNotUnlocked);
+
+    game.total_player_pot = game
+        .total_player_pot
+        .checked_sub(claimable)
+        .ok_or(GameError::PlayerPotUnderflow)?;
+    commitment.pending_payout_amount -= claimable;
+
+    let transferred = withdraw_from_treasury_to_player(
+        game,
+        &ctx.accounts.game_treasury,
+        &ctx.accounts.system_program,
+        &ctx.accounts.player,
+        claimable,
+    )?;
+    game.total_paid_out = game
+        .total_paid_out
+        .checked_add(transferred)
+        .ok_or(GameError::PayoutAmountOverflow)?;
+    msg!(
+        "Transferred payout tranche of {} to player {}, {} still locked",
+        claimable,
+        player,
+        commitment.pending_payout_amount
+    );
+    Ok(())
+}
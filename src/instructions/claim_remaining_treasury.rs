@@ -1,24 +1,66 @@
-use crate::withdraw_from_treasury_to_player;
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::current_clock;
 use crate::ClaimRemainingTreasury;
+use crate::GameError;
+use crate::TreasuryClaimedToEscrow;
+use crate::TreasuryWithdrawn;
 use anchor_lang::prelude::*;
 
 pub fn claim_remaining_treasury(ctx: Context<ClaimRemainingTreasury>) -> Result<()> {
+    let clock = current_clock()?;
+    let recipient = ctx.accounts.host_claim_recipient()?;
     let game = &mut ctx.accounts.game;
+    // draining takes player stakes with it, so only once nobody is owed anything or every player window is over.
+    // claim_host_liquidity_only is the way to take profit before then
+    require!(
+        game.total_player_pot == 0
+            || clock.unix_timestamp >= game.final_claim_deadline.unwrap_or(i64::MAX),
+        GameError::PlayerObligationsOutstanding
+    );
     // provided authority from the signer
     let authority = *ctx.accounts.authority.key;
     let game_treasury = &ctx.accounts.game_treasury;
-    let treasury_balance = game_treasury.to_account_info().lamports();
-    if treasury_balance != 0 {
+    // rent reserve stays behind, it's taken by close_empty_treasury. so does anything owed to charity,
+    // and held payouts which stay claimable by their players however long the dispute went unfinalized
+    let claimable_balance = game_treasury
+        .to_account_info()
+        .lamports()
+        .saturating_sub(game.treasury_rent_reserve)
+        .saturating_sub(game.undistributed_charity_amount())
+        .saturating_sub(game.total_held_payout);
+    // rate limit, the rest needs more calls
+    let claim_amount = claimable_balance.min(game.max_treasury_withdrawal_per_tx);
+    if claim_amount != 0 {
         withdraw_from_treasury_to_player(
             game,
             game_treasury,
             &ctx.accounts.system_program,
-            &ctx.accounts.authority,
-            treasury_balance,
+            &recipient,
+            claim_amount,
         )?;
+        if let Some(escrow_address) = game.host_fee_escrow {
+            emit!(TreasuryClaimedToEscrow {
+                game: game.key(),
+                escrow_address,
+                amount: claim_amount,
+            });
+        }
+        game.total_claimed_by_authority = game
+            .total_claimed_by_authority
+            .checked_add(claim_amount)
+            .ok_or(GameError::Overflow)?;
+        let remaining_balance = game_treasury.to_account_info().lamports();
+        emit!(TreasuryWithdrawn {
+            game: game.key(),
+            authority,
+            amount: claim_amount,
+            remaining_balance,
+            total_claimed_by_authority: game.total_claimed_by_authority,
+        });
         msg!(
-            "Reveal deadline passed. Claimed implicit host liquidity {} lamports from treasury for authority {}. Remaining player pot obligation: {}.",
-            treasury_balance,
+            "Reveal deadline passed. Claimed implicit host liquidity {} of {} lamports from treasury for authority {}. Remaining player pot obligation: {}.",
+            claim_amount,
+            claimable_balance,
             authority,
             game.total_player_pot // Log remaining player funds obligation
         );
@@ -0,0 +1,39 @@
+use crate::current_clock;
+use crate::AuditEntry;
+use crate::AuditInstruction;
+use crate::ClawbackUnrevealedBet;
+use crate::GameError;
+use crate::UnrevealedBetClawedBack;
+use anchor_lang::prelude::*;
+
+pub fn clawback_unrevealed_bet(ctx: Context<ClawbackUnrevealedBet>, player: Pubkey) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let amount = ctx.accounts.bet_commitment.amount;
+
+    // stake stays in the treasury, leaving the player pot turns it into host liquidity
+    game.total_player_pot = game
+        .total_player_pot
+        .checked_sub(amount)
+        .ok_or(GameError::PlayerPotUnderflow)?;
+    game.record_audit(
+        ctx.accounts.audit_log.as_mut(),
+        AuditEntry::new(
+            AuditInstruction::Clawback,
+            ctx.accounts.authority.key(),
+            0,
+            current_clock()?.unix_timestamp,
+        ),
+    )?;
+
+    emit!(UnrevealedBetClawedBack {
+        player,
+        amount,
+        game: game.key(),
+    });
+    msg!(
+        "Clawed back unrevealed bet of {} lamports from player {}, commitment closed",
+        amount,
+        player
+    );
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::cpi_helpers::transfer_from_treasury;
+use crate::CloseEmptyTreasury;
+use anchor_lang::prelude::*;
+
+pub fn close_empty_treasury(ctx: Context<CloseEmptyTreasury>) -> Result<()> {
+    let game_treasury = &ctx.accounts.game_treasury;
+    let remaining_balance = game_treasury.to_account_info().lamports();
+    if remaining_balance == 0 {
+        msg!("Treasury is already empty, nothing to close.");
+        return Ok(());
+    }
+    // bypasses the rent reserve check in withdraw_from_treasury_to_player, this is the one place allowed to zero the treasury
+    transfer_from_treasury(
+        &ctx.accounts.game,
+        game_treasury,
+        &ctx.accounts.system_program,
+        &ctx.accounts.authority,
+        remaining_balance,
+    )?;
+    msg!(
+        "Returned treasury rent reserve {} lamports to authority {}. Treasury is now empty.",
+        remaining_balance,
+        ctx.accounts.authority.key()
+    );
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::current_clock;
+use crate::CloseGame;
+use crate::GameClosed;
+use crate::HistoricalEntry;
+use anchor_lang::prelude::*;
+
+pub fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+    let clock = current_clock()?;
+    let game = &ctx.accounts.game;
+    let closed_at = clock.unix_timestamp;
+
+    // keep the result readable after the game account is gone
+    ctx.accounts.results_feed.push(HistoricalEntry {
+        game_pubkey: game.key(),
+        result: game.result.unwrap_or_default(),
+        total_wagered: game.total_wagered,
+        total_paid_out: game.total_paid_out,
+        closed_at,
+    });
+
+    // saturating since the count stays with whoever created the game if the authority was transferred since
+    let authority_games = &mut ctx.accounts.authority_games;
+    authority_games.active_count = authority_games.active_count.saturating_sub(1);
+    emit!(GameClosed {
+        authority: game.authority,
+        active_count: authority_games.active_count,
+    });
+
+    let program_stats = &mut ctx.accounts.program_stats;
+    program_stats.last_game_closed_at = closed_at;
+    msg!(
+        "Game closed by authority: {}. Next game can start after {}s cooldown.",
+        ctx.accounts.authority.key(),
+        program_stats.min_cooldown_seconds
+    );
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::cpi_helpers::transfer_from_treasury;
+use crate::current_clock;
+use crate::CloseStaleGame;
+use crate::GameClosed;
+use crate::GameError;
+use crate::HistoricalEntry;
+use crate::StaleGameClosed;
+use crate::PROTOCOL_DEAD_LETTER_PUBKEY;
+use anchor_lang::prelude::*;
+use std::str::FromStr;
+
+pub fn close_stale_game(ctx: Context<CloseStaleGame>) -> Result<()> {
+    let clock = current_clock()?;
+    let dead_letter =
+        Pubkey::from_str(PROTOCOL_DEAD_LETTER_PUBKEY).map_err(|_| ProgramError::InvalidArgument)?;
+    require_keys_eq!(
+        ctx.accounts.dead_letter.key(),
+        dead_letter,
+        GameError::InvalidDeadLetterAccount
+    );
+    let game = &ctx.accounts.game;
+    let closed_at = clock.unix_timestamp;
+
+    // whatever is left in the treasury is the host's, but they abandoned it so it goes to the dead letter account.
+    // takes the rent reserve too since the treasury won't be used again
+    let swept_lamports = ctx.accounts.game_treasury.lamports();
+    if swept_lamports > 0 {
+        transfer_from_treasury(
+            game,
+            &ctx.accounts.game_treasury,
+            &ctx.accounts.system_program,
+            &ctx.accounts.dead_letter,
+            swept_lamports,
+        )?;
+    }
+
+    ctx.accounts.results_feed.push(HistoricalEntry {
+        game_pubkey: game.key(),
+        result: game.result.unwrap_or_default(),
+        total_wagered: game.total_wagered,
+        total_paid_out: game.total_paid_out,
+        closed_at,
+    });
+    let authority_games = &mut ctx.accounts.authority_games;
+    authority_games.active_count = authority_games.active_count.saturating_sub(1);
+    emit!(GameClosed {
+        authority: game.authority,
+        active_count: authority_games.active_count,
+    });
+    emit!(StaleGameClosed {
+        game: game.key(),
+        closed_by: ctx.accounts.caller.key(),
+        swept_lamports,
+        closed_at,
+    });
+    msg!(
+        "Stale game closed by {}. Swept {} lamports from the treasury to the dead letter account.",
+        ctx.accounts.caller.key(),
+        swept_lamports
+    );
+    Ok(())
+}
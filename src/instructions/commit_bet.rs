@@ -1,50 +1,509 @@
+use crate::current_clock;
+use crate::require_with_context;
+use crate::worst_case_remaining_payout_obligation;
+use crate::AuditEntry;
+use crate::AuditInstruction;
+use crate::BetCommitment;
+use crate::BetPhase;
 use crate::CommitBet;
+use crate::CommitmentAuditLog;
+use crate::CommitmentMarker;
+use crate::CommitmentReceipt;
+use crate::Game;
 use crate::GameError;
+use crate::GlobalJackpot;
+use crate::BPS_DENOMINATOR;
+use crate::PYTH_SOL_USD_PRICE_FEED;
+use crate::REFERRAL_CODE_LENGTH;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
-use anchor_lang::solana_program::program::invoke_signed;
-use anchor_lang::solana_program::system_instruction;
+use anchor_lang::system_program::{transfer, Transfer};
+use std::str::FromStr;
 
-pub fn commit_bet(ctx: Context<CommitBet>, commitment: [u8; 32], amount: u64) -> Result<()> {
-    // limit bet range to 0 to 1 sol
+// Pyth price account layout (v2): magic u32 at 0, account type u32 at 8, exponent i32 at 20,
+// then the aggregate price i64 at 208 with its status u32 at 224
+const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+const PYTH_EXPONENT_OFFSET: usize = 20;
+const PYTH_AGG_PRICE_OFFSET: usize = 208;
+const PYTH_AGG_STATUS_OFFSET: usize = 224;
+
+pub fn commit_bet(
+    ctx: Context<CommitBet>,
+    commitment: [u8; 32],
+    amount: u64,
+    player_note: Option<[u8; 64]>,
+    referral_code: Option<[u8; REFERRAL_CODE_LENGTH]>,
+) -> Result<()> {
+    let clock = current_clock()?;
+    require_betting_window(&ctx.accounts.game, clock.unix_timestamp)?;
+    let bet_commitment = &mut ctx.accounts.bet_commitment;
+    bet_commitment.player = *ctx.accounts.player.key;
+    bet_commitment.commitment = commitment;
+    bet_commitment.committed_at_slot = clock.slot;
+    // never written again, so the note can't be changed after the result is known
+    bet_commitment.player_note = player_note.unwrap_or([0; 64]);
+    fund_commitment(
+        &mut ctx.accounts.game,
+        bet_commitment,
+        &ctx.accounts.game_treasury,
+        &ctx.accounts.system_program,
+        &ctx.accounts.player,
+        amount,
+        clock.unix_timestamp,
+    )?;
+    collect_anti_sybil_stake(
+        &ctx.accounts.game,
+        bet_commitment,
+        ctx.accounts.anti_sybil_vault.as_ref(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.player,
+    )?;
+    contribute_to_global_jackpot(
+        &ctx.accounts.game,
+        amount,
+        ctx.accounts.global_jackpot.as_deref_mut(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.player,
+    )?;
+    // optional Pyth SOL/USD price feed is the first remaining account
+    bet_commitment.committed_amount_usd_cents =
+        stake_in_usd_cents(ctx.remaining_accounts.first(), amount)?;
+    if let Some(code) = referral_code {
+        // only ever a ReferralCode account at the code's PDA can hold the code, so matching it is enough
+        let referral = ctx
+            .accounts
+            .referral_code
+            .as_mut()
+            .filter(|referral| referral.code == code && referral.owner != bet_commitment.player)
+            .ok_or(GameError::InvalidReferralCodeAccount)?;
+        referral.uses = referral.uses.checked_add(1).ok_or(GameError::Overflow)?;
+        bet_commitment.referral_code = code;
+        msg!(
+            "Referred by {} with code {}",
+            referral.owner,
+            String::from_utf8_lossy(&code)
+        );
+    }
+    mark_unique_commitment(
+        &ctx.accounts.game,
+        ctx.accounts.commitment_marker.as_deref_mut(),
+        ctx.bumps.commitment_marker,
+        bet_commitment,
+    )?;
+    write_commitment_receipt(
+        &mut ctx.accounts.commitment_receipt,
+        bet_commitment,
+        ctx.bumps.commitment_receipt,
+        clock.unix_timestamp,
+    );
+    initialize_audit_log(
+        &ctx.accounts.game,
+        ctx.accounts.audit_log.as_mut(),
+        ctx.bumps.audit_log,
+        bet_commitment,
+        clock.unix_timestamp,
+    )
+}
+
+// display only USD value of amount lamports at the price feed's current price.
+// 0 without a feed or when the price isn't trading, the stake is still taken either way
+pub(crate) fn stake_in_usd_cents(price_feed: Option<&AccountInfo>, amount: u64) -> Result<u32> {
+    let Some(price_feed) = price_feed else {
+        return Ok(0);
+    };
+    let expected_feed =
+        Pubkey::from_str(PYTH_SOL_USD_PRICE_FEED).map_err(|_| ProgramError::InvalidArgument)?;
+    require_keys_eq!(price_feed.key(), expected_feed, GameError::InvalidPriceFeed);
+
+    let data = price_feed.try_borrow_data()?;
+    let read = |offset: usize| -> Result<[u8; 4]> {
+        data.get(offset..offset + 4)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(GameError::InvalidPriceFeed.into())
+    };
     require!(
+        u32::from_le_bytes(read(0)?) == PYTH_MAGIC
+            && u32::from_le_bytes(read(8)?) == PYTH_PRICE_ACCOUNT_TYPE,
+        GameError::InvalidPriceFeed
+    );
+    if u32::from_le_bytes(read(PYTH_AGG_STATUS_OFFSET)?) != PYTH_STATUS_TRADING {
+        msg!("SOL/USD price isn't trading, USD value left at 0");
+        return Ok(0);
+    }
+    let exponent = i32::from_le_bytes(read(PYTH_EXPONENT_OFFSET)?);
+    let price = data
+        .get(PYTH_AGG_PRICE_OFFSET..PYTH_AGG_PRICE_OFFSET + 8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(i64::from_le_bytes)
+        .ok_or(GameError::InvalidPriceFeed)?;
+    let Ok(price) = u128::try_from(price) else {
+        return Ok(0);
+    };
+
+    // cents = lamports / LAMPORTS_PER_SOL * price * 10^exponent * 100, saturating as it's only for display
+    let unscaled_cents = amount as u128 * price * 100 / LAMPORTS_PER_SOL as u128;
+    let cents = match 10u128.checked_pow(exponent.unsigned_abs()) {
+        Some(scale) if exponent < 0 => unscaled_cents / scale,
+        Some(scale) => unscaled_cents.saturating_mul(scale),
+        None if exponent < 0 => 0,
+        None => u128::MAX,
+    };
+    Ok(u32::try_from(cents).unwrap_or(u32::MAX))
+}
+
+// takes the game's anti sybil stake from payer into the vault on top of the bet, refunded when the bet
+// is revealed or reclaimed. for escrowed bets the escrow owner pays it and gets it back on reveal
+pub(crate) fn collect_anti_sybil_stake<'info>(
+    game: &Game,
+    bet_commitment: &mut BetCommitment,
+    anti_sybil_vault: Option<&SystemAccount<'info>>,
+    system_program: &Program<'info, System>,
+    payer: &Signer<'info>,
+) -> Result<()> {
+    if game.anti_sybil_stake == 0 {
+        return Ok(());
+    }
+    let anti_sybil_vault = anti_sybil_vault.ok_or(GameError::AntiSybilVaultRequired)?;
+    transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            Transfer {
+                from: payer.to_account_info(),
+                to: anti_sybil_vault.to_account_info(),
+            },
+        ),
+        game.anti_sybil_stake,
+    )?;
+    bet_commitment.anti_sybil_stake = game.anti_sybil_stake;
+    msg!(
+        "Anti sybil stake of {} lamports held until the bet is revealed",
+        game.anti_sybil_stake
+    );
+    Ok(())
+}
+
+// pays the game's jackpot cut of the bet from payer into the global jackpot, on top of the bet so the
+// game's own pot and payouts are untouched
+pub(crate) fn contribute_to_global_jackpot<'info>(
+    game: &Game,
+    amount: u64,
+    global_jackpot: Option<&mut Account<'info, GlobalJackpot>>,
+    system_program: &Program<'info, System>,
+    payer: &Signer<'info>,
+) -> Result<()> {
+    if game.progressive_jackpot_contribution_bps == 0 {
+        return Ok(());
+    }
+    let global_jackpot = global_jackpot.ok_or(GameError::GlobalJackpotRequired)?;
+    let contribution = (amount as u128 * game.progressive_jackpot_contribution_bps as u128
+        / BPS_DENOMINATOR as u128) as u64;
+    if contribution == 0 {
+        return Ok(());
+    }
+    transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            Transfer {
+                from: payer.to_account_info(),
+                to: global_jackpot.to_account_info(),
+            },
+        ),
+        contribution,
+    )?;
+    global_jackpot.pot = global_jackpot
+        .pot
+        .checked_add(contribution)
+        .ok_or(GameError::Overflow)?;
+    msg!(
+        "{} lamports into the global jackpot, now {}",
+        contribution,
+        global_jackpot.pot
+    );
+    Ok(())
+}
+
+// copies the funded bet into its receipt, call after fund_commitment so game and amount are set
+pub(crate) fn write_commitment_receipt(
+    receipt: &mut CommitmentReceipt,
+    bet_commitment: &BetCommitment,
+    bump: u8,
+    now: i64,
+) {
+    receipt.player = bet_commitment.player;
+    receipt.commitment = bet_commitment.commitment;
+    receipt.amount = bet_commitment.amount;
+    receipt.committed_at = now;
+    receipt.game = bet_commitment.game;
+    receipt.bump = bump;
+}
+
+// fills in the bet's commitment marker, it has to be passed if the game requires unique commitments.
+// anchor already refused the commit if another bet created the marker for this commitment
+pub(crate) fn mark_unique_commitment(
+    game: &Game,
+    commitment_marker: Option<&mut Account<CommitmentMarker>>,
+    bump: Option<u8>,
+    bet_commitment: &BetCommitment,
+) -> Result<()> {
+    let Some(commitment_marker) = commitment_marker else {
+        require!(
+            !game.unique_commitments,
+            GameError::CommitmentMarkerRequired
+        );
+        return Ok(());
+    };
+    commitment_marker.game = bet_commitment.game;
+    commitment_marker.player = bet_commitment.player;
+    commitment_marker.bump = bump.unwrap_or_default();
+    Ok(())
+}
+
+// starts the bet's audit log with the commit, the log has to be passed if the game keeps them
+pub(crate) fn initialize_audit_log(
+    game: &Game,
+    mut audit_log: Option<&mut Account<CommitmentAuditLog>>,
+    bump: Option<u8>,
+    bet_commitment: &Account<BetCommitment>,
+    now: i64,
+) -> Result<()> {
+    if let Some(audit_log) = audit_log.as_deref_mut() {
+        audit_log.bet_commitment = bet_commitment.key();
+        audit_log.bump = bump.unwrap_or_default();
+    }
+    game.record_audit(
+        audit_log,
+        AuditEntry::new(
+            AuditInstruction::Commit,
+            bet_commitment.player,
+            bet_commitment.amount as i64,
+            now,
+        ),
+    )
+}
+
+// bet count, commitment window and submission deadline checks shared by every commit instruction.
+// done here rather than as account constraints so a rejection logs the limit and the rejected value
+pub(crate) fn require_betting_window(game: &Game, now: i64) -> Result<()> {
+    if let Some(max_bet_count) = game.max_bet_count {
+        require_with_context!(
+            game.bet_count < max_bet_count,
+            GameError::MaxBetCountReached,
+            expected = format!("fewer than {} bets", max_bet_count),
+            got = game.bet_count,
+        );
+    }
+    if let Some(window_start) = game.commitment_window_start {
+        require_with_context!(
+            now >= window_start,
+            GameError::BettingNotYetOpen,
+            expected = format!("commit at or after {}", window_start),
+            got = now,
+        );
+    }
+    let submission_deadline = game.submission_deadline.ok_or(GameError::DeadlineNotSet)?;
+    require_with_context!(
+        now < submission_deadline,
+        GameError::SubmissionDeadlineNotReached,
+        expected = format!("commit before {}", submission_deadline),
+        got = now,
+    );
+    Ok(())
+}
+
+// takes the stake from payer into the treasury and books the bet on the game.
+// shared with commit_via_escrow, where the escrow owner pays but the bet belongs to the beneficiary
+pub(crate) fn fund_commitment<'info>(
+    game: &mut Account<'info, Game>,
+    bet_commitment: &mut BetCommitment,
+    game_treasury: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    payer: &Signer<'info>,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    // limit bet range to 0 to 1 sol
+    require_with_context!(
         0 < amount && amount <= LAMPORTS_PER_SOL,
-        GameError::InvalidBetAmount
+        GameError::InvalidBetAmount,
+        expected = format!("bet amount between 1 and {} lamports", LAMPORTS_PER_SOL),
+        got = amount,
     );
-    let game = &mut ctx.accounts.game;
-    let bet_commitment = &mut ctx.accounts.bet_commitment;
+
+    // cap how much of the treasury this game's players can have at stake
+    let treasury_balance = game_treasury.lamports();
+    let max_player_pot =
+        treasury_balance as u128 * game.max_treasury_exposure_bps as u128 / BPS_DENOMINATOR as u128;
+    let new_player_pot = game.total_player_pot as u128 + amount as u128;
+    require_with_context!(
+        new_player_pot <= max_player_pot,
+        GameError::ExceedsMaxExposure,
+        expected = format!("player pot at most {} lamports", max_player_pot),
+        got = new_player_pot,
+    );
+
     // --- Rest of the commit logic ---
-    invoke_signed(
-        &system_instruction::transfer(
-            ctx.accounts.player.key,
-            ctx.accounts.game_treasury.key,
-            amount,
+    transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            Transfer {
+                from: payer.to_account_info(),
+                to: game_treasury.to_account_info(),
+            },
         ),
-        &[
-            ctx.accounts.player.to_account_info(),
-            ctx.accounts.game_treasury.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-        ],
-        &[],
+        amount,
     )?;
 
-    bet_commitment.player = *ctx.accounts.player.key;
-    bet_commitment.commitment = commitment;
-    bet_commitment.game = *game.to_account_info().key;
+    bet_commitment.game = game.key();
+    bet_commitment.original_player = bet_commitment.player;
     bet_commitment.amount = amount;
     bet_commitment.is_claimed = false;
     bet_commitment.attempted_reveal = false;
+    bet_commitment.held_payout = 0;
+    bet_commitment.is_frozen = false;
+    bet_commitment.pending_payout_amount = 0;
+    bet_commitment.is_early_bird = now < game.early_bird_cutoff;
+    bet_commitment.commitment_version = game.commitment_version;
+    bet_commitment.revealed_bet_value = None;
+    bet_commitment.payout_amount = 0;
+    bet_commitment.bet_index = game.bet_count;
+    bet_commitment.proof_of_bet_mint = None;
+    bet_commitment.anti_sybil_stake = 0;
+    bet_commitment.vesting_payout = 0;
+
+    if amount > game.highest_bet_amount {
+        game.highest_bet_amount = amount;
+        game.highest_bet_player = Some(bet_commitment.player);
+    }
 
     game.bet_count = game.bet_count.checked_add(1).ok_or(GameError::Overflow)?;
     game.total_player_pot = game
         .total_player_pot
         .checked_add(amount)
+        .ok_or(GameError::PlayerPotOverflow)?;
+    game.total_wagered = game
+        .total_wagered
+        .checked_add(amount)
         .ok_or(GameError::Overflow)?;
 
+    // with this bet in the pot, the host's own liquidity still has to cover the worst case
+    let host_liquidity = game_treasury
+        .lamports()
+        .saturating_sub(game.total_player_pot)
+        .saturating_sub(game.treasury_rent_reserve);
+    let required_liquidity = worst_case_remaining_payout_obligation(game) as u128
+        * game.minimum_host_liquidity_ratio as u128
+        / BPS_DENOMINATOR as u128;
+    require_with_context!(
+        host_liquidity as u128 >= required_liquidity,
+        GameError::InsufficientHostLiquidityForNewBet,
+        expected = format!("host liquidity at least {} lamports", required_liquidity),
+        got = host_liquidity,
+    );
+
     msg!(
         "Bet committed by player: {} for amount: {}",
         bet_commitment.player,
         amount,
     );
+    if game.max_bet_count == Some(game.bet_count) {
+        game.set_phase(BetPhase::Closed);
+        msg!(
+            "Game reached its maximum of {} bets, betting closed",
+            game.bet_count
+        );
+    }
+
+    // the commit still goes through, this is just for UIs to warn about
+    let remaining_seconds = game
+        .submission_deadline
+        .unwrap_or_default()
+        .saturating_sub(now);
+    if remaining_seconds < game.submission_deadline_grace_alert_seconds {
+        msg!(
+            "WarningDeadlineImminent: only {} seconds left before the submission deadline",
+            remaining_seconds
+        );
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{
+        default_test_game, empty_bet_commitment, test_game, test_signer, test_system_program,
+        test_treasury,
+    };
+
+    // funds a bet from a fresh payer into a fresh treasury, returning the bet and both ending balances
+    fn fund(
+        game: &mut Account<'static, Game>,
+        treasury_lamports: u64,
+        amount: u64,
+    ) -> Result<(BetCommitment, u64, u64)> {
+        let treasury = test_treasury(&game.key(), treasury_lamports);
+        let payer = test_signer(10 * LAMPORTS_PER_SOL);
+        let mut bet_commitment = empty_bet_commitment();
+        fund_commitment(
+            game,
+            &mut bet_commitment,
+            &treasury,
+            &test_system_program(),
+            &payer,
+            amount,
+            0,
+        )?;
+        Ok((bet_commitment, payer.lamports(), treasury.lamports()))
+    }
+
+    #[test]
+    fn fund_commitment_moves_and_books_the_full_amount() {
+        let mut game = test_game(default_test_game());
+        let (bet_commitment, payer_lamports, treasury_lamports) =
+            fund(&mut game, 100 * LAMPORTS_PER_SOL, LAMPORTS_PER_SOL).unwrap();
+        assert_eq!(payer_lamports, 9 * LAMPORTS_PER_SOL);
+        assert_eq!(treasury_lamports, 101 * LAMPORTS_PER_SOL);
+        assert_eq!(bet_commitment.amount, LAMPORTS_PER_SOL);
+        assert_eq!(bet_commitment.game, game.key());
+        assert_eq!(bet_commitment.bet_index, 0);
+        assert_eq!(game.bet_count, 1);
+        assert_eq!(game.total_player_pot, LAMPORTS_PER_SOL);
+        assert_eq!(game.total_wagered, LAMPORTS_PER_SOL);
+
+        let (bet_commitment, payer_lamports, treasury_lamports) =
+            fund(&mut game, 100 * LAMPORTS_PER_SOL, 1).unwrap();
+        assert_eq!(payer_lamports, 10 * LAMPORTS_PER_SOL - 1);
+        assert_eq!(treasury_lamports, 100 * LAMPORTS_PER_SOL + 1);
+        assert_eq!(bet_commitment.bet_index, 1);
+        assert_eq!(game.total_player_pot, LAMPORTS_PER_SOL + 1);
+    }
+
+    #[test]
+    fn fund_commitment_rejects_amounts_outside_zero_to_one_sol() {
+        let mut game = test_game(default_test_game());
+        for amount in [0, LAMPORTS_PER_SOL + 1] {
+            assert_eq!(
+                fund(&mut game, 100 * LAMPORTS_PER_SOL, amount).err(),
+                Some(GameError::InvalidBetAmount.into())
+            );
+        }
+        assert_eq!(game.bet_count, 0);
+        assert_eq!(game.total_player_pot, 0);
+    }
+
+    #[test]
+    fn fund_commitment_caps_treasury_exposure() {
+        let mut game = default_test_game();
+        game.max_treasury_exposure_bps = 1_000;
+        let mut game = test_game(game);
+        // 10% of 10 SOL, one full bet fits and one more lamport doesn't
+        fund(&mut game, 10 * LAMPORTS_PER_SOL, LAMPORTS_PER_SOL).unwrap();
+        assert_eq!(
+            fund(&mut game, 10 * LAMPORTS_PER_SOL, 1).err(),
+            Some(GameError::ExceedsMaxExposure.into())
+        );
+        assert_eq!(game.total_player_pot, LAMPORTS_PER_SOL);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::current_clock;
+use crate::instructions::{
+    collect_anti_sybil_stake, contribute_to_global_jackpot, fund_commitment, initialize_audit_log,
+    mark_unique_commitment, require_betting_window, stake_in_usd_cents, write_commitment_receipt,
+};
+use crate::CommitBetGated;
+use anchor_lang::prelude::*;
+
+pub fn commit_bet_with_token_account_proof(
+    ctx: Context<CommitBetGated>,
+    commitment: [u8; 32],
+    amount: u64,
+    player_note: Option<[u8; 64]>,
+) -> Result<()> {
+    let clock = current_clock()?;
+    require_betting_window(&ctx.accounts.game, clock.unix_timestamp)?;
+    msg!(
+        "Player {} holds gate NFT in token account {}",
+        ctx.accounts.player.key,
+        ctx.accounts.nft_token_account.key()
+    );
+    let bet_commitment = &mut ctx.accounts.bet_commitment;
+    bet_commitment.player = *ctx.accounts.player.key;
+    bet_commitment.commitment = commitment;
+    bet_commitment.committed_at_slot = clock.slot;
+    bet_commitment.player_note = player_note.unwrap_or([0; 64]);
+    fund_commitment(
+        &mut ctx.accounts.game,
+        bet_commitment,
+        &ctx.accounts.game_treasury,
+        &ctx.accounts.system_program,
+        &ctx.accounts.player,
+        amount,
+        clock.unix_timestamp,
+    )?;
+    collect_anti_sybil_stake(
+        &ctx.accounts.game,
+        bet_commitment,
+        ctx.accounts.anti_sybil_vault.as_ref(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.player,
+    )?;
+    contribute_to_global_jackpot(
+        &ctx.accounts.game,
+        amount,
+        ctx.accounts.global_jackpot.as_deref_mut(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.player,
+    )?;
+    bet_commitment.committed_amount_usd_cents =
+        stake_in_usd_cents(ctx.remaining_accounts.first(), amount)?;
+    mark_unique_commitment(
+        &ctx.accounts.game,
+        ctx.accounts.commitment_marker.as_deref_mut(),
+        ctx.bumps.commitment_marker,
+        bet_commitment,
+    )?;
+    write_commitment_receipt(
+        &mut ctx.accounts.commitment_receipt,
+        bet_commitment,
+        ctx.bumps.commitment_receipt,
+        clock.unix_timestamp,
+    );
+    initialize_audit_log(
+        &ctx.accounts.game,
+        ctx.accounts.audit_log.as_mut(),
+        ctx.bumps.audit_log,
+        bet_commitment,
+        clock.unix_timestamp,
+    )
+}
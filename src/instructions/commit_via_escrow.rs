@@ -0,0 +1,65 @@
+use crate::current_clock;
+use crate::instructions::{
+    collect_anti_sybil_stake, contribute_to_global_jackpot, fund_commitment,
+    mark_unique_commitment, require_betting_window, stake_in_usd_cents,
+};
+use crate::CommitViaEscrow;
+use anchor_lang::prelude::*;
+
+pub fn commit_via_escrow(
+    ctx: Context<CommitViaEscrow>,
+    escrow_id: u64,
+    beneficiary: Pubkey,
+    commitment: [u8; 32],
+    amount: u64,
+) -> Result<()> {
+    let clock = current_clock()?;
+    require_betting_window(&ctx.accounts.game, clock.unix_timestamp)?;
+    let bet_escrow = &mut ctx.accounts.bet_escrow;
+    bet_escrow.owner = ctx.accounts.owner.key();
+    bet_escrow.beneficiary = beneficiary;
+    bet_escrow.escrow_id = escrow_id;
+    bet_escrow.bump = ctx.bumps.bet_escrow;
+    bet_escrow.bet.player = beneficiary;
+    bet_escrow.bet.commitment = commitment;
+    bet_escrow.bet.committed_at_slot = clock.slot;
+    fund_commitment(
+        &mut ctx.accounts.game,
+        &mut bet_escrow.bet,
+        &ctx.accounts.game_treasury,
+        &ctx.accounts.system_program,
+        &ctx.accounts.owner,
+        amount,
+        clock.unix_timestamp,
+    )?;
+    // the owner puts up the stake and gets it back when they reveal
+    collect_anti_sybil_stake(
+        &ctx.accounts.game,
+        &mut bet_escrow.bet,
+        ctx.accounts.anti_sybil_vault.as_ref(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.owner,
+    )?;
+    contribute_to_global_jackpot(
+        &ctx.accounts.game,
+        amount,
+        ctx.accounts.global_jackpot.as_deref_mut(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.owner,
+    )?;
+    bet_escrow.bet.committed_amount_usd_cents =
+        stake_in_usd_cents(ctx.remaining_accounts.first(), amount)?;
+    mark_unique_commitment(
+        &ctx.accounts.game,
+        ctx.accounts.commitment_marker.as_deref_mut(),
+        ctx.bumps.commitment_marker,
+        &bet_escrow.bet,
+    )?;
+    msg!(
+        "Escrow {} owned by {} committed a bet for beneficiary {}",
+        escrow_id,
+        bet_escrow.owner,
+        beneficiary
+    );
+    Ok(())
+}
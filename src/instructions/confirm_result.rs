@@ -0,0 +1,24 @@
+use crate::instructions::record_result;
+use crate::require_with_context;
+use crate::ConfirmResult;
+use crate::GameError;
+use anchor_lang::prelude::*;
+
+pub fn confirm_result(ctx: Context<ConfirmResult>, result: u8) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let pending_result = game.pending_result.ok_or(GameError::NoPendingResult)?;
+    require_with_context!(
+        result == pending_result,
+        GameError::PendingResultMismatch,
+        expected = pending_result,
+        got = result,
+    );
+    game.pending_result = None;
+    let result_confidence = game.pending_result_confidence;
+    msg!(
+        "Result {} confirmed by result oracle {}",
+        result,
+        ctx.accounts.result_oracle.key()
+    );
+    record_result(game, result, result_confidence)
+}
@@ -0,0 +1,38 @@
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::CharityDistributed;
+use crate::DistributeToCharity;
+use crate::GameError;
+use anchor_lang::prelude::*;
+
+pub fn distribute_to_charity(ctx: Context<DistributeToCharity>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let amount = game.undistributed_charity_amount();
+    if amount == 0 {
+        msg!("Nothing owed to charity yet.");
+        return Ok(());
+    }
+    withdraw_from_treasury_to_player(
+        game,
+        &ctx.accounts.game_treasury,
+        &ctx.accounts.system_program,
+        &ctx.accounts.charity_wallet,
+        amount,
+    )?;
+    game.charity_distributed_amount = game
+        .charity_distributed_amount
+        .checked_add(amount)
+        .ok_or(GameError::Overflow)?;
+
+    emit!(CharityDistributed {
+        game: game.key(),
+        charity_wallet: ctx.accounts.charity_wallet.key(),
+        amount,
+        total_charity_amount: game.total_charity_amount,
+    });
+    msg!(
+        "Distributed {} lamports of losing stakes to charity {}",
+        amount,
+        ctx.accounts.charity_wallet.key()
+    );
+    Ok(())
+}
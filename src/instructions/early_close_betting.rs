@@ -0,0 +1,19 @@
+use crate::current_clock;
+use crate::BetPhase;
+use crate::EarlyCloseBetting;
+use anchor_lang::prelude::*;
+
+// pulling the submission deadline in to now means the result window is measured from the close
+pub fn early_close_betting(ctx: Context<EarlyCloseBetting>) -> Result<()> {
+    let clock = current_clock()?;
+    let game = &mut ctx.accounts.game;
+    game.set_phase(BetPhase::Closed);
+    game.submission_deadline = Some(clock.unix_timestamp);
+    msg!(
+        "Betting closed early by {} with {} lamports from {} bets",
+        ctx.accounts.authority.key,
+        game.total_player_pot,
+        game.bet_count
+    );
+    Ok(())
+}
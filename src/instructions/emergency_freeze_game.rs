@@ -0,0 +1,19 @@
+use crate::EmergencyFreezeGame;
+use crate::GameFrozen;
+use anchor_lang::prelude::*;
+
+pub fn emergency_freeze_game(ctx: Context<EmergencyFreezeGame>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    game.emergency_frozen = true;
+    let program_admin = ctx.accounts.program_admin.key();
+    emit!(GameFrozen {
+        game: game.key(),
+        program_admin,
+    });
+    msg!(
+        "Game {} emergency frozen by program admin {}",
+        game.key(),
+        program_admin
+    );
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use crate::EmergencyUnfreezeGame;
+use crate::GameUnfrozen;
+use anchor_lang::prelude::*;
+
+pub fn emergency_unfreeze_game(ctx: Context<EmergencyUnfreezeGame>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    game.emergency_frozen = false;
+    let program_admin = ctx.accounts.program_admin.key();
+    let authority = ctx.accounts.authority.key();
+    emit!(GameUnfrozen {
+        game: game.key(),
+        program_admin,
+        authority,
+    });
+    msg!(
+        "Game {} unfrozen by program admin {} and authority {}",
+        game.key(),
+        program_admin,
+        authority
+    );
+    Ok(())
+}
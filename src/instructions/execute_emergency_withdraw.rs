@@ -0,0 +1,51 @@
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::current_clock;
+use crate::require_with_context;
+use crate::ExecuteEmergencyWithdraw;
+use crate::GameError;
+use anchor_lang::prelude::*;
+
+pub fn execute_emergency_withdraw(ctx: Context<ExecuteEmergencyWithdraw>) -> Result<()> {
+    let clock = current_clock()?;
+    let game = &mut ctx.accounts.game;
+    let amount = game
+        .pending_withdraw_amount
+        .ok_or(GameError::NoPendingWithdraw)?;
+    require_with_context!(
+        clock.unix_timestamp >= game.pending_withdraw_unlocks_at,
+        GameError::WithdrawTimelockActive,
+        expected = format!("time at least {}", game.pending_withdraw_unlocks_at),
+        got = clock.unix_timestamp,
+    );
+
+    // only host liquidity, players' stakes, charity's share and the rent reserve stay put whatever was proposed
+    let host_liquidity = ctx
+        .accounts
+        .game_treasury
+        .lamports()
+        .saturating_sub(game.treasury_rent_reserve)
+        .saturating_sub(game.total_player_pot)
+        .saturating_sub(game.undistributed_charity_amount());
+    let withdraw_amount = amount.min(host_liquidity);
+    game.pending_withdraw_amount = None;
+    if withdraw_amount > 0 {
+        withdraw_from_treasury_to_player(
+            game,
+            &ctx.accounts.game_treasury,
+            &ctx.accounts.system_program,
+            &ctx.accounts.authority,
+            withdraw_amount,
+        )?;
+        game.total_claimed_by_authority = game
+            .total_claimed_by_authority
+            .checked_add(withdraw_amount)
+            .ok_or(GameError::Overflow)?;
+    }
+    msg!(
+        "Emergency withdrawal executed, {} of {} proposed lamports sent to authority {}",
+        withdraw_amount,
+        amount,
+        ctx.accounts.authority.key
+    );
+    Ok(())
+}
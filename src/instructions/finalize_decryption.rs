@@ -0,0 +1,35 @@
+use crate::decrypt_hidden_result;
+use crate::instructions::record_or_propose_result;
+use crate::FinalizeDecryption;
+use crate::GameError;
+use crate::HIDDEN_RESULT_THRESHOLD;
+use anchor_lang::prelude::*;
+
+pub fn finalize_decryption(ctx: Context<FinalizeDecryption>) -> Result<()> {
+    let result_decryption = &ctx.accounts.result_decryption;
+    let ciphertext = result_decryption
+        .encrypted_result
+        .ok_or(GameError::EncryptedResultNotSubmitted)?;
+    // keyholder i interpolates at i + 1. shares are proven at submission and the key shares lie on one
+    // polynomial, so any HIDDEN_RESULT_THRESHOLD of them decrypt to the same result
+    let shares: Vec<(i64, [u8; 32])> = result_decryption
+        .decryption_shares
+        .iter()
+        .zip(result_decryption.share_submitted)
+        .enumerate()
+        .filter(|(_, (_, submitted))| *submitted)
+        .map(|(index, (share, _))| (index as i64 + 1, *share))
+        .take(HIDDEN_RESULT_THRESHOLD)
+        .collect();
+    require!(
+        shares.len() == HIDDEN_RESULT_THRESHOLD,
+        GameError::NotEnoughDecryptionShares
+    );
+    let result = decrypt_hidden_result(&ciphertext, &shares).ok_or(GameError::DecryptionFailed)?;
+    msg!(
+        "Hidden result decrypted from keyholders {:?}",
+        shares.iter().map(|(number, _)| number).collect::<Vec<_>>()
+    );
+    // decrypted from the host's own submission, so it goes through the oracle like a plain result would
+    record_or_propose_result(&mut ctx.accounts.game, result, 100)
+}
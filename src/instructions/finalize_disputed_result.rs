@@ -0,0 +1,13 @@
+use crate::FinalizeDisputedResult;
+use anchor_lang::prelude::*;
+
+pub fn finalize_disputed_result(ctx: Context<FinalizeDisputedResult>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    game.is_disputed = false;
+    msg!(
+        "Disputed result {:?} finalized by authority: {}. Held payouts can now be claimed.",
+        game.result,
+        game.authority
+    );
+    Ok(())
+}
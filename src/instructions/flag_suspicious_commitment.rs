@@ -0,0 +1,46 @@
+use crate::current_clock;
+use crate::AuditEntry;
+use crate::AuditInstruction;
+use crate::FlagSuspiciousCommitment;
+use crate::SuspiciousCommitmentFlagged;
+use anchor_lang::prelude::*;
+
+pub fn flag_suspicious_commitment(
+    ctx: Context<FlagSuspiciousCommitment>,
+    player: Pubkey,
+) -> Result<()> {
+    let clock = current_clock()?;
+    let bet_commitment = &mut ctx.accounts.bet_commitment;
+    bet_commitment.is_frozen = true;
+
+    let flagged_at = clock.unix_timestamp;
+    let betting_anomaly = &mut ctx.accounts.betting_anomaly;
+    betting_anomaly.game = ctx.accounts.game.key();
+    betting_anomaly.player = player;
+    betting_anomaly.committed_at_slot = bet_commitment.committed_at_slot;
+    betting_anomaly.flagged_at = flagged_at;
+    betting_anomaly.bump = ctx.bumps.betting_anomaly;
+
+    ctx.accounts.game.record_audit(
+        ctx.accounts.audit_log.as_mut(),
+        AuditEntry::new(
+            AuditInstruction::Freeze,
+            ctx.accounts.authority.key(),
+            0,
+            flagged_at,
+        ),
+    )?;
+
+    emit!(SuspiciousCommitmentFlagged {
+        game: betting_anomaly.game,
+        player,
+        committed_at_slot: bet_commitment.committed_at_slot,
+        flagged_at,
+    });
+    msg!(
+        "Commitment for player {} (committed at slot {}) flagged as suspicious and frozen.",
+        player,
+        bet_commitment.committed_at_slot
+    );
+    Ok(())
+}
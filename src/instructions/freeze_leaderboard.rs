@@ -0,0 +1,17 @@
+use crate::current_clock;
+use crate::FreezeLeaderboard;
+use anchor_lang::prelude::*;
+
+pub fn freeze_leaderboard(ctx: Context<FreezeLeaderboard>) -> Result<()> {
+    let clock = current_clock()?;
+    let leaderboard = &mut ctx.accounts.leaderboard;
+    leaderboard.is_frozen = true;
+    leaderboard.snapshot_taken_at = clock.unix_timestamp;
+    leaderboard.snapshot_taken_by = ctx.accounts.authority.key();
+    msg!(
+        "Leaderboard frozen by {} at {}. This snapshot is the final record.",
+        leaderboard.snapshot_taken_by,
+        leaderboard.snapshot_taken_at
+    );
+    Ok(())
+}
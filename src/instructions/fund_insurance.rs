@@ -0,0 +1,28 @@
+use crate::FundInsurance;
+use crate::GameError;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+pub fn fund_insurance(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.program_admin.to_account_info(),
+                to: ctx.accounts.insurance_fund.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    let insurance_fund = &mut ctx.accounts.insurance_fund;
+    insurance_fund.total_funded = insurance_fund
+        .total_funded
+        .checked_add(amount)
+        .ok_or(GameError::Overflow)?;
+    msg!(
+        "Insurance fund topped up by {} lamports, {} funded in total",
+        amount,
+        insurance_fund.total_funded
+    );
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use crate::InitializeAuthorityGames;
+use anchor_lang::prelude::*;
+
+pub fn initialize_authority_games(
+    ctx: Context<InitializeAuthorityGames>,
+    authority: Pubkey,
+) -> Result<()> {
+    let authority_games = &mut ctx.accounts.authority_games;
+    authority_games.authority = authority;
+    authority_games.active_count = 0;
+    authority_games.bump = ctx.bumps.authority_games;
+    msg!(
+        "Active game counter initialized for authority: {}",
+        authority
+    );
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use crate::InitializeBetDistribution;
+use anchor_lang::prelude::*;
+
+pub fn initialize_bet_distribution(ctx: Context<InitializeBetDistribution>) -> Result<()> {
+    let bet_distribution = &mut ctx.accounts.bet_distribution;
+    bet_distribution.game = ctx.accounts.game.key();
+    bet_distribution.revealed_bet_distribution = [0; 101];
+    bet_distribution.bump = ctx.bumps.bet_distribution;
+    msg!(
+        "Bet distribution initialized for game: {}",
+        bet_distribution.game
+    );
+    Ok(())
+}
@@ -1,29 +1,303 @@
-use crate::{InitializeGame, GAME_AUTHORITY_PUBKEY, SUBMISSION_DEADLINE_TIMESTAMP};
+use crate::require_with_context;
+use crate::{
+    current_clock, unlock_schedule_total_bps, BetPhase, Game, GameCreated, GameError,
+    InitializeGame, InitializeGameBumps, ANTI_SYBIL_STAKE_LAMPORTS, AUDIT_LOG_ENABLED,
+    AUTO_EXTENSION_ENABLED, BIGGEST_LOSER_PRIZE_LAMPORTS, BPS_DENOMINATOR, BURN_ADDRESS,
+    BURN_LOSING_BETS, COMMITMENT_VERSION, COOLDOWN_BETWEEN_REVEALS_SLOTS,
+    DEFAULT_DISPUTE_THRESHOLD, DISPUTE_GRACE_PERIOD_SECONDS, EARLY_BIRD_BONUS_BPS,
+    EARLY_CLOSE_ENABLED, EARLY_CLOSE_MIN_POT, FINAL_CLAIM_DEADLINE_TIMESTAMP,
+    GAME_AUTHORITY_PUBKEY, GAME_VERSION, GRACE_PERIOD_SECONDS, HIGHEST_BET_BONUS_LAMPORTS,
+    LATE_REVEAL_PENALTY_BPS, LATE_REVEAL_THRESHOLD_PCT, MAX_BET_COUNT, MAX_TREASURY_EXPOSURE_BPS,
+    MAX_TREASURY_WITHDRAWAL_PER_TX, MINIMUM_HOST_LIQUIDITY_RATIO, MINT_WINNER_NFT,
+    MIN_PAYOUT_THRESHOLD_LAMPORTS, MIN_REVEAL_PCT_FOR_NO_EXTENSION, PAYOUT_CURVE_TYPE,
+    PAYOUT_UNLOCK_SCHEDULE, PROGRESSIVE_JACKPOT_CONTRIBUTION_BPS, RESULT_UNCERTAINTY_RANGE,
+    RESULT_VERIFICATION_DELAY_SECONDS, REVEAL_DEADLINE_TIMESTAMP, REVEAL_EXTENSION_SECONDS,
+    REVEAL_OPENS_IMMEDIATELY, REWARD_HIGHEST_BET, SUBMISSION_DEADLINE_GRACE_ALERT_SECONDS,
+    SUBMISSION_DEADLINE_TIMESTAMP, TREASURY_ALERT_THRESHOLD, TREASURY_CRITICAL_THRESHOLD,
+    TREASURY_MIN_UTILIZATION_BPS, UNIQUE_COMMITMENTS,
+};
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 use std::str::FromStr;
 
-pub fn initialize_game(ctx: Context<InitializeGame>) -> Result<()> {
+pub fn initialize_game(
+    ctx: Context<InitializeGame>,
+    is_test_mode: bool,
+    nft_gate_mint: Option<Pubkey>,
+    result_oracle: Option<Pubkey>,
+    commitment_window_start: Option<i64>,
+    host_fee_escrow: Option<Pubkey>,
+) -> Result<()> {
+    init_game(
+        ctx.accounts,
+        &ctx.bumps,
+        SUBMISSION_DEADLINE_TIMESTAMP,
+        REVEAL_DEADLINE_TIMESTAMP,
+        FINAL_CLAIM_DEADLINE_TIMESTAMP,
+        is_test_mode,
+        nft_gate_mint,
+        result_oracle,
+        commitment_window_start,
+        host_fee_escrow,
+    )
+}
+
+// shared with initialize_test_game, which picks its own deadlines
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn init_game(
+    accounts: &mut InitializeGame,
+    bumps: &InitializeGameBumps,
+    submission_deadline: i64,
+    reveal_deadline: i64,
+    final_claim_deadline: i64,
+    is_test_mode: bool,
+    nft_gate_mint: Option<Pubkey>,
+    result_oracle: Option<Pubkey>,
+    commitment_window_start: Option<i64>,
+    host_fee_escrow: Option<Pubkey>,
+) -> Result<()> {
+    let clock = current_clock()?;
+    // test mode lets the authority skip the submission deadline, never allow it for real money
+    if cfg!(feature = "mainnet") {
+        require!(!is_test_mode, GameError::TestModeUnavailable);
+    }
     msg!("Initializing game...");
-    let game = &mut ctx.accounts.game;
+    let game = &mut accounts.game;
+    game.game_version = GAME_VERSION;
     game.authority =
         Pubkey::from_str(GAME_AUTHORITY_PUBKEY).map_err(|_| ProgramError::InvalidArgument)?;
     game.result = None;
-    game.is_open_for_bets = true;
-    game.is_open_for_reveals = false;
+    game.set_phase(BetPhase::Open);
     game.bet_count = 0;
     game.total_player_pot = 0;
-    game.bump = ctx.bumps.game;
-    game.treasury_bump = ctx.bumps.game_treasury;
+    game.total_wagered = 0;
+    game.total_paid_out = 0;
+    game.sum_of_revealed_bet_values = 0;
+    game.revealed_bet_count = 0;
+    game.revealed_count = 0;
+    game.reveal_opens_at = 0;
+    game.winning_reveal_count = 0;
+    game.bump = bumps.game;
+    game.treasury_bump = bumps.game_treasury;
 
-    // Set hardcoded submission deadline
-    game.submission_deadline = Some(SUBMISSION_DEADLINE_TIMESTAMP);
+    game.submission_deadline = Some(submission_deadline);
     game.reveal_deadline = None; // Reveal deadline set when result is submitted
     game.final_claim_deadline = None;
+    game.scheduled_reveal_deadline = reveal_deadline;
+    game.scheduled_final_claim_deadline = final_claim_deadline;
+
+    game.result_confidence = 0;
+    game.result_lower = 0;
+    game.result_upper = 0;
+    game.is_disputed = false;
+    game.reveal_deadline_extended = false;
+    game.final_claim_deadline_extension_count = 0;
+    game.treasury_multisig = None;
+    game.co_authority = None;
+    game.pending_withdraw_amount = None;
+    game.pending_withdraw_unlocks_at = 0;
+    game.pending_authority = None;
+    game.pending_authority_proposed_at = 0;
+    game.total_claimed_by_authority = 0;
+    game.total_charity_amount = 0;
+    game.charity_distributed_amount = 0;
+    game.bet_merkle_root = None;
+    game.is_test_mode = is_test_mode;
+    game.nft_gate_mint = nft_gate_mint;
+    game.highest_bet_player = None;
+    game.highest_bet_amount = 0;
+    game.biggest_loss_gap = 0;
+    game.biggest_loser = None;
+    game.biggest_loser_prize_claimed = false;
+    game.host_result_signature = None;
+    // the whole point is a second party, so the authority can't confirm its own results
+    require!(
+        result_oracle != Some(game.authority),
+        GameError::InvalidResultOracle
+    );
+    game.result_oracle = result_oracle;
+    game.pending_result = None;
+    game.pending_result_confidence = 0;
+    game.bet_count_at_submission = 0;
+    game.total_player_pot_at_submission = 0;
+    game.commitment_window_start = commitment_window_start;
+    game.host_fee_escrow = host_fee_escrow;
+    game.emergency_frozen = false;
+    game.total_held_payout = 0;
+    // early birds are counted from when betting actually opens
+    let betting_opens = commitment_window_start.map_or(clock.unix_timestamp, |window_start| {
+        window_start.max(clock.unix_timestamp)
+    });
+    let betting_window = submission_deadline.saturating_sub(betting_opens).max(0);
+    game.early_bird_cutoff = submission_deadline - betting_window * 3 / 4;
+    apply_default_game_config(game);
+
+    let authority_games = &mut accounts.authority_games;
+    require_keys_eq!(
+        authority_games.authority,
+        game.authority,
+        GameError::InvalidAuthority
+    );
+    let max_games_per_authority = accounts.program_stats.max_games_per_authority;
+    require_with_context!(
+        authority_games.active_count < max_games_per_authority as u32,
+        GameError::TooManyActiveGames,
+        expected = format!("fewer than {} active games", max_games_per_authority),
+        got = authority_games.active_count,
+    );
+    authority_games.active_count += 1;
+    emit!(GameCreated {
+        authority: game.authority,
+        active_count: authority_games.active_count,
+    });
+
+    // fund the treasury up to rent exemption so it can't be garbage collected once payouts start draining it
+    game.treasury_rent_reserve = Rent::get()?.minimum_balance(0);
+    transfer(
+        CpiContext::new(
+            accounts.system_program.to_account_info(),
+            Transfer {
+                from: accounts.payer.to_account_info(),
+                to: accounts.game_treasury.to_account_info(),
+            },
+        ),
+        game.treasury_rent_reserve,
+    )?;
+
+    validate_game_constraints(game)?;
 
     msg!(
-        "Game initialized with hardcoded authority: {}. Hardcoded Submission deadline: {}",
+        "Game initialized with hardcoded authority: {}. Submission deadline: {}. Treasury rent reserve: {}. Test mode: {}",
         game.authority,
-        SUBMISSION_DEADLINE_TIMESTAMP
+        submission_deadline,
+        game.treasury_rent_reserve,
+        is_test_mode
     );
     Ok(())
 }
+
+// invariants across fields that are each fine on their own but have to agree with each other,
+// checked once everything is set so a malformed game is never created
+pub fn validate_game_constraints(game: &Game) -> Result<()> {
+    let check = |holds: bool, constraint: &str| -> Result<()> {
+        if !holds {
+            msg!("Invalid game configuration: {}", constraint);
+            return err!(GameError::InvalidGameConfiguration);
+        }
+        Ok(())
+    };
+    let bps = |value: u16| value as u64 <= BPS_DENOMINATOR;
+
+    let Some(submission_deadline) = game.submission_deadline else {
+        return check(false, "submission_deadline is set");
+    };
+    check(
+        game.commitment_window_start
+            .is_none_or(|window_start| window_start < submission_deadline),
+        "commitment_window_start < submission_deadline",
+    )?;
+    check(
+        submission_deadline < game.scheduled_reveal_deadline,
+        "submission_deadline < scheduled_reveal_deadline",
+    )?;
+    // a result on time still has to leave some of the reveal window after the verification delay
+    check(
+        submission_deadline.saturating_add(game.result_verification_delay_seconds as i64)
+            < game.scheduled_reveal_deadline,
+        "submission_deadline + result_verification_delay_seconds < scheduled_reveal_deadline",
+    )?;
+    check(
+        game.scheduled_reveal_deadline <= game.scheduled_final_claim_deadline,
+        "scheduled_reveal_deadline <= scheduled_final_claim_deadline",
+    )?;
+    check(
+        game.early_bird_cutoff <= submission_deadline,
+        "early_bird_cutoff <= submission_deadline",
+    )?;
+    check(
+        game.dispute_threshold <= 100
+            && game.min_reveal_pct_for_no_extension <= 100
+            && game.late_reveal_threshold_pct <= 100,
+        "percentages are at most 100",
+    )?;
+    check(
+        bps(game.max_treasury_exposure_bps)
+            && bps(game.early_bird_bonus_bps)
+            && bps(game.treasury_min_utilization_bps)
+            && bps(game.progressive_jackpot_contribution_bps)
+            && bps(game.late_reveal_penalty_bps),
+        "bps are at most BPS_DENOMINATOR",
+    )?;
+    check(
+        game.treasury_critical_threshold <= game.treasury_alert_threshold,
+        "treasury_critical_threshold <= treasury_alert_threshold",
+    )?;
+    check(
+        game.result_uncertainty_range <= 100,
+        "result_uncertainty_range <= 100",
+    )?;
+    check(
+        game.max_bet_count != Some(0),
+        "max_bet_count allows at least one bet",
+    )?;
+    check(
+        matches!(
+            unlock_schedule_total_bps(&game.payout_unlock_schedule),
+            0 | BPS_DENOMINATOR
+        ),
+        "payout_unlock_schedule unlocks 0 or BPS_DENOMINATOR",
+    )
+}
+
+// hardcoded per-game settings, shared with migrate_game so migrated games get the same config
+pub(crate) fn apply_default_game_config(game: &mut Game) {
+    game.dispute_threshold = DEFAULT_DISPUTE_THRESHOLD;
+    game.dispute_grace_period_seconds = DISPUTE_GRACE_PERIOD_SECONDS;
+    game.max_treasury_exposure_bps = MAX_TREASURY_EXPOSURE_BPS;
+    game.treasury_alert_threshold = TREASURY_ALERT_THRESHOLD;
+    game.treasury_critical_threshold = TREASURY_CRITICAL_THRESHOLD;
+    game.treasury_min_utilization_bps = TREASURY_MIN_UTILIZATION_BPS;
+    game.progressive_jackpot_contribution_bps = PROGRESSIVE_JACKPOT_CONTRIBUTION_BPS;
+    game.early_close_enabled = EARLY_CLOSE_ENABLED;
+    game.early_close_min_pot = EARLY_CLOSE_MIN_POT;
+    game.payout_unlock_schedule = PAYOUT_UNLOCK_SCHEDULE;
+    game.result_submission_hash = [0; 32];
+    game.max_bet_count = MAX_BET_COUNT;
+    game.minimum_host_liquidity_ratio = MINIMUM_HOST_LIQUIDITY_RATIO;
+    game.commit_reveal_same_tx = false;
+    game.hidden_result_mode = false;
+    game.late_reveal_penalty_bps = LATE_REVEAL_PENALTY_BPS;
+    game.late_reveal_threshold_pct = LATE_REVEAL_THRESHOLD_PCT;
+    game.submit_result_timestamp = None;
+    game.auto_extension_enabled = AUTO_EXTENSION_ENABLED;
+    game.min_reveal_pct_for_no_extension = MIN_REVEAL_PCT_FOR_NO_EXTENSION;
+    game.reveal_extension_seconds = REVEAL_EXTENSION_SECONDS;
+    game.cooldown_between_reveals_slots = COOLDOWN_BETWEEN_REVEALS_SLOTS;
+    game.early_bird_bonus_bps = EARLY_BIRD_BONUS_BPS;
+    game.reward_highest_bet = REWARD_HIGHEST_BET;
+    game.highest_bet_bonus_lamports = HIGHEST_BET_BONUS_LAMPORTS;
+    game.biggest_loser_prize_lamports = BIGGEST_LOSER_PRIZE_LAMPORTS;
+    game.commitment_version = COMMITMENT_VERSION;
+    game.mint_winner_nft = MINT_WINNER_NFT;
+    game.unique_commitments = UNIQUE_COMMITMENTS;
+    game.audit_log_enabled = AUDIT_LOG_ENABLED;
+    game.min_payout_threshold = MIN_PAYOUT_THRESHOLD_LAMPORTS;
+    game.grace_period_seconds = GRACE_PERIOD_SECONDS;
+    game.max_treasury_withdrawal_per_tx = MAX_TREASURY_WITHDRAWAL_PER_TX;
+    game.reveal_opens_immediately = REVEAL_OPENS_IMMEDIATELY;
+    game.result_verification_delay_seconds = RESULT_VERIFICATION_DELAY_SECONDS;
+    game.result_uncertainty_range = RESULT_UNCERTAINTY_RANGE;
+    game.burn_losing_bets = BURN_LOSING_BETS;
+    game.burn_address = BURN_ADDRESS;
+    game.is_charity_mode = false;
+    game.charity_wallet = None;
+    game.submission_deadline_grace_alert_seconds = SUBMISSION_DEADLINE_GRACE_ALERT_SECONDS;
+    game.vrf_public_key = None;
+    game.payout_curve_type = PAYOUT_CURVE_TYPE;
+    game.step_threshold = None;
+    game.constant_multiplier = None;
+    game.custom_payout_curve = false;
+    game.anti_sybil_stake = ANTI_SYBIL_STAKE_LAMPORTS;
+    game.callback_program = None;
+    game.callback_discriminant = [0; 8];
+}
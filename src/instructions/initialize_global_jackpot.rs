@@ -0,0 +1,13 @@
+use crate::InitializeGlobalJackpot;
+use anchor_lang::prelude::*;
+
+pub fn initialize_global_jackpot(ctx: Context<InitializeGlobalJackpot>) -> Result<()> {
+    let global_jackpot = &mut ctx.accounts.global_jackpot;
+    global_jackpot.pot = 0;
+    global_jackpot.epoch = 0;
+    global_jackpot.claimed_this_epoch = false;
+    global_jackpot.last_winner = None;
+    global_jackpot.bump = ctx.bumps.global_jackpot;
+    msg!("Global jackpot initialized");
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use crate::key_shares_consistent;
+use crate::GameError;
+use crate::InitializeHiddenResult;
+use crate::HIDDEN_RESULT_KEYHOLDERS;
+use anchor_lang::prelude::*;
+
+pub fn initialize_hidden_result(
+    ctx: Context<InitializeHiddenResult>,
+    keyholders: [Pubkey; HIDDEN_RESULT_KEYHOLDERS],
+    key_shares: [[u8; 32]; HIDDEN_RESULT_KEYHOLDERS],
+) -> Result<()> {
+    // a repeated keyholder would count twice towards the threshold
+    let all_distinct = keyholders.iter().enumerate().all(|(index, keyholder)| {
+        *keyholder != Pubkey::default() && !keyholders[..index].contains(keyholder)
+    });
+    require!(all_distinct, GameError::InvalidKeyholders);
+    require!(
+        key_shares_consistent(&key_shares),
+        GameError::InvalidKeyShares
+    );
+
+    let game_key = ctx.accounts.game.key();
+    let result_decryption = &mut ctx.accounts.result_decryption;
+    result_decryption.game = game_key;
+    result_decryption.keyholders = keyholders;
+    result_decryption.key_shares = key_shares;
+    result_decryption.encrypted_result = None;
+    result_decryption.decryption_shares = [[0; 32]; HIDDEN_RESULT_KEYHOLDERS];
+    result_decryption.share_submitted = [false; HIDDEN_RESULT_KEYHOLDERS];
+    result_decryption.bump = ctx.bumps.result_decryption;
+    ctx.accounts.game.hidden_result_mode = true;
+    msg!("Hidden result mode on with keyholders {:?}", keyholders);
+    Ok(())
+}
@@ -0,0 +1,11 @@
+use crate::InitializeInsuranceFund;
+use anchor_lang::prelude::*;
+
+pub fn initialize_insurance_fund(ctx: Context<InitializeInsuranceFund>) -> Result<()> {
+    let insurance_fund = &mut ctx.accounts.insurance_fund;
+    insurance_fund.total_funded = 0;
+    insurance_fund.total_paid = 0;
+    insurance_fund.bump = ctx.bumps.insurance_fund;
+    msg!("Protocol insurance fund initialized");
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use crate::InitializeLeaderboard;
+use crate::LeaderboardEntry;
+use crate::LEADERBOARD_CAPACITY;
+use anchor_lang::prelude::*;
+
+pub fn initialize_leaderboard(ctx: Context<InitializeLeaderboard>) -> Result<()> {
+    let leaderboard = &mut ctx.accounts.leaderboard;
+    leaderboard.game = ctx.accounts.game.key();
+    leaderboard.entries = [LeaderboardEntry::default(); LEADERBOARD_CAPACITY];
+    leaderboard.is_frozen = false;
+    leaderboard.snapshot_taken_at = 0;
+    leaderboard.snapshot_taken_by = Pubkey::default();
+    leaderboard.bump = ctx.bumps.leaderboard;
+    msg!("Leaderboard initialized for game: {}", leaderboard.game);
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::compute_payout_curve;
+use crate::require_with_context;
+use crate::GameError;
+use crate::InitializePayoutCurve;
+use crate::MAX_PAYOUT_CURVE_BASE_TIMES_100;
+use crate::MAX_PAYOUT_CURVE_EXPONENT_TIMES_100;
+use anchor_lang::prelude::*;
+
+pub fn initialize_payout_curve(
+    ctx: Context<InitializePayoutCurve>,
+    base_times_100: u32,
+    exponent_times_100: u32,
+) -> Result<()> {
+    require_with_context!(
+        base_times_100 <= MAX_PAYOUT_CURVE_BASE_TIMES_100,
+        GameError::InvalidPayoutCurve,
+        expected = format!("base_times_100 at most {}", MAX_PAYOUT_CURVE_BASE_TIMES_100),
+        got = base_times_100,
+    );
+    require_with_context!(
+        exponent_times_100 <= MAX_PAYOUT_CURVE_EXPONENT_TIMES_100,
+        GameError::InvalidPayoutCurve,
+        expected = format!(
+            "exponent_times_100 at most {}",
+            MAX_PAYOUT_CURVE_EXPONENT_TIMES_100
+        ),
+        got = exponent_times_100,
+    );
+
+    let payout_curve = &mut ctx.accounts.payout_curve;
+    payout_curve.game = ctx.accounts.game.key();
+    payout_curve.base_times_100 = base_times_100;
+    payout_curve.exponent_times_100 = exponent_times_100;
+    payout_curve.multipliers = compute_payout_curve(base_times_100, exponent_times_100);
+    payout_curve.bump = ctx.bumps.payout_curve;
+    ctx.accounts.game.custom_payout_curve = true;
+    msg!(
+        "Payout curve {} * exp(-{} * x) + 0.1 (times 100) initialized, multiplier {} on the result down to {}",
+        base_times_100,
+        exponent_times_100,
+        payout_curve.multipliers[0],
+        payout_curve.multipliers[100]
+    );
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use crate::InitializePlayerPnl;
+use anchor_lang::prelude::*;
+
+pub fn initialize_player_pnl(ctx: Context<InitializePlayerPnl>) -> Result<()> {
+    let player_pnl = &mut ctx.accounts.player_pnl;
+    player_pnl.player = ctx.accounts.player.key();
+    player_pnl.total_wagered = 0;
+    player_pnl.total_won = 0;
+    player_pnl.games_played = 0;
+    player_pnl.games_won = 0;
+    player_pnl.biggest_win_lamports = 0;
+    player_pnl.bump = ctx.bumps.player_pnl;
+    player_pnl.last_reveal_slot = 0;
+    msg!(
+        "Player P&L record initialized for player: {}",
+        player_pnl.player
+    );
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use crate::InitializePlayerReputation;
+use anchor_lang::prelude::*;
+
+pub fn initialize_player_reputation(ctx: Context<InitializePlayerReputation>) -> Result<()> {
+    let player_reputation = &mut ctx.accounts.player_reputation;
+    player_reputation.player = ctx.accounts.player.key();
+    player_reputation.games_participated = 0;
+    player_reputation.games_revealed = 0;
+    player_reputation.games_timed_out = 0;
+    player_reputation.reputation_score = 0;
+    player_reputation.bump = ctx.bumps.player_reputation;
+    player_reputation.unpaid_reveals = 0;
+    msg!(
+        "Player reputation record initialized for player: {}",
+        player_reputation.player
+    );
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use crate::{
+    InitializeProgramStats, DEFAULT_MAX_GAMES_PER_AUTHORITY, DEFAULT_MIN_COOLDOWN_SECONDS,
+    MAX_CALLBACK_PROGRAMS, PROGRAM_ADMIN_PUBKEY,
+};
+use anchor_lang::prelude::*;
+use std::str::FromStr;
+
+pub fn initialize_program_stats(ctx: Context<InitializeProgramStats>) -> Result<()> {
+    let program_stats = &mut ctx.accounts.program_stats;
+    program_stats.program_admin =
+        Pubkey::from_str(PROGRAM_ADMIN_PUBKEY).map_err(|_| ProgramError::InvalidArgument)?;
+    program_stats.last_game_closed_at = 0;
+    program_stats.min_cooldown_seconds = DEFAULT_MIN_COOLDOWN_SECONDS;
+    program_stats.bump = ctx.bumps.program_stats;
+    program_stats.max_games_per_authority = DEFAULT_MAX_GAMES_PER_AUTHORITY;
+    program_stats.callback_program_whitelist = [Pubkey::default(); MAX_CALLBACK_PROGRAMS];
+
+    msg!(
+        "Program stats initialized with hardcoded admin: {}. Min cooldown between games: {}s",
+        program_stats.program_admin,
+        program_stats.min_cooldown_seconds
+    );
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use crate::InitializeResultsFeed;
+use anchor_lang::prelude::*;
+
+pub fn initialize_results_feed(ctx: Context<InitializeResultsFeed>) -> Result<()> {
+    let results_feed = &mut ctx.accounts.results_feed;
+    results_feed.write_index = 0;
+    results_feed.bump = ctx.bumps.results_feed;
+    msg!("Historical results feed initialized.");
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use crate::current_clock;
+use crate::instructions::init_game;
+use crate::GameError;
+use crate::InitializeTestGame;
+use anchor_lang::prelude::*;
+
+pub fn initialize_test_game(
+    ctx: Context<InitializeTestGame>,
+    submission_deadline_offset: i64,
+    reveal_deadline_offset: i64,
+    final_claim_offset: i64,
+) -> Result<()> {
+    let clock = current_clock()?;
+    let now = clock.unix_timestamp;
+    let from_now = |offset: i64| now.checked_add(offset).ok_or(GameError::Overflow);
+    let submission_deadline = from_now(submission_deadline_offset)?;
+    let reveal_deadline = from_now(reveal_deadline_offset)?;
+    let final_claim_deadline = from_now(final_claim_offset)?;
+    msg!(
+        "TEST GAME. Deadlines relative to {}: submission {}, reveal {}, final claim {}",
+        now,
+        submission_deadline,
+        reveal_deadline,
+        final_claim_deadline
+    );
+    init_game(
+        &mut ctx.accounts.initialize_game,
+        &ctx.bumps.initialize_game,
+        submission_deadline,
+        reveal_deadline,
+        final_claim_deadline,
+        true,
+        None,
+        None,
+        None,
+        None,
+    )
+}
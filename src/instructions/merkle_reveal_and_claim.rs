@@ -0,0 +1,91 @@
+use crate::instructions::claim_reveal;
+use crate::require_with_context;
+use crate::BetCommitment;
+use crate::Game;
+use crate::GameError;
+use crate::RevealAndClaim;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+// domain separation so an inner node can't be passed off as a leaf
+const MERKLE_LEAF_PREFIX: u8 = 0;
+const MERKLE_NODE_PREFIX: u8 = 1;
+// leaf_index is a u32, so no tree is deeper than this
+const MAX_MERKLE_PROOF_LEN: usize = 32;
+
+// leaf for a bet in the tree the authority commits to, clients build the tree from these.
+// original_player rather than player, transfer_bet_ownership changes the owner after the root is set
+pub fn bet_merkle_leaf(bet_commitment: &BetCommitment) -> [u8; 32] {
+    keccak::hashv(&[
+        &[MERKLE_LEAF_PREFIX],
+        bet_commitment.original_player.as_ref(),
+        &bet_commitment.commitment,
+        &bet_commitment.amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+// walks up from the leaf, the bits of leaf_index say whether each sibling sits on the left or right
+pub fn compute_merkle_root(leaf: [u8; 32], proof: &[[u8; 32]], leaf_index: u32) -> [u8; 32] {
+    proof
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (depth, sibling)| {
+            let (left, right) = if (leaf_index >> depth) & 1 == 0 {
+                (node, *sibling)
+            } else {
+                (*sibling, node)
+            };
+            keccak::hashv(&[&[MERKLE_NODE_PREFIX], &left, &right]).to_bytes()
+        })
+}
+
+pub fn merkle_reveal_and_claim(
+    ctx: Context<RevealAndClaim>,
+    proof: Vec<[u8; 32]>,
+    leaf_index: u32,
+    bet_value: u8,
+    salt: u64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.game.bet_merkle_root.is_some(),
+        GameError::BetMerkleRootNotSet
+    );
+    verify_bet_merkle_proof(
+        &ctx.accounts.game,
+        &ctx.accounts.bet_commitment,
+        &proof,
+        leaf_index,
+    )?;
+    claim_reveal(ctx, bet_value, salt)
+}
+
+// once the authority has set a bet merkle root every reveal has to prove its bet is in it,
+// passes without a root so reveal_via_escrow can call it either way
+pub(crate) fn verify_bet_merkle_proof(
+    game: &Game,
+    bet_commitment: &BetCommitment,
+    proof: &[[u8; 32]],
+    leaf_index: u32,
+) -> Result<()> {
+    let Some(bet_merkle_root) = game.bet_merkle_root else {
+        return Ok(());
+    };
+    require_with_context!(
+        proof.len() <= MAX_MERKLE_PROOF_LEN,
+        GameError::InvalidMerkleProof,
+        expected = format!("proof of at most {} nodes", MAX_MERKLE_PROOF_LEN),
+        got = proof.len(),
+    );
+    let leaf = bet_merkle_leaf(bet_commitment);
+    require!(
+        compute_merkle_root(leaf, proof, leaf_index) == bet_merkle_root,
+        GameError::InvalidMerkleProof
+    );
+    msg!(
+        "Merkle proof verified for player {} at leaf {}",
+        bet_commitment.original_player,
+        leaf_index
+    );
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use crate::instructions::apply_default_game_config;
+use crate::Game;
+use crate::GameError;
+use crate::GameSnapshot;
+use crate::GameV1;
+use crate::MigrateGame;
+use crate::FINAL_CLAIM_DEADLINE_TIMESTAMP;
+use crate::GAME_VERSION;
+use crate::REVEAL_DEADLINE_TIMESTAMP;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+pub fn migrate_game(ctx: Context<MigrateGame>) -> Result<()> {
+    let game_info = ctx.accounts.game.to_account_info();
+    let mut game = match GameSnapshot::from_account_info(&game_info)? {
+        GameSnapshot::V1(legacy) => game_from_v1(legacy),
+        GameSnapshot::V2(_) => return err!(GameError::GameAlreadyMigrated),
+    };
+    require_keys_eq!(
+        game.authority,
+        ctx.accounts.authority.key(),
+        GameError::InvalidAuthority
+    );
+    // bets placed on the original deployment are still in the old BetCommitment layout, seeded by player
+    // rather than original_player, and can't be loaded by this program. only empty games move over
+    require!(game.bet_count == 0, GameError::MigrationWithBets);
+    // the old layout didn't snapshot at submission, current totals are the closest we have
+    if let Some(result) = game.result {
+        game.result_lower = result;
+        game.result_upper = result;
+        game.bet_count_at_submission = game.bet_count;
+        game.total_player_pot_at_submission = game.total_player_pot;
+    }
+
+    // v1 never funded a rent reserve, fund it the same way initialize_game does so it isn't counted as host liquidity
+    game.treasury_rent_reserve = Rent::get()?.minimum_balance(0);
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: ctx.accounts.game_treasury.to_account_info(),
+            },
+        ),
+        game.treasury_rent_reserve,
+    )?;
+
+    // top up rent for the bigger layout before growing the account
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(8 + Game::INIT_SPACE);
+    let rent_top_up = rent_exempt_minimum.saturating_sub(game_info.lamports());
+    if rent_top_up > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: game_info.clone(),
+                },
+            ),
+            rent_top_up,
+        )?;
+    }
+    game_info.realloc(8 + Game::INIT_SPACE, true)?;
+
+    let mut data = game_info.try_borrow_mut_data()?;
+    GameSnapshot::V2(game).try_serialize(&mut &mut data[..])?;
+    msg!(
+        "Migrated game from v1 to v{} layout. Rent topped up by {} lamports.",
+        GAME_VERSION,
+        rent_top_up
+    );
+    Ok(())
+}
+
+// fields the old layout didn't have get the same config a fresh game would
+fn default_game() -> Box<Game> {
+    let mut game = Box::<Game>::default();
+    game.game_version = GAME_VERSION;
+    apply_default_game_config(&mut game);
+    game
+}
+
+fn game_from_v1(legacy: GameV1) -> Box<Game> {
+    let defaults = default_game();
+    Box::new(Game {
+        authority: legacy.authority,
+        result: legacy.result,
+        is_open_for_bets: legacy.is_open_for_bets,
+        is_open_for_reveals: legacy.is_open_for_reveals,
+        bet_count: legacy.bet_count,
+        total_player_pot: legacy.total_player_pot,
+        bump: legacy.bump,
+        treasury_bump: legacy.treasury_bump,
+        submission_deadline: legacy.submission_deadline,
+        reveal_deadline: legacy.reveal_deadline,
+        final_claim_deadline: legacy.final_claim_deadline,
+        scheduled_reveal_deadline: REVEAL_DEADLINE_TIMESTAMP,
+        scheduled_final_claim_deadline: FINAL_CLAIM_DEADLINE_TIMESTAMP,
+        // v1 didn't track lifetime totals so those start at 0
+        ..*defaults
+    })
+}
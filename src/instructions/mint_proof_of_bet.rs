@@ -0,0 +1,100 @@
+use crate::MintProofOfBet;
+use crate::PROOF_OF_BET_METADATA_URI;
+use crate::TROPHY_SYMBOL;
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::mpl_token_metadata::types::DataV2;
+use anchor_spl::metadata::{
+    create_master_edition_v3, create_metadata_accounts_v3, CreateMasterEditionV3,
+    CreateMetadataAccountsV3,
+};
+use anchor_spl::token::{mint_to, MintTo};
+
+pub fn mint_proof_of_bet(ctx: Context<MintProofOfBet>) -> Result<()> {
+    let accounts = ctx.accounts;
+    let game_key = accounts.game.key();
+    let bet_index = accounts.bet_commitment.bet_index;
+
+    // treasury PDA is the mint and update authority, so it signs every CPI here
+    let seeds = &[
+        b"treasury".as_ref(),
+        game_key.as_ref(),
+        &[accounts.game.treasury_bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            MintTo {
+                mint: accounts.proof_of_bet_mint.to_account_info(),
+                to: accounts.player_token_account.to_account_info(),
+                authority: accounts.game_treasury.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        1,
+    )?;
+
+    // the bet value stays secret, only which game and which bet. name is capped at 32 chars
+    let game_id = game_key.to_string();
+    let data = DataV2 {
+        name: format!("McNuggies Bet {}#{}", &game_id[..4], bet_index),
+        symbol: TROPHY_SYMBOL.to_string(),
+        uri: format!(
+            "{}?game={}&bet={}",
+            PROOF_OF_BET_METADATA_URI, game_id, bet_index
+        ),
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    };
+    create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: accounts.metadata.to_account_info(),
+                mint: accounts.proof_of_bet_mint.to_account_info(),
+                mint_authority: accounts.game_treasury.to_account_info(),
+                payer: accounts.player.to_account_info(),
+                update_authority: accounts.game_treasury.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+                rent: accounts.rent.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        data,
+        false,
+        true,
+        None,
+    )?;
+
+    // max supply 0 makes it a 1 of 1, this also hands mint authority over to the edition
+    create_master_edition_v3(
+        CpiContext::new_with_signer(
+            accounts.token_metadata_program.to_account_info(),
+            CreateMasterEditionV3 {
+                edition: accounts.master_edition.to_account_info(),
+                mint: accounts.proof_of_bet_mint.to_account_info(),
+                update_authority: accounts.game_treasury.to_account_info(),
+                mint_authority: accounts.game_treasury.to_account_info(),
+                payer: accounts.player.to_account_info(),
+                metadata: accounts.metadata.to_account_info(),
+                token_program: accounts.token_program.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+                rent: accounts.rent.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        Some(0),
+    )?;
+
+    accounts.bet_commitment.proof_of_bet_mint = Some(accounts.proof_of_bet_mint.key());
+    msg!(
+        "Minted proof of bet {} for bet {} to {}",
+        accounts.proof_of_bet_mint.key(),
+        bet_index,
+        accounts.player.key()
+    );
+    Ok(())
+}
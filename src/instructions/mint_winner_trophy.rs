@@ -0,0 +1,107 @@
+use crate::GameError;
+use crate::MintWinnerTrophy;
+use crate::TROPHY_METADATA_URI;
+use crate::TROPHY_SYMBOL;
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::mpl_token_metadata::types::DataV2;
+use anchor_spl::metadata::{
+    create_master_edition_v3, create_metadata_accounts_v3, CreateMasterEditionV3,
+    CreateMetadataAccountsV3,
+};
+use anchor_spl::token::{mint_to, MintTo};
+
+pub fn mint_winner_trophy(ctx: Context<MintWinnerTrophy>, winner: Pubkey) -> Result<()> {
+    let accounts = ctx.accounts;
+    let bet_value = accounts
+        .bet_commitment
+        .revealed_bet_value
+        .ok_or(GameError::NotAWinner)?;
+    let true_result = accounts.game.result.ok_or(GameError::ResultNotSubmitted)?;
+    let payout_amount = accounts.bet_commitment.payout_amount;
+    let game_date = accounts.game.submission_deadline.unwrap_or_default();
+
+    // treasury PDA is the mint and update authority, so it signs every CPI here
+    let game_key = accounts.game.key();
+    let seeds = &[
+        b"treasury".as_ref(),
+        game_key.as_ref(),
+        &[accounts.game.treasury_bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            MintTo {
+                mint: accounts.trophy_mint.to_account_info(),
+                to: accounts.winner_token_account.to_account_info(),
+                authority: accounts.game_treasury.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        1,
+    )?;
+
+    // name is capped at 32 chars on chain, the full details go in the uri
+    let data = DataV2 {
+        name: format!("McNuggies Trophy {}/{}", bet_value, true_result),
+        symbol: TROPHY_SYMBOL.to_string(),
+        uri: format!(
+            "{}?bet={}&result={}&payout={}&date={}",
+            TROPHY_METADATA_URI, bet_value, true_result, payout_amount, game_date
+        ),
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    };
+    create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: accounts.metadata.to_account_info(),
+                mint: accounts.trophy_mint.to_account_info(),
+                mint_authority: accounts.game_treasury.to_account_info(),
+                payer: accounts.authority.to_account_info(),
+                update_authority: accounts.game_treasury.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+                rent: accounts.rent.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        data,
+        false,
+        true,
+        None,
+    )?;
+
+    // max supply 0 makes it a 1 of 1, this also hands mint authority over to the edition
+    create_master_edition_v3(
+        CpiContext::new_with_signer(
+            accounts.token_metadata_program.to_account_info(),
+            CreateMasterEditionV3 {
+                edition: accounts.master_edition.to_account_info(),
+                mint: accounts.trophy_mint.to_account_info(),
+                update_authority: accounts.game_treasury.to_account_info(),
+                mint_authority: accounts.game_treasury.to_account_info(),
+                payer: accounts.authority.to_account_info(),
+                metadata: accounts.metadata.to_account_info(),
+                token_program: accounts.token_program.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+                rent: accounts.rent.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        Some(0),
+    )?;
+
+    msg!(
+        "Minted trophy {} to winner {}. Bet: {}, Result: {}, Payout: {}",
+        accounts.trophy_mint.key(),
+        winner,
+        bet_value,
+        true_result,
+        payout_amount
+    );
+    Ok(())
+}
@@ -1,15 +1,159 @@
+pub mod accept_authority_transfer;
+pub mod batch_freeze_commitments;
+pub mod batch_unfreeze_commitments;
+pub mod cancel_authority_transfer;
+pub mod check_and_extend_reveal_deadline;
+pub mod claim_biggest_loser_prize;
+pub mod claim_held_payout;
+pub mod claim_host_liquidity_only;
+pub mod claim_insurance;
+pub mod claim_next_tranche;
 pub mod claim_remaining_treasury;
+pub mod clawback_unrevealed_bet;
+pub mod close_empty_treasury;
+pub mod close_game;
+pub mod close_stale_game;
 pub mod commit_bet;
+pub mod commit_bet_with_token_account_proof;
+pub mod commit_via_escrow;
+pub mod confirm_result;
+pub mod distribute_to_charity;
+pub mod early_close_betting;
+pub mod emergency_freeze_game;
+pub mod emergency_unfreeze_game;
+pub mod execute_emergency_withdraw;
+pub mod finalize_decryption;
+pub mod finalize_disputed_result;
+pub mod flag_suspicious_commitment;
+pub mod freeze_leaderboard;
+pub mod fund_insurance;
+pub mod initialize_authority_games;
+pub mod initialize_bet_distribution;
 pub mod initialize_game;
+pub mod initialize_global_jackpot;
+pub mod initialize_hidden_result;
+pub mod initialize_insurance_fund;
+pub mod initialize_leaderboard;
+pub mod initialize_payout_curve;
+pub mod initialize_player_pnl;
+pub mod initialize_player_reputation;
+pub mod initialize_program_stats;
+pub mod initialize_results_feed;
+#[cfg(feature = "test-helpers")]
+pub mod initialize_test_game;
+pub mod merkle_reveal_and_claim;
+pub mod migrate_game;
+pub mod mint_proof_of_bet;
+pub mod mint_winner_trophy;
+pub mod new_jackpot_epoch;
+pub mod open_reveal_period;
+pub mod pre_commit_result_hash;
+pub mod preview_payout;
+pub mod propose_authority_transfer;
+pub mod propose_emergency_withdraw;
+pub mod rebalance_excess;
 pub mod reclaim_bet_on_timeout;
+pub mod redeem_proof_of_bet;
+pub mod register_referral_code;
 pub mod reveal_and_claim;
+pub mod reveal_deadline_heartbeat;
+pub mod reveal_via_escrow;
+pub mod set_callback_program_whitelist;
+pub mod set_charity_mode;
+pub mod set_co_authority;
+#[cfg(feature = "test-helpers")]
+pub mod set_commit_reveal_same_tx;
+pub mod set_payout_callback;
+pub mod set_payout_curve;
+pub mod set_program_params;
+pub mod set_test_result;
+pub mod set_treasury_multisig;
+pub mod set_vrf_public_key;
+pub mod submit_bet_merkle_root;
+pub mod submit_decryption_share;
+pub mod submit_encrypted_result;
 pub mod submit_results;
+pub mod sweep_stale_commitment;
+pub mod transfer_bet_ownership;
+pub mod withdraw_missed_reveal_bet;
 pub mod withdraw_unpaid_bet;
 
+pub use accept_authority_transfer::*;
+pub use batch_freeze_commitments::*;
+pub use batch_unfreeze_commitments::*;
+pub use cancel_authority_transfer::*;
+pub use check_and_extend_reveal_deadline::*;
+pub use claim_biggest_loser_prize::*;
+pub use claim_held_payout::*;
+pub use claim_host_liquidity_only::*;
+pub use claim_insurance::*;
+pub use claim_next_tranche::*;
 pub use claim_remaining_treasury::*;
+pub use clawback_unrevealed_bet::*;
+pub use close_empty_treasury::*;
+pub use close_game::*;
+pub use close_stale_game::*;
 pub use commit_bet::*;
+pub use commit_bet_with_token_account_proof::*;
+pub use commit_via_escrow::*;
+pub use confirm_result::*;
+pub use distribute_to_charity::*;
+pub use early_close_betting::*;
+pub use emergency_freeze_game::*;
+pub use emergency_unfreeze_game::*;
+pub use execute_emergency_withdraw::*;
+pub use finalize_decryption::*;
+pub use finalize_disputed_result::*;
+pub use flag_suspicious_commitment::*;
+pub use freeze_leaderboard::*;
+pub use fund_insurance::*;
+pub use initialize_authority_games::*;
+pub use initialize_bet_distribution::*;
 pub use initialize_game::*;
+pub use initialize_global_jackpot::*;
+pub use initialize_hidden_result::*;
+pub use initialize_insurance_fund::*;
+pub use initialize_leaderboard::*;
+pub use initialize_payout_curve::*;
+pub use initialize_player_pnl::*;
+pub use initialize_player_reputation::*;
+pub use initialize_program_stats::*;
+pub use initialize_results_feed::*;
+#[cfg(feature = "test-helpers")]
+pub use initialize_test_game::*;
+pub use merkle_reveal_and_claim::*;
+pub use migrate_game::*;
+pub use mint_proof_of_bet::*;
+pub use mint_winner_trophy::*;
+pub use new_jackpot_epoch::*;
+pub use open_reveal_period::*;
+pub use pre_commit_result_hash::*;
+pub use preview_payout::*;
+pub use propose_authority_transfer::*;
+pub use propose_emergency_withdraw::*;
+pub use rebalance_excess::*;
 pub use reclaim_bet_on_timeout::*;
+pub use redeem_proof_of_bet::*;
+pub use register_referral_code::*;
 pub use reveal_and_claim::*;
+pub use reveal_deadline_heartbeat::*;
+pub use reveal_via_escrow::*;
+pub use set_callback_program_whitelist::*;
+pub use set_charity_mode::*;
+pub use set_co_authority::*;
+#[cfg(feature = "test-helpers")]
+pub use set_commit_reveal_same_tx::*;
+pub use set_payout_callback::*;
+pub use set_payout_curve::*;
+pub use set_program_params::*;
+pub use set_test_result::*;
+pub use set_treasury_multisig::*;
+pub use set_vrf_public_key::*;
+pub use submit_bet_merkle_root::*;
+pub use submit_decryption_share::*;
+pub use submit_encrypted_result::*;
 pub use submit_results::*;
+pub use sweep_stale_commitment::*;
+pub use transfer_bet_ownership::*;
+pub use withdraw_missed_reveal_bet::*;
 pub use withdraw_unpaid_bet::*;
@@ -0,0 +1,19 @@
+use crate::GameError;
+use crate::NewJackpotEpoch;
+use anchor_lang::prelude::*;
+
+// the pot carries over, only the claimed flag resets so the next exact guess can win it
+pub fn new_jackpot_epoch(ctx: Context<NewJackpotEpoch>) -> Result<()> {
+    let global_jackpot = &mut ctx.accounts.global_jackpot;
+    global_jackpot.epoch = global_jackpot
+        .epoch
+        .checked_add(1)
+        .ok_or(GameError::Overflow)?;
+    global_jackpot.claimed_this_epoch = false;
+    msg!(
+        "Jackpot epoch {} started with {} lamports",
+        global_jackpot.epoch,
+        global_jackpot.pot
+    );
+    Ok(())
+}
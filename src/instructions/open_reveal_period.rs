@@ -0,0 +1,14 @@
+use crate::BetPhase;
+use crate::OpenRevealPeriod;
+use anchor_lang::prelude::*;
+
+pub fn open_reveal_period(ctx: Context<OpenRevealPeriod>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    game.set_phase(BetPhase::Revealing);
+    msg!(
+        "Reveal period opened by {}. Reveal deadline: {}",
+        ctx.accounts.authority.key,
+        game.reveal_deadline.unwrap_or_default()
+    );
+    Ok(())
+}
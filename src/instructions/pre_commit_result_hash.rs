@@ -0,0 +1,18 @@
+use crate::GameError;
+use crate::PreCommitResultHash;
+use anchor_lang::prelude::*;
+
+pub fn pre_commit_result_hash(
+    ctx: Context<PreCommitResultHash>,
+    result_hash: [u8; 32],
+) -> Result<()> {
+    // all zeroes is how the game says there's no pre-commitment
+    require!(result_hash != [0; 32], GameError::InvalidResultHash);
+    let game = &mut ctx.accounts.game;
+    game.result_submission_hash = result_hash;
+    msg!(
+        "Authority {} pre-committed to the result hash",
+        ctx.accounts.authority.key
+    );
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::preview_payout_offchain;
+use crate::PayoutEstimate;
+use crate::PreviewPayout;
+use crate::PAYOUT_MULTIPLIER_LUT;
+use anchor_lang::prelude::*;
+
+// the bet value only ever goes into a simulation, so nothing about the commitment is revealed on chain
+pub fn preview_payout(
+    _ctx: Context<PreviewPayout>,
+    bet_value: u8,
+    assumed_result: u8,
+    bet_amount: u64,
+) -> Result<()> {
+    let payout_amount = preview_payout_offchain(bet_value, assumed_result, bet_amount)?;
+    let assumed_diff = assumed_result as i16 - bet_value as i16;
+    let multiplier_scaled = u8::try_from(assumed_diff)
+        .map(|difference| PAYOUT_MULTIPLIER_LUT[difference as usize])
+        .unwrap_or(0);
+    emit!(PayoutEstimate {
+        assumed_diff,
+        multiplier_scaled,
+        estimated_payout: payout_amount,
+    });
+    msg!(
+        "Preview payout for bet {} of {} lamports if the result is {}: {} lamports",
+        bet_value,
+        bet_amount,
+        assumed_result,
+        payout_amount
+    );
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use crate::current_clock;
+use crate::ProposeAuthorityTransfer;
+use anchor_lang::prelude::*;
+
+pub fn propose_authority_transfer(
+    ctx: Context<ProposeAuthorityTransfer>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let clock = current_clock()?;
+    let game = &mut ctx.accounts.game;
+    game.pending_authority = Some(new_authority);
+    game.pending_authority_proposed_at = clock.unix_timestamp;
+    msg!(
+        "Authority transfer from {} to {} proposed",
+        game.authority,
+        new_authority
+    );
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use crate::current_clock;
+use crate::GameError;
+use crate::ProposeEmergencyWithdraw;
+use crate::EMERGENCY_WITHDRAW_TIMELOCK_SECONDS;
+use anchor_lang::prelude::*;
+
+pub fn propose_emergency_withdraw(
+    ctx: Context<ProposeEmergencyWithdraw>,
+    amount: u64,
+) -> Result<()> {
+    let clock = current_clock()?;
+    let game = &mut ctx.accounts.game;
+    game.pending_withdraw_amount = Some(amount);
+    game.pending_withdraw_unlocks_at = clock
+        .unix_timestamp
+        .checked_add(EMERGENCY_WITHDRAW_TIMELOCK_SECONDS)
+        .ok_or(GameError::Overflow)?;
+    msg!(
+        "Emergency withdrawal of {} lamports proposed, executable from {}",
+        amount,
+        game.pending_withdraw_unlocks_at
+    );
+    Ok(())
+}
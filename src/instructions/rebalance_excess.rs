@@ -0,0 +1,59 @@
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::GameError;
+use crate::RebalanceExcess;
+use crate::TreasuryOverFunded;
+use crate::BPS_DENOMINATOR;
+use anchor_lang::prelude::*;
+
+pub fn rebalance_excess(ctx: Context<RebalanceExcess>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let game_treasury = &ctx.accounts.game_treasury;
+    // same host liquidity as claim_host_liquidity_only
+    let host_liquidity = game_treasury
+        .to_account_info()
+        .lamports()
+        .saturating_sub(game.treasury_rent_reserve)
+        .saturating_sub(game.total_player_pot)
+        .saturating_sub(game.undistributed_charity_amount());
+    // players who still haven't revealed or been paid keep this much cover, on top of their stakes
+    let required_liquidity = game.total_player_pot as u128
+        * (BPS_DENOMINATOR + game.treasury_min_utilization_bps as u64) as u128
+        / BPS_DENOMINATOR as u128;
+    let excess_amount = u64::try_from((host_liquidity as u128).saturating_sub(required_liquidity))
+        .map_err(|_| GameError::Overflow)?
+        .min(game.max_treasury_withdrawal_per_tx);
+    if excess_amount == 0 {
+        msg!(
+            "Host liquidity {} isn't over the {} the player pot needs, nothing to rebalance.",
+            host_liquidity,
+            required_liquidity
+        );
+        return Ok(());
+    }
+
+    let current_utilization_bps =
+        (game.total_player_pot as u128 * BPS_DENOMINATOR as u128 / host_liquidity as u128) as u64;
+    emit!(TreasuryOverFunded {
+        game: game.key(),
+        excess_amount,
+        current_utilization_bps,
+    });
+    withdraw_from_treasury_to_player(
+        game,
+        game_treasury,
+        &ctx.accounts.system_program,
+        &ctx.accounts.authority,
+        excess_amount,
+    )?;
+    game.total_claimed_by_authority = game
+        .total_claimed_by_authority
+        .checked_add(excess_amount)
+        .ok_or(GameError::Overflow)?;
+    msg!(
+        "Rebalanced {} lamports of over-funding back to authority {}. Utilization was {} bps.",
+        excess_amount,
+        ctx.accounts.authority.key(),
+        current_utilization_bps
+    );
+    Ok(())
+}
@@ -1,4 +1,9 @@
-use crate::withdraw_from_treasury_to_player;
+use crate::cpi_helpers::release_anti_sybil_stake;
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::current_clock;
+use crate::require_with_context;
+use crate::AuditEntry;
+use crate::AuditInstruction;
 use crate::GameError;
 use crate::ReclaimBetOnTimeout;
 use anchor_lang::prelude::*;
@@ -12,26 +17,53 @@ pub fn reclaim_bet_on_timeout(ctx: Context<ReclaimBetOnTimeout>) -> Result<()> {
     let treasury_balance = ctx.accounts.game_treasury.to_account_info().lamports();
     // woops, casino bankrupt ggs. contact me for payout? guess this really trusts the authority
     // ensure liquidity in treasury is high enough to cover all bets before making your bets!
-    require!(
+    require_with_context!(
         treasury_balance >= reclaim_amount,
-        GameError::InsufficientTreasuryForReclaim
+        GameError::InsufficientTreasuryForReclaim,
+        expected = format!("treasury balance at least {} lamports", reclaim_amount),
+        got = treasury_balance,
     );
 
     // updating total_player_pot to reflect the payout, decrementing initial stake so remaining comes out of host's liquidity
     game.total_player_pot = game
         .total_player_pot
         .checked_sub(reclaim_amount)
-        .ok_or(GameError::TotalPayoutPotDesynced)?;
+        .ok_or(GameError::PlayerPotUnderflow)?;
 
     commitment.is_claimed = true;
 
-    withdraw_from_treasury_to_player(
+    let transferred = withdraw_from_treasury_to_player(
         game,
         &ctx.accounts.game_treasury,
         &ctx.accounts.system_program,
         &ctx.accounts.player,
         reclaim_amount,
     )?;
+    game.total_paid_out = game
+        .total_paid_out
+        .checked_add(transferred)
+        .ok_or(GameError::PayoutAmountOverflow)?;
+    if let Some(player_reputation) = ctx.accounts.player_reputation.as_mut() {
+        player_reputation.record_timeout()?;
+    }
+    // the authority timed out, not the player, so the anti sybil stake goes back too
+    release_anti_sybil_stake(
+        &game.key(),
+        commitment,
+        ctx.accounts.anti_sybil_vault.as_ref(),
+        ctx.bumps.anti_sybil_vault,
+        &ctx.accounts.system_program,
+        &ctx.accounts.player,
+    )?;
+    game.record_audit(
+        ctx.accounts.audit_log.as_mut(),
+        AuditEntry::new(
+            AuditInstruction::ReclaimOnTimeout,
+            player,
+            -(transferred as i64),
+            current_clock()?.unix_timestamp,
+        ),
+    )?;
 
     msg!(
         "Authority missed deadline. Reclaimed {} lamports for player {}.",
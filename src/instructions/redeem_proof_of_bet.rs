@@ -0,0 +1,53 @@
+use crate::current_clock;
+use crate::AuditEntry;
+use crate::AuditInstruction;
+use crate::BetOwnershipTransferred;
+use crate::RedeemProofOfBet;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn};
+
+pub fn redeem_proof_of_bet(ctx: Context<RedeemProofOfBet>) -> Result<()> {
+    let clock = current_clock()?;
+    let accounts = ctx.accounts;
+    burn(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Burn {
+                mint: accounts.proof_of_bet_mint.to_account_info(),
+                from: accounts.holder_token_account.to_account_info(),
+                authority: accounts.holder.to_account_info(),
+            },
+        ),
+        1,
+    )?;
+
+    // holder takes the bet over like transfer_bet_ownership, so every player instruction now goes to them
+    let bet_commitment = &mut accounts.bet_commitment;
+    let old_owner = bet_commitment.player;
+    let new_owner = accounts.holder.key();
+    bet_commitment.player = new_owner;
+    bet_commitment.proof_of_bet_mint = None;
+
+    accounts.game.record_audit(
+        accounts.audit_log.as_mut(),
+        AuditEntry::new(
+            AuditInstruction::TransferOwnership,
+            old_owner,
+            0,
+            clock.unix_timestamp,
+        ),
+    )?;
+    emit!(BetOwnershipTransferred {
+        game: accounts.game.key(),
+        bet_commitment: bet_commitment.key(),
+        old_owner,
+        new_owner,
+    });
+    msg!(
+        "Proof of bet {} redeemed, bet {} now owned by {}",
+        accounts.proof_of_bet_mint.key(),
+        bet_commitment.key(),
+        new_owner
+    );
+    Ok(())
+}
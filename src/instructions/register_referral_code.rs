@@ -0,0 +1,29 @@
+use crate::require_with_context;
+use crate::GameError;
+use crate::RegisterReferralCode;
+use crate::REFERRAL_CODE_LENGTH;
+use anchor_lang::prelude::*;
+
+pub fn register_referral_code(
+    ctx: Context<RegisterReferralCode>,
+    code: [u8; REFERRAL_CODE_LENGTH],
+) -> Result<()> {
+    require_with_context!(
+        code.iter().all(u8::is_ascii_alphanumeric),
+        GameError::InvalidReferralCode,
+        expected = "8 ASCII letters or digits",
+        got = format!("{:?}", code),
+    );
+    let referral_code = &mut ctx.accounts.referral_code;
+    referral_code.code = code;
+    referral_code.owner = ctx.accounts.owner.key();
+    referral_code.uses = 0;
+    referral_code.total_referral_paid = 0;
+    referral_code.bump = ctx.bumps.referral_code;
+    msg!(
+        "Referral code {} registered to {}",
+        String::from_utf8_lossy(&code),
+        referral_code.owner
+    );
+    Ok(())
+}
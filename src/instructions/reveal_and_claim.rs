@@ -1,136 +1,621 @@
-use crate::withdraw_from_treasury_to_player;
+use crate::compute_versioned_commitment;
+use crate::cpi_helpers::release_anti_sybil_stake;
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::current_clock;
+use crate::require_with_context;
+use crate::settle_reveal_amounts;
+use crate::AuditEntry;
+use crate::AuditInstruction;
+use crate::BetCommitment;
+use crate::Game;
 use crate::GameError;
+use crate::GlobalJackpot;
+use crate::GlobalJackpotWon;
+use crate::HighestBetBonusPaid;
+use crate::PayoutCurve;
+use crate::PlayerPnL;
+use crate::ProgramStats;
+use crate::RevealAmounts;
 use crate::RevealAndClaim;
-use crate::FINAL_CLAIM_DEADLINE_TIMESTAMP;
-use crate::PAYOUT_MULTIPLIER_LUT;
-use crate::PAYOUT_SCALE;
+use crate::RevealOutcome;
+use crate::TreasuryBelowAlert;
+use crate::GLOBAL_GAME_SEED;
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
 
 pub fn reveal_and_claim(ctx: Context<RevealAndClaim>, bet_value: u8, salt: u64) -> Result<()> {
-    require!(bet_value <= 100, GameError::InvalidBetValue);
-    let game = &mut ctx.accounts.game;
-    let commitment_account = &mut ctx.accounts.bet_commitment;
-    let player = *ctx.accounts.player.key;
-    let Some(true_result) = game.result else {
-        return Err(GameError::ResultNotSubmitted.into());
+    // with a bet merkle root set, reveals have to go through merkle_reveal_and_claim
+    require!(
+        ctx.accounts.game.bet_merkle_root.is_none(),
+        GameError::BetMerkleProofRequired
+    );
+    claim_reveal(ctx, bet_value, salt)
+}
+
+// reveal_and_claim without the merkle root check, shared with merkle_reveal_and_claim once the proof is verified
+pub(crate) fn claim_reveal(ctx: Context<RevealAndClaim>, bet_value: u8, salt: u64) -> Result<()> {
+    let clock = current_clock()?;
+    let accounts = ctx.accounts;
+    require_reveal_window(
+        &accounts.game,
+        accounts.bet_commitment.amount,
+        clock.unix_timestamp,
+    )?;
+    verify_reveal(&accounts.bet_commitment, bet_value, salt)?;
+
+    enforce_reveal_cooldown(
+        &accounts.game,
+        accounts.player_pnl.as_deref_mut(),
+        clock.slot,
+    )?;
+
+    // histogram of guesses, only counted on the first reveal like the rest of the analytics
+    if let Some(bet_distribution) = accounts.bet_distribution.as_mut() {
+        if !accounts.bet_commitment.attempted_reveal {
+            bet_distribution.record(bet_value)?;
+        }
+    }
+
+    // a reveal retried after the host couldn't pay only counts once
+    let is_first_reveal = !accounts.bet_commitment.attempted_reveal;
+    let payout_amount = settle_reveal(
+        &mut accounts.game,
+        &mut accounts.bet_commitment,
+        &accounts.game_treasury,
+        &accounts.system_program,
+        &accounts.player.to_account_info(),
+        accounts.player_pnl.as_mut(),
+        accounts.payout_curve.as_deref(),
+        bet_value,
+        true,
+    )?;
+    forward_losing_stake(
+        &mut accounts.game,
+        &accounts.bet_commitment,
+        &accounts.game_treasury,
+        &accounts.system_program,
+        accounts.burn_address.as_ref(),
+        bet_value,
+    )?;
+
+    // revealing at all earns the anti sybil stake back, win or lose
+    if is_first_reveal {
+        let game_key = accounts.game.key();
+        release_anti_sybil_stake(
+            &game_key,
+            &mut accounts.bet_commitment,
+            accounts.anti_sybil_vault.as_ref(),
+            ctx.bumps.anti_sybil_vault,
+            &accounts.system_program,
+            &accounts.player,
+        )?;
+        if accounts.game.result == Some(bet_value) {
+            if let Some(global_jackpot) = accounts.global_jackpot.as_deref_mut() {
+                pay_global_jackpot(
+                    global_jackpot,
+                    accounts.game.key(),
+                    &accounts.player.to_account_info(),
+                )?;
+            }
+        }
+    }
+
+    if let Some(player_reputation) = accounts.player_reputation.as_mut() {
+        if is_first_reveal {
+            player_reputation.record_reveal()?;
+            // attempted_reveal on a bet that's still open means the host couldn't pay it
+            if accounts.bet_commitment.attempted_reveal && !accounts.bet_commitment.is_claimed {
+                player_reputation.record_unpaid_reveal()?;
+            }
+        }
+    }
+
+    // held part isn't transferred yet, claim_held_payout logs it when it is
+    let transferred = payout_amount.saturating_sub(accounts.bet_commitment.held_payout);
+    accounts.game.record_audit(
+        accounts.audit_log.as_mut(),
+        AuditEntry::new(
+            AuditInstruction::Reveal,
+            accounts.player.key(),
+            -(transferred as i64),
+            clock.unix_timestamp,
+        ),
+    )?;
+
+    // payouts held back or left pending on liquidity aren't a finished payout yet
+    if payout_amount > 0 && accounts.bet_commitment.is_claimed {
+        notify_payout_callback(
+            &accounts.game,
+            accounts.program_stats.as_ref(),
+            accounts.callback_program.as_ref(),
+            accounts.player.key(),
+            payout_amount,
+        )?;
+    }
+
+    // once frozen the leaderboard is the final record, late reveals don't move it
+    if let Some(leaderboard) = accounts.leaderboard.as_mut() {
+        if !leaderboard.is_frozen && payout_amount > 0 {
+            leaderboard.record(accounts.player.key(), payout_amount);
+        }
+    }
+    Ok(())
+}
+
+// first exact guess of the epoch across all games takes the whole jackpot. it's program owned so
+// it's debited directly, keeping it rent exempt
+fn pay_global_jackpot<'info>(
+    global_jackpot: &mut Account<'info, GlobalJackpot>,
+    game: Pubkey,
+    player: &AccountInfo<'info>,
+) -> Result<()> {
+    if global_jackpot.claimed_this_epoch || global_jackpot.pot == 0 {
+        return Ok(());
+    }
+    let jackpot_info = global_jackpot.to_account_info();
+    let available = jackpot_info
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(jackpot_info.data_len()));
+    let amount = global_jackpot.pot.min(available);
+    jackpot_info.sub_lamports(amount)?;
+    player.add_lamports(amount)?;
+    global_jackpot.pot = 0;
+    global_jackpot.claimed_this_epoch = true;
+    global_jackpot.last_winner = Some(player.key());
+
+    emit!(GlobalJackpotWon {
+        game,
+        player: player.key(),
+        amount,
+        epoch: global_jackpot.epoch,
+    });
+    msg!(
+        "Player {} won the global jackpot of {} lamports",
+        player.key(),
+        amount
+    );
+    Ok(())
+}
+
+// CPIs the game's callback program with callback_discriminant || game || player || payout_amount,
+// game signing as a PDA so the callback knows the payout came from here. skipped, not failed,
+// if the admin has since delisted the program, so a bad callback can't hold up reveals
+fn notify_payout_callback<'info>(
+    game: &Account<'info, Game>,
+    program_stats: Option<&Account<'info, ProgramStats>>,
+    callback_program: Option<&UncheckedAccount<'info>>,
+    player: Pubkey,
+    payout_amount: u64,
+) -> Result<()> {
+    let Some(expected_program) = game.callback_program else {
+        return Ok(());
     };
-    let bet_amount = commitment_account.amount;
-    // validate the bet value and salt, revealing the bet value
-    let mut hasher = keccak::Hasher::default();
-    hasher.hash(&bet_value.to_le_bytes());
-    hasher.hash(&salt.to_le_bytes());
-    let hashed = hasher.result().to_bytes();
+    let program_stats = program_stats.ok_or(GameError::InvalidCallbackProgram)?;
+    if !program_stats.is_callback_whitelisted(&expected_program) {
+        msg!(
+            "Callback program {} is no longer whitelisted, skipping the payout callback",
+            expected_program
+        );
+        return Ok(());
+    }
+    let callback_program = callback_program.ok_or(GameError::InvalidCallbackProgram)?;
+
+    let mut data = game.callback_discriminant.to_vec();
+    data.extend_from_slice(game.key().as_ref());
+    data.extend_from_slice(player.as_ref());
+    data.extend_from_slice(&payout_amount.to_le_bytes());
+    let instruction = Instruction {
+        program_id: expected_program,
+        accounts: vec![AccountMeta::new_readonly(game.key(), true)],
+        data,
+    };
+    invoke_signed(
+        &instruction,
+        &[game.to_account_info(), callback_program.to_account_info()],
+        &[&[GLOBAL_GAME_SEED, &[game.bump]]],
+    )?;
+    msg!(
+        "Payout callback {} notified of {} lamports to {}",
+        expected_program,
+        payout_amount,
+        player
+    );
+    Ok(())
+}
+
+// validate the bet value and salt against the commitment, revealing the bet value
+pub(crate) fn verify_reveal(
+    commitment_account: &BetCommitment,
+    bet_value: u8,
+    salt: u64,
+) -> Result<()> {
+    require_with_context!(
+        bet_value <= 100,
+        GameError::InvalidBetValue,
+        expected = "bet value between 0 and 100",
+        got = bet_value,
+    );
+    // verify against the scheme the bet was committed under, not whatever the game uses now
+    let expected_commitment =
+        compute_versioned_commitment(commitment_account.commitment_version, bet_value, salt)
+            .ok_or(GameError::UnsupportedCommitmentVersion)?;
     require!(
-        hashed == commitment_account.commitment,
+        expected_commitment == commitment_account.commitment,
         GameError::CommitmentMismatch
     );
     msg!(
         "Bet reveal verified for player: {} (Bet: {}, Salt: {}, Amount: {})",
-        player,
+        commitment_account.player,
         bet_value,
         salt,
-        bet_amount
+        commitment_account.amount
+    );
+    Ok(())
+}
+
+// the stake of a bet settle_reveal just lost goes to the host unless the game says otherwise.
+// burn_losing_bets sends it to the burn address now, charity mode books it for distribute_to_charity.
+// the stake already left the player pot in settle_reveal, this just stops the host keeping it
+pub(crate) fn forward_losing_stake<'info>(
+    game: &mut Account<'info, Game>,
+    commitment_account: &BetCommitment,
+    game_treasury: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    burn_address: Option<&UncheckedAccount<'info>>,
+    bet_value: u8,
+) -> Result<()> {
+    // dust payouts settled as a loss aren't over the result, the host keeps those
+    if game.result_difference(bet_value).is_some() {
+        return Ok(());
+    }
+    if !game.burn_losing_bets {
+        if game.is_charity_mode {
+            game.total_charity_amount = game
+                .total_charity_amount
+                .checked_add(commitment_account.amount)
+                .ok_or(GameError::Overflow)?;
+        }
+        return Ok(());
+    }
+    let burn_address = burn_address.ok_or(GameError::BurnAddressRequired)?;
+    withdraw_from_treasury_to_player(
+        game,
+        game_treasury,
+        system_program,
+        burn_address,
+        commitment_account.amount,
+    )?;
+    msg!(
+        "Burned losing stake of {} lamports from player {}",
+        commitment_account.amount,
+        commitment_account.player
+    );
+    Ok(())
+}
+
+// reveal window and player pot checks shared by the reveal instructions.
+// done here rather than as account constraints so a rejection logs the limit and the rejected value
+pub(crate) fn require_reveal_window(game: &Game, bet_amount: u64, now: i64) -> Result<()> {
+    require_with_context!(
+        now >= game.reveal_opens_at,
+        GameError::VerificationDelayNotElapsed,
+        expected = format!("reveal at or after {}", game.reveal_opens_at),
+        got = now,
+    );
+    let reveal_deadline = game.reveal_deadline.ok_or(GameError::DeadlineNotSet)?;
+    require_with_context!(
+        now < reveal_deadline,
+        GameError::RevealDeadlineNotReached,
+        expected = format!("reveal before {}", reveal_deadline),
+        got = now,
+    );
+    // sanity check, total_player_pot should always hold every unsettled stake
+    require_with_context!(
+        game.total_player_pot >= bet_amount,
+        GameError::InsufficientPlayerPot,
+        expected = format!("player pot at least {} lamports", bet_amount),
+        got = game.total_player_pot,
+    );
+    Ok(())
+}
+
+// throttle back-to-back reveals from the same signer so other players can interleave theirs.
+// the last reveal slot lives on the signer's PlayerPnL, so it has to be passed while the game has a cooldown
+pub(crate) fn enforce_reveal_cooldown(
+    game: &Game,
+    player_pnl: Option<&mut PlayerPnL>,
+    slot: u64,
+) -> Result<()> {
+    let Some(player_pnl) = player_pnl else {
+        require!(
+            game.cooldown_between_reveals_slots == 0,
+            GameError::PlayerPnlRequired
+        );
+        return Ok(());
+    };
+    let next_reveal_slot = player_pnl
+        .last_reveal_slot
+        .saturating_add(game.cooldown_between_reveals_slots as u64);
+    require_with_context!(
+        slot >= next_reveal_slot,
+        GameError::RevealCooldownActive,
+        expected = format!("slot at least {}", next_reveal_slot),
+        got = slot,
     );
+    player_pnl.last_reveal_slot = slot;
+    Ok(())
+}
+
+// pays out a verified reveal to recipient. shared with reveal_via_escrow, where the recipient is the escrow beneficiary.
+// vest_payout locks what game.payout_unlock_schedule hasn't unlocked yet for claim_next_tranche, only
+// bets at their commitment PDA can claim it.
+// returns the payout, 0 for a loss or when the host couldn't cover it yet
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn settle_reveal<'info>(
+    game: &mut Account<'info, Game>,
+    commitment_account: &mut BetCommitment,
+    game_treasury: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    recipient: &AccountInfo<'info>,
+    player_pnl: Option<&mut Account<'info, PlayerPnL>>,
+    payout_curve: Option<&PayoutCurve>,
+    bet_value: u8,
+    vest_payout: bool,
+) -> Result<u64> {
+    let player = *recipient.key;
+    require!(game.result.is_some(), GameError::ResultNotSubmitted);
+    let bet_amount = commitment_account.amount;
+    commitment_account.revealed_bet_value = Some(bet_value);
+
+    // post-game analytics on the revealed guesses, only counted on the first reveal
+    // as a player whose payout failed on liquidity can reveal again later
+    if !commitment_account.attempted_reveal {
+        game.sum_of_revealed_bet_values = game
+            .sum_of_revealed_bet_values
+            .checked_add(bet_value as u64)
+            .ok_or(GameError::Overflow)?;
+        game.revealed_bet_count = game
+            .revealed_bet_count
+            .checked_add(1)
+            .ok_or(GameError::Overflow)?;
+    }
 
     // --- Claim Logic --- //
 
-    // LOSS CASE - OVER BET THE TRUE RESULT
-    if bet_value > true_result {
-        // payout is zero, this is a loss since user bet OVER the true result. Host keeps the bet amount.
-        msg!("Player lost, no payout {}. Bet marked as settled.", player);
+    // None is a loss, the bet is OVER the true result (the top of its uncertainty range)
+    let difference = game.result_difference(bet_value);
+    // payout_multiplier sanity checks the difference is at most 100
+    let scaled_multiplier = difference
+        .map(|difference| game.payout_multiplier(difference, payout_curve))
+        .transpose()?;
+    // the single biggest bet gets a flat bonus on top for guessing the result exactly (anywhere in its range)
+    let highest_bet_bonus = if game.reward_highest_bet
+        && difference == Some(0)
+        && game.highest_bet_player == Some(commitment_account.player)
+    {
+        game.highest_bet_bonus_lamports
+    } else {
+        0
+    };
+    let settlement = settle_reveal_amounts(&RevealAmounts {
+        bet_amount,
+        scaled_multiplier,
+        // early birds get a bonus on top of a winning payout
+        early_bird_bonus_bps: if commitment_account.is_early_bird {
+            game.early_bird_bonus_bps
+        } else {
+            0
+        },
+        highest_bet_bonus,
+        // revealing in the tail of the window costs a share of the winnings
+        late_reveal_penalty_bps: game
+            .applied_late_reveal_penalty_bps(current_clock()?.unix_timestamp),
+        min_payout_threshold: game.min_payout_threshold,
+        total_player_pot: game.total_player_pot,
+        // Check host liquidity implicitly
+        treasury_balance: game_treasury.lamports(),
+        treasury_rent_reserve: game.treasury_rent_reserve,
+        treasury_critical_threshold: game.treasury_critical_threshold,
+        is_disputed: game.is_disputed,
+    })?;
+    let payout_amount = settlement.payout_amount;
+
+    if matches!(
+        settlement.outcome,
+        RevealOutcome::Loss | RevealOutcome::BelowThreshold
+    ) {
+        match difference {
+            None => {
+                // payout is zero, this is a loss since user bet OVER the true result. Host keeps the bet amount.
+                msg!("Player lost, no payout {}. Bet marked as settled.", player);
+                // strictly greater, so on a tie the earlier reveal keeps it
+                let loss_gap = bet_value - game.result_upper;
+                if loss_gap > game.biggest_loss_gap {
+                    game.biggest_loss_gap = loss_gap;
+                    game.biggest_loser = Some(commitment_account.player);
+                }
+            }
+            // never ran on the exponential curve as it's > 0, linear and step curves can pay nothing though
+            Some(_) if settlement.outcome == RevealOutcome::Loss => {
+                msg!("No payout for player {}. Bet marked as settled.", player);
+            }
+            // dust payouts aren't worth paying out, settle them like a loss and the host keeps the stake
+            Some(_) => msg!(
+                "PayoutBelowThreshold: payout {} for player {} is below the minimum {}. Bet settled as a loss.",
+                payout_amount,
+                player,
+                game.min_payout_threshold
+            ),
+        }
+        commitment_account.is_claimed = true;
+        game.record_settled_reveal(false)?;
         // player is exiting the pot, decrementing the initial staked bet from total player pot
-        game.total_player_pot = game
-            .total_player_pot
-            .checked_sub(bet_amount)
-            .ok_or(GameError::TotalPayoutPotDesynced)?;
-        return Ok(());
+        game.total_player_pot = settlement.total_player_pot;
+        if let Some(player_pnl) = player_pnl {
+            player_pnl.record_reveal(bet_amount, 0)?;
+        }
+        return Ok(0);
     }
 
     // WIN CASE - AT LEAST EATEN X NUGGETS
-    // since we claim 0 <= guessed_bet <= 100 previously, sanity check max difference is 100
-    let difference = (true_result - bet_value) as usize;
-    require!(
-        difference < PAYOUT_MULTIPLIER_LUT.len(),
-        GameError::InvalidBetValue
-    );
-    let scaled_multiplier = PAYOUT_MULTIPLIER_LUT[difference];
-    let payout_amount =
-        ((bet_amount as u128 * scaled_multiplier as u128) / (PAYOUT_SCALE as u128)) as u64;
     msg!(
         "Player {} qualifies for payout. Diff: {}, Multiplier (scaled): {}, Bet: {}, Payout: {}",
         player,
-        difference,
-        scaled_multiplier,
+        difference.unwrap_or_default(),
+        scaled_multiplier.unwrap_or_default(),
         bet_amount,
         payout_amount
     );
-    // this actually never gets ran as exponential payout curve is > 0, keeping here for sanity
-    if payout_amount == 0 {
-        // if payout is zero, effectively a loss. Host keeps the bet amount.
-        msg!("No payout for player {}. Bet marked as settled.", player);
-        // player is exiting the pot, decrementing the initial staked bet from total player pot
-        game.total_player_pot = game
-            .total_player_pot
-            .checked_sub(bet_amount)
-            .ok_or(GameError::TotalPayoutPotDesynced)?;
-        return Ok(());
+    if settlement.early_bird_bonus > 0 {
+        msg!("Early bird bonus {} added", settlement.early_bird_bonus);
     }
-
-    // Check host liquidity implicitly
-    let treasury_balance = ctx.accounts.game_treasury.to_account_info().lamports();
-
-    // if the player's payout is <= initial stake, we can claim back the initial stake to use as payout
-    // since the player lost, we will eagerly claim back the initial stake to pay out the difference
-    if payout_amount <= bet_amount {
-        msg!("Player payout is less than initial stake. Host funds will increase by initial stake and payout will be the difference.");
-        // decreasing player's initial stake from total_player_pot, since payout is \leq initial stake i.e. 10% payout returns 10% of initial stake
-        game.total_player_pot = game
-            .total_player_pot
-            .checked_sub(bet_amount)
-            .ok_or(GameError::TotalPayoutPotDesynced)?;
+    if settlement.late_reveal_penalty > 0 {
+        msg!(
+            "Late reveal penalty of {} taken",
+            settlement.late_reveal_penalty
+        );
     }
 
-    // this should represent the portion of liquidity that is the host's pool. NOT USING OTHER CONTESTANT'S MONEY!!!! so they can always reclaim their initial stake
-    // total_player_pot can NEVER exceed treasury_balance as it should be backed one to one. treasury MUST NOT withdraw anywhere else without subtracting total_player_pot
-    let host_liquidity = treasury_balance
-        .checked_sub(game.total_player_pot)
-        .ok_or(GameError::TotalPayoutPotDesynced)?;
-    if payout_amount > host_liquidity {
+    // stop a race to drain the last of the host's liquidity, the winner is recorded as owed the same way
+    // as when the host can't cover the payout
+    if settlement.host_liquidity < game.treasury_critical_threshold {
+        msg!(
+            "TreasuryBelowCritical: host liquidity {} is under the critical threshold {}, payout not sent",
+            settlement.host_liquidity,
+            game.treasury_critical_threshold
+        );
+    }
+    if settlement.outcome == RevealOutcome::Unpaid {
         // host liquidity insufficient, player can use [`withdraw_unpaid_bet`] to reclaim their bet later if host does not fund...
         commitment_account.attempted_reveal = true;
+        // remember what they're owed so withdraw_unpaid_bet can pay it out if the host tops up
+        commitment_account.pending_payout_amount = payout_amount;
         // set the final claim deadline so player can reclaim their initial stake later if host does not fund
         // we don't handle potentially splittng treasury amongst players as thats a bit complicated. lets assume im at least that trustworthy
-        game.final_claim_deadline = Some(FINAL_CLAIM_DEADLINE_TIMESTAMP);
+        game.final_claim_deadline = Some(game.scheduled_final_claim_deadline);
         msg!("Host liquidity insufficient for payout. Player can use withdraw_unpaid_bet to reclaim their bet.");
-        return Ok(());
+        return Ok(0);
     }
 
-    // this handles the alternate case to claim back initial stake from pot, IF payout is successful,
-    // host liquidity was sufficient for payout
-    if payout_amount > bet_amount {
-        // updating total_player_pot to reflect the payout, decrementing initial stake so remaining comes out of host's liquidity
+    // host liquidity was sufficient for payout, the player is exiting the pot with their initial stake and
+    // anything past the stake comes out of host's liquidity. while the result is disputed, part of the payout
+    // is held back until finalize_disputed_result and stays in total_player_pot so the host can't claim it
+    game.total_player_pot = settlement.total_player_pot;
+    let held_payout = settlement.held_payout;
+    game.total_held_payout = game
+        .total_held_payout
+        .checked_add(held_payout)
+        .ok_or(GameError::PlayerPotOverflow)?;
+    commitment_account.held_payout = held_payout;
+    let transfer_amount = payout_amount - held_payout;
+
+    // the locked part of a vesting payout is tracked in total_player_pot like the held part
+    let transfer_amount = if vest_payout && game.has_payout_unlock_schedule() {
+        let unlocked = game.unlocked_payout(transfer_amount, current_clock()?.unix_timestamp);
+        let locked = transfer_amount - unlocked;
         game.total_player_pot = game
             .total_player_pot
-            .checked_sub(bet_amount)
-            .ok_or(GameError::TotalPayoutPotDesynced)?;
-    }
+            .checked_add(locked)
+            .ok_or(GameError::PlayerPotOverflow)?;
+        commitment_account.vesting_payout = transfer_amount;
+        commitment_account.pending_payout_amount = locked;
+        msg!(
+            "{} lamports of the payout unlocked, {} vesting for claim_next_tranche",
+            unlocked,
+            locked
+        );
+        unlocked
+    } else {
+        transfer_amount
+    };
 
     commitment_account.is_claimed = true;
+    commitment_account.payout_amount = payout_amount;
+    game.record_settled_reveal(true)?;
     // perform payout
     msg!(
         "Implicit host liquidity sufficient ({} >= {}). Proceeding with transfer.",
-        host_liquidity,
+        settlement.host_liquidity,
         payout_amount
     );
-    withdraw_from_treasury_to_player(
+    let transferred = withdraw_from_treasury_to_player(
         game,
-        &ctx.accounts.game_treasury,
-        &ctx.accounts.system_program,
-        &ctx.accounts.player,
-        payout_amount,
+        game_treasury,
+        system_program,
+        recipient,
+        transfer_amount,
     )?;
+    game.total_paid_out = game
+        .total_paid_out
+        .checked_add(transferred)
+        .ok_or(GameError::PayoutAmountOverflow)?;
+    if let Some(player_pnl) = player_pnl {
+        player_pnl.record_reveal(bet_amount, payout_amount)?;
+    }
 
-    msg!("Transferred payout {} to player {}. Bet marked as settled. Player should call CleanupBetCommitment to reclaim rent.", payout_amount, player);
-    Ok(())
+    // let an off-chain monitor know the host should top up
+    let remaining_host_liquidity = game_treasury
+        .lamports()
+        .saturating_sub(game.total_player_pot)
+        .saturating_sub(game.treasury_rent_reserve);
+    if remaining_host_liquidity < game.treasury_alert_threshold {
+        let avg_payout = game
+            .total_paid_out
+            .checked_div(game.revealed_bet_count)
+            .unwrap_or(0);
+        emit!(TreasuryBelowAlert {
+            host_liquidity: remaining_host_liquidity,
+            threshold: game.treasury_alert_threshold,
+            remaining_claims_at_avg_payout: remaining_host_liquidity
+                .checked_div(avg_payout)
+                .unwrap_or(0),
+        });
+    }
+
+    if highest_bet_bonus > 0 {
+        emit!(HighestBetBonusPaid {
+            game: game.key(),
+            player,
+            bet_amount,
+            bonus_lamports: highest_bet_bonus,
+        });
+    }
+
+    if held_payout > 0 {
+        msg!(
+            "Result is disputed. Held back {} of payout for player {} until the result is finalized.",
+            held_payout,
+            player
+        );
+    }
+    msg!("Transferred payout {} to player {}. Bet marked as settled. Player should call CleanupBetCommitment to reclaim rent.", transfer_amount, player);
+    Ok(payout_amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::default_test_game;
+
+    #[test]
+    fn reveal_cooldown_only_needs_player_pnl_when_configured() {
+        let mut game = default_test_game();
+        // players without a PnL account can reveal on the default config
+        enforce_reveal_cooldown(&game, None, 100).unwrap();
+
+        game.cooldown_between_reveals_slots = 10;
+        assert_eq!(
+            enforce_reveal_cooldown(&game, None, 100).err(),
+            Some(GameError::PlayerPnlRequired.into())
+        );
+        let mut player_pnl = PlayerPnL::default();
+        enforce_reveal_cooldown(&game, Some(&mut player_pnl), 100).unwrap();
+        assert_eq!(player_pnl.last_reveal_slot, 100);
+        assert_eq!(
+            enforce_reveal_cooldown(&game, Some(&mut player_pnl), 109).err(),
+            Some(GameError::RevealCooldownActive.into())
+        );
+        enforce_reveal_cooldown(&game, Some(&mut player_pnl), 110).unwrap();
+    }
 }
@@ -0,0 +1,22 @@
+use crate::current_clock;
+use crate::worst_case_remaining_payout_obligation;
+use crate::HeartbeatEvent;
+use crate::RevealDeadlineHeartbeat;
+use anchor_lang::prelude::*;
+
+pub fn reveal_deadline_heartbeat(ctx: Context<RevealDeadlineHeartbeat>) -> Result<()> {
+    let clock = current_clock()?;
+    let game = &ctx.accounts.game;
+    let remaining_seconds = game
+        .reveal_deadline
+        .unwrap_or(0)
+        .saturating_sub(clock.unix_timestamp);
+    emit!(HeartbeatEvent {
+        game: game.key(),
+        remaining_seconds,
+        unrevealed_bet_count: game.bet_count.saturating_sub(game.revealed_bet_count),
+        total_player_pot: game.total_player_pot,
+        worst_case_payout_obligation: worst_case_remaining_payout_obligation(game),
+    });
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use crate::cpi_helpers::release_anti_sybil_stake;
+use crate::current_clock;
+use crate::instructions::enforce_reveal_cooldown;
+use crate::instructions::forward_losing_stake;
+use crate::instructions::require_reveal_window;
+use crate::instructions::settle_reveal;
+use crate::instructions::verify_bet_merkle_proof;
+use crate::instructions::verify_reveal;
+use crate::RevealViaEscrow;
+use anchor_lang::prelude::*;
+
+pub fn reveal_via_escrow(
+    ctx: Context<RevealViaEscrow>,
+    bet_value: u8,
+    salt: u64,
+    merkle_proof: Vec<[u8; 32]>,
+    leaf_index: u32,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    let clock = current_clock()?;
+    require_reveal_window(
+        &accounts.game,
+        accounts.bet_escrow.bet.amount,
+        clock.unix_timestamp,
+    )?;
+    verify_bet_merkle_proof(
+        &accounts.game,
+        &accounts.bet_escrow.bet,
+        &merkle_proof,
+        leaf_index,
+    )?;
+    verify_reveal(&accounts.bet_escrow.bet, bet_value, salt)?;
+    msg!(
+        "Escrow {} revealed by owner {}, payout goes to beneficiary {}",
+        accounts.bet_escrow.escrow_id,
+        accounts.owner.key(),
+        accounts.beneficiary.key()
+    );
+    // the cooldown is on the owner, who sends the reveals. escrows don't get P&L tracking
+    enforce_reveal_cooldown(
+        &accounts.game,
+        accounts.owner_pnl.as_deref_mut(),
+        clock.slot,
+    )?;
+    settle_reveal(
+        &mut accounts.game,
+        &mut accounts.bet_escrow.bet,
+        &accounts.game_treasury,
+        &accounts.system_program,
+        &accounts.beneficiary.to_account_info(),
+        None,
+        accounts.payout_curve.as_deref(),
+        bet_value,
+        false,
+    )?;
+    forward_losing_stake(
+        &mut accounts.game,
+        &accounts.bet_escrow.bet,
+        &accounts.game_treasury,
+        &accounts.system_program,
+        accounts.burn_address.as_ref(),
+        bet_value,
+    )?;
+    // the stake came from the owner when the escrow was committed, so it goes back to them
+    let game_key = accounts.game.key();
+    release_anti_sybil_stake(
+        &game_key,
+        &mut accounts.bet_escrow.bet,
+        accounts.anti_sybil_vault.as_ref(),
+        ctx.bumps.anti_sybil_vault,
+        &accounts.system_program,
+        &accounts.owner,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::require_with_context;
+use crate::GameError;
+use crate::SetCallbackProgramWhitelist;
+use crate::MAX_CALLBACK_PROGRAMS;
+use anchor_lang::prelude::*;
+
+pub fn set_callback_program_whitelist(
+    ctx: Context<SetCallbackProgramWhitelist>,
+    whitelist: Vec<Pubkey>,
+) -> Result<()> {
+    require_with_context!(
+        whitelist.len() <= MAX_CALLBACK_PROGRAMS,
+        GameError::CallbackWhitelistTooLong,
+        expected = format!("at most {} programs", MAX_CALLBACK_PROGRAMS),
+        got = whitelist.len(),
+    );
+    let program_stats = &mut ctx.accounts.program_stats;
+    program_stats.callback_program_whitelist = [Pubkey::default(); MAX_CALLBACK_PROGRAMS];
+    program_stats.callback_program_whitelist[..whitelist.len()].copy_from_slice(&whitelist);
+    msg!(
+        "Callback program whitelist set by admin {}: {:?}",
+        program_stats.program_admin,
+        whitelist
+    );
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use crate::SetCharityMode;
+use anchor_lang::prelude::*;
+
+pub fn set_charity_mode(
+    ctx: Context<SetCharityMode>,
+    charity_wallet: Option<Pubkey>,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    game.is_charity_mode = charity_wallet.is_some();
+    game.charity_wallet = charity_wallet;
+    msg!("Charity mode set, losing stakes go to {:?}", charity_wallet);
+    Ok(())
+}
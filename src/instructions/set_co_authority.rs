@@ -0,0 +1,14 @@
+use crate::SetCoAuthority;
+use anchor_lang::prelude::*;
+
+pub fn set_co_authority(ctx: Context<SetCoAuthority>, co_authority: Option<Pubkey>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let old_co_authority = game.co_authority;
+    game.co_authority = co_authority;
+    msg!(
+        "Co-authority changed from {:?} to {:?}",
+        old_co_authority,
+        co_authority
+    );
+    Ok(())
+}
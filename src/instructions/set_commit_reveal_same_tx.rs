@@ -0,0 +1,11 @@
+use crate::SetCommitRevealSameTx;
+use anchor_lang::prelude::*;
+
+pub fn set_commit_reveal_same_tx(ctx: Context<SetCommitRevealSameTx>, enabled: bool) -> Result<()> {
+    ctx.accounts.game.commit_reveal_same_tx = enabled;
+    msg!(
+        "TEST GAME. Same transaction commit and reveal set to {}",
+        enabled
+    );
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use crate::GameError;
+use crate::SetPayoutCallback;
+use anchor_lang::prelude::*;
+
+pub fn set_payout_callback(
+    ctx: Context<SetPayoutCallback>,
+    callback_program: Option<Pubkey>,
+    callback_discriminant: [u8; 8],
+) -> Result<()> {
+    // only admin vetted programs, an arbitrary one could fail every reveal that pays out
+    if let Some(callback_program) = callback_program.as_ref() {
+        require!(
+            ctx.accounts
+                .program_stats
+                .is_callback_whitelisted(callback_program),
+            GameError::InvalidCallbackProgram
+        );
+    }
+    let game = &mut ctx.accounts.game;
+    game.callback_program = callback_program;
+    game.callback_discriminant = callback_discriminant;
+    msg!(
+        "Payout callback set to {:?} with discriminant {:?}",
+        callback_program,
+        callback_discriminant
+    );
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::GameError;
+use crate::PayoutCurveType;
+use crate::SetPayoutCurve;
+use anchor_lang::prelude::*;
+
+pub fn set_payout_curve(
+    ctx: Context<SetPayoutCurve>,
+    payout_curve_type: PayoutCurveType,
+    step_threshold: Option<u8>,
+    constant_multiplier: Option<u32>,
+) -> Result<()> {
+    // each curve takes exactly its own parameter, so a stale one can't be left behind on the game
+    let parameters_match = match payout_curve_type {
+        PayoutCurveType::ExponentialDecay | PayoutCurveType::LinearDecay => {
+            step_threshold.is_none() && constant_multiplier.is_none()
+        }
+        PayoutCurveType::StepFunction => {
+            step_threshold.is_some_and(|step_threshold| step_threshold <= 100)
+                && constant_multiplier.is_none()
+        }
+        PayoutCurveType::Constant => step_threshold.is_none() && constant_multiplier.is_some(),
+    };
+    require!(parameters_match, GameError::InvalidPayoutCurve);
+
+    let game = &mut ctx.accounts.game;
+    game.payout_curve_type = payout_curve_type;
+    game.step_threshold = step_threshold;
+    game.constant_multiplier = constant_multiplier;
+    msg!(
+        "Payout curve set to {:?}, step threshold {:?}, constant multiplier {:?}",
+        payout_curve_type,
+        step_threshold,
+        constant_multiplier
+    );
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::require_with_context;
+use crate::GameError;
+use crate::SetProgramParams;
+use anchor_lang::prelude::*;
+
+pub fn set_program_params(
+    ctx: Context<SetProgramParams>,
+    min_cooldown_seconds: i64,
+    max_games_per_authority: u8,
+) -> Result<()> {
+    require_with_context!(
+        min_cooldown_seconds >= 0,
+        GameError::InvalidCooldown,
+        expected = "non-negative cooldown",
+        got = min_cooldown_seconds,
+    );
+    let program_stats = &mut ctx.accounts.program_stats;
+    program_stats.min_cooldown_seconds = min_cooldown_seconds;
+    program_stats.max_games_per_authority = max_games_per_authority;
+    msg!(
+        "Program params updated by admin: {}. Min cooldown between games: {}s. Max games per authority: {}",
+        program_stats.program_admin,
+        min_cooldown_seconds,
+        max_games_per_authority
+    );
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use crate::instructions::record_result;
+use crate::require_with_context;
+use crate::GameError;
+use crate::SetTestResult;
+use anchor_lang::prelude::*;
+
+pub fn set_test_result(ctx: Context<SetTestResult>, result: u8) -> Result<()> {
+    require_with_context!(
+        result <= 100,
+        GameError::InvalidBetValue,
+        expected = "result between 0 and 100",
+        got = result,
+    );
+    msg!("TEST GAME. Setting result without deadline checks.");
+    // test results are never disputed
+    record_result(&mut ctx.accounts.game, result, 100)
+}
@@ -0,0 +1,27 @@
+use crate::SetTreasuryMultisig;
+use crate::TreasuryMultisigChanged;
+use anchor_lang::prelude::*;
+
+// the authority itself only changes through propose_authority_transfer and accept_authority_transfer,
+// so a mistyped key can never lock the host out
+pub fn set_treasury_multisig(
+    ctx: Context<SetTreasuryMultisig>,
+    new_treasury_multisig: Option<Pubkey>,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let old_treasury_multisig = game.treasury_multisig;
+    game.treasury_multisig = new_treasury_multisig;
+
+    emit!(TreasuryMultisigChanged {
+        game: game.key(),
+        authority: game.authority,
+        old_treasury_multisig,
+        new_treasury_multisig,
+    });
+    msg!(
+        "Treasury multisig changed from {:?} to {:?}",
+        old_treasury_multisig,
+        new_treasury_multisig
+    );
+    Ok(())
+}
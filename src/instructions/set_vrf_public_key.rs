@@ -0,0 +1,20 @@
+use crate::GameError;
+use crate::SetVrfPublicKey;
+use anchor_lang::prelude::*;
+
+pub fn set_vrf_public_key(
+    ctx: Context<SetVrfPublicKey>,
+    vrf_public_key: Option<Pubkey>,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    // whoever submits the result mustn't hold the key too, or they'd know the result for every bet count before betting closes
+    if let Some(vrf_public_key) = vrf_public_key.as_ref() {
+        require!(
+            !game.can_submit_result(vrf_public_key),
+            GameError::InvalidVrfPublicKey
+        );
+    }
+    game.vrf_public_key = vrf_public_key;
+    msg!("VRF key set to {:?}", vrf_public_key);
+    Ok(())
+}
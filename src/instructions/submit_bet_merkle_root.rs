@@ -0,0 +1,13 @@
+use crate::SubmitBetMerkleRoot;
+use anchor_lang::prelude::*;
+
+pub fn submit_bet_merkle_root(ctx: Context<SubmitBetMerkleRoot>, root: [u8; 32]) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    game.bet_merkle_root = Some(root);
+    msg!(
+        "Bet merkle root committed over {} bets: {:?}",
+        game.bet_count,
+        root
+    );
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::verify_decryption_share;
+use crate::GameError;
+use crate::SubmitDecryptionShare;
+use crate::DECRYPTION_SHARE_PROOF_LEN;
+use anchor_lang::prelude::*;
+use solana_curve25519::ristretto::{validate_ristretto, PodRistrettoPoint};
+
+// the proof ties the share to the keyholder's key share, so a bad share can't be stored and hold up
+// finalize_decryption, and one submission per keyholder is enough
+pub fn submit_decryption_share(
+    ctx: Context<SubmitDecryptionShare>,
+    share: [u8; 32],
+    proof: [u8; DECRYPTION_SHARE_PROOF_LEN],
+) -> Result<()> {
+    require!(
+        validate_ristretto(&PodRistrettoPoint(share)),
+        GameError::InvalidRistrettoPoint
+    );
+    let result_decryption = &mut ctx.accounts.result_decryption;
+    let index = result_decryption
+        .keyholder_index(ctx.accounts.keyholder.key)
+        .ok_or(GameError::NotAKeyholder)?;
+    require!(
+        !result_decryption.share_submitted[index],
+        GameError::DecryptionShareAlreadySubmitted
+    );
+    let ciphertext = result_decryption
+        .encrypted_result
+        .ok_or(GameError::EncryptedResultNotSubmitted)?;
+    let c1: [u8; 32] = ciphertext[..32]
+        .try_into()
+        .map_err(|_| GameError::InvalidRistrettoPoint)?;
+    require!(
+        verify_decryption_share(&result_decryption.key_shares[index], &c1, &share, &proof),
+        GameError::InvalidDecryptionShareProof
+    );
+    result_decryption.decryption_shares[index] = share;
+    result_decryption.share_submitted[index] = true;
+    msg!(
+        "Decryption share {} of {} submitted by keyholder {}",
+        result_decryption
+            .share_submitted
+            .iter()
+            .filter(|submitted| **submitted)
+            .count(),
+        result_decryption.keyholders.len(),
+        ctx.accounts.keyholder.key
+    );
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::BetPhase;
+use crate::GameError;
+use crate::SubmitEncryptedResult;
+use anchor_lang::prelude::*;
+use solana_curve25519::ristretto::{validate_ristretto, PodRistrettoPoint};
+
+pub fn submit_encrypted_result(
+    ctx: Context<SubmitEncryptedResult>,
+    ciphertext: [u8; 64],
+) -> Result<()> {
+    let (c1, c2) = ciphertext.split_at(32);
+    for half in [c1, c2] {
+        let point = PodRistrettoPoint(
+            half.try_into()
+                .map_err(|_| GameError::InvalidRistrettoPoint)?,
+        );
+        require!(validate_ristretto(&point), GameError::InvalidRistrettoPoint);
+    }
+    ctx.accounts.result_decryption.encrypted_result = Some(ciphertext);
+    // bets close now like any other submission, the keyholders decrypt it from here
+    ctx.accounts.game.set_phase(BetPhase::Closed);
+    msg!(
+        "Encrypted result submitted by {}, waiting on keyholder decryption shares",
+        ctx.accounts.signer.key
+    );
+    Ok(())
+}
@@ -1,20 +1,236 @@
+use crate::compute_result_hash;
+use crate::current_clock;
+use crate::ecvrf_verify;
+use crate::require_with_context;
+use crate::BetPhase;
+use crate::Game;
 use crate::GameError;
 use crate::SubmitResult;
-use crate::REVEAL_DEADLINE_TIMESTAMP;
+use crate::VrfResult;
+use crate::ECVRF_PROOF_LEN;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
 
-pub fn submit_results(ctx: Context<SubmitResult>, result: u8) -> Result<()> {
-    require!(result <= 100, GameError::InvalidBetValue);
+// Ed25519 program instruction data: u8 signature count, u8 padding, then per signature
+// 7 u16 offsets (signature, signature ix, pubkey, pubkey ix, message, message size, message ix)
+const ED25519_OFFSETS_START: usize = 2;
+// instruction index the Ed25519 program uses to mean "in this same instruction"
+const ED25519_SAME_INSTRUCTION: u16 = u16::MAX;
+
+pub fn submit_results(
+    ctx: Context<SubmitResult>,
+    result: u8,
+    result_confidence: u8,
+    result_signature: [u8; 64],
+    authority_salt: Option<u64>,
+) -> Result<()> {
+    require_with_context!(
+        result <= 100,
+        GameError::InvalidBetValue,
+        expected = "result between 0 and 100",
+        got = result,
+    );
+    require_with_context!(
+        result_confidence <= 100,
+        GameError::InvalidResultConfidence,
+        expected = "result confidence between 0 and 100",
+        got = result_confidence,
+    );
+    // players bet on the result being random, the authority can't swap in one of its own
+    require!(
+        ctx.accounts.game.vrf_public_key.is_none(),
+        GameError::VrfResultRequired
+    );
+    verify_ed25519_instruction(
+        &ctx.accounts.instructions_sysvar,
+        ctx.accounts.signer.key,
+        &result_signature,
+        &result_signature_message(&ctx.accounts.game.key(), result),
+        GameError::InvalidResultSignature,
+    )?;
     let game = &mut ctx.accounts.game;
+    if game.result_submission_hash != [0; 32] {
+        let opens_hash = authority_salt.is_some_and(|authority_salt| {
+            compute_result_hash(result, authority_salt) == game.result_submission_hash
+        });
+        require!(opens_hash, GameError::ResultHashMismatch);
+        msg!("Result matches the hash committed before betting");
+    }
+    // never overwritten, bets close on submission whether or not an oracle still has to confirm
+    game.host_result_signature = Some(result_signature);
+    msg!("Result signed by {}", ctx.accounts.signer.key);
+    record_or_propose_result(game, result, result_confidence)
+}
+
+// games with a result oracle hold the result until confirm_result, the rest record it straight away.
+// bets close either way, the authority knows the result now. if the oracle never confirms,
+// players reclaim_bet_on_timeout once the result deadline passes
+pub(crate) fn record_or_propose_result(
+    game: &mut Game,
+    result: u8,
+    result_confidence: u8,
+) -> Result<()> {
+    let Some(result_oracle) = game.result_oracle else {
+        return record_result(game, result, result_confidence);
+    };
+    game.set_phase(BetPhase::Closed);
+    game.pending_result = Some(result);
+    game.pending_result_confidence = result_confidence;
+    msg!(
+        "Result {} pending confirmation by result oracle {}",
+        result,
+        result_oracle
+    );
+    Ok(())
+}
+
+// a random result for games that aren't settled by an external event. the VRF key is pinned before any bets
+// and a key has one proof per input, so the submitter can't pick the result, only pass on the key's output
+pub fn submit_result_with_vrf(ctx: Context<SubmitResult>, vrf_result: VrfResult) -> Result<()> {
+    // a random result can't be known ahead of time, let alone committed to
+    require!(
+        ctx.accounts.game.result_submission_hash == [0; 32],
+        GameError::ResultPreCommitted
+    );
+    let game_key = ctx.accounts.game.key();
+    let vrf_public_key = ctx
+        .accounts
+        .game
+        .vrf_public_key
+        .ok_or(GameError::VrfNotEnabled)?;
+    require_keys_eq!(
+        Pubkey::new_from_array(vrf_result.public_key),
+        vrf_public_key,
+        GameError::InvalidVrfProof
+    );
+    let proof: &[u8; ECVRF_PROOF_LEN] = vrf_result
+        .proof
+        .as_slice()
+        .try_into()
+        .map_err(|_| GameError::InvalidVrfProof)?;
+    // input covers the final bet count so the output isn't fixed before betting closes
+    let vrf_input = vrf_input_message(&game_key, ctx.accounts.game.bet_count);
+    let beta = ecvrf_verify(&vrf_result.public_key, proof, &vrf_input)
+        .ok_or(GameError::InvalidVrfProof)?;
+    require!(vrf_result.beta == beta, GameError::InvalidVrfProof);
+
+    let result = vrf_result.beta[0] % 101;
+    msg!(
+        "VRF result {} from key {}, submitted by {}",
+        result,
+        vrf_public_key,
+        ctx.accounts.signer.key
+    );
+    // nothing for the host to be unsure about with a random result
+    record_or_propose_result(&mut ctx.accounts.game, result, 100)
+}
+
+// the message the authority or co-authority signs off chain for a result
+pub fn result_signature_message(game: &Pubkey, result: u8) -> [u8; 32] {
+    keccak::hashv(&[game.as_ref(), &[result]]).to_bytes()
+}
+
+// the message the VRF key signs for a game's random result
+pub fn vrf_input_message(game: &Pubkey, bet_count: u64) -> [u8; 32] {
+    keccak::hashv(&[game.as_ref(), b"vrf", &bet_count.to_le_bytes()]).to_bytes()
+}
+
+// programs can't run Ed25519 verification themselves, so the transaction carries an Ed25519 program
+// instruction right before this one, and we check it verified exactly this signer, signature and message
+fn verify_ed25519_instruction(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    signature: &[u8; 64],
+    message: &[u8],
+    error: GameError,
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let ed25519_index = current_index.checked_sub(1).ok_or(error)?;
+    let ed25519_ix = load_instruction_at_checked(ed25519_index as usize, instructions_sysvar)?;
+    require_keys_eq!(ed25519_ix.program_id, ed25519_program::ID, error);
+
+    let data = &ed25519_ix.data;
+    if data.first() != Some(&1) {
+        return Err(error.into());
+    }
+    let offset = |index: usize| -> Result<u16> {
+        let at = ED25519_OFFSETS_START + index * 2;
+        data.get(at..at + 2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+            .ok_or(error.into())
+    };
+    let slice = |start: u16, len: usize| -> Result<&[u8]> {
+        data.get(start as usize..start as usize + len)
+            .ok_or(error.into())
+    };
+    // everything has to live in the Ed25519 instruction itself, not point into some other instruction
+    if offset(1)? != ED25519_SAME_INSTRUCTION
+        || offset(3)? != ED25519_SAME_INSTRUCTION
+        || offset(6)? != ED25519_SAME_INSTRUCTION
+    {
+        return Err(error.into());
+    }
+    let verified = slice(offset(0)?, 64)? == signature.as_slice()
+        && slice(offset(2)?, 32)? == signer.as_ref()
+        && offset(5)? as usize == message.len()
+        && slice(offset(4)?, message.len())? == message;
+    if !verified {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+// shared with set_test_result, which skips the deadline checks on SubmitResult
+pub(crate) fn record_result(game: &mut Game, result: u8, result_confidence: u8) -> Result<()> {
+    let now = current_clock()?.unix_timestamp;
     game.result = Some(result);
-    game.is_open_for_bets = false;
-    game.is_open_for_reveals = true;
-    game.reveal_deadline = Some(REVEAL_DEADLINE_TIMESTAMP); // Set hardcoded reveal deadline
+    game.submit_result_timestamp = Some(now);
+    // gives the authority time to review the result before the first payout
+    game.reveal_opens_at = now.saturating_add(game.result_verification_delay_seconds as i64);
+    game.result_confidence = result_confidence;
+    game.result_lower = result.saturating_sub(game.result_uncertainty_range);
+    game.result_upper = result
+        .saturating_add(game.result_uncertainty_range)
+        .min(100);
+    // bets close either way, reveals may wait for open_reveal_period
+    game.set_phase(if game.reveal_opens_immediately {
+        BetPhase::Revealing
+    } else {
+        BetPhase::Closed
+    });
+    game.reveal_deadline = Some(game.scheduled_reveal_deadline);
+    // snapshot of who was in when the result locked, for post game accounting
+    game.bet_count_at_submission = game.bet_count;
+    game.total_player_pot_at_submission = game.total_player_pot;
     msg!(
-        "Result {} submitted by authority: {}. Hardcoded Reveal deadline: {}",
+        "Result {} ({} to {}) submitted for authority: {}. Reveal deadline: {}. Bets at submission: {}, player pot: {}",
         result,
+        game.result_lower,
+        game.result_upper,
         game.authority,
-        REVEAL_DEADLINE_TIMESTAMP
+        game.scheduled_reveal_deadline,
+        game.bet_count_at_submission,
+        game.total_player_pot_at_submission
     );
+
+    // host isn't sure about the result (e.g. disputed footage), give players extra time while it gets sorted out
+    if result_confidence < game.dispute_threshold {
+        game.is_disputed = true;
+        game.reveal_deadline = Some(
+            game.scheduled_reveal_deadline
+                .checked_add(game.dispute_grace_period_seconds)
+                .ok_or(GameError::Overflow)?,
+        );
+        msg!(
+            "Result confidence {} below dispute threshold {}. Result disputed, reveal deadline extended to {}",
+            result_confidence,
+            game.dispute_threshold,
+            game.reveal_deadline.unwrap_or_default()
+        );
+    }
     Ok(())
 }
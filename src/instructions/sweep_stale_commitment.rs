@@ -0,0 +1,31 @@
+use crate::cpi_helpers::release_anti_sybil_stake;
+use crate::StaleCommitmentSwept;
+use crate::SweepStaleCommitment;
+use anchor_lang::prelude::*;
+
+pub fn sweep_stale_commitment(ctx: Context<SweepStaleCommitment>, player: Pubkey) -> Result<()> {
+    let game_key = ctx.accounts.game.key();
+    // the bet itself is left alone, clawback_unrevealed_bet deals with the stake in the treasury
+    let amount = release_anti_sybil_stake(
+        &game_key,
+        &mut ctx.accounts.bet_commitment,
+        Some(&ctx.accounts.anti_sybil_vault),
+        Some(ctx.bumps.anti_sybil_vault),
+        &ctx.accounts.system_program,
+        &ctx.accounts.sweeper,
+    )?;
+
+    emit!(StaleCommitmentSwept {
+        game: game_key,
+        player,
+        sweeper: ctx.accounts.sweeper.key(),
+        amount,
+    });
+    msg!(
+        "Swept {} lamports of anti sybil stake from {}'s unrevealed bet to {}",
+        amount,
+        player,
+        ctx.accounts.sweeper.key()
+    );
+    Ok(())
+}
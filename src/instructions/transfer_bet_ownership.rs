@@ -0,0 +1,37 @@
+use crate::current_clock;
+use crate::AuditEntry;
+use crate::AuditInstruction;
+use crate::BetOwnershipTransferred;
+use crate::TransferBetOwnership;
+use anchor_lang::prelude::*;
+
+pub fn transfer_bet_ownership(ctx: Context<TransferBetOwnership>, new_owner: Pubkey) -> Result<()> {
+    let clock = current_clock()?;
+    let bet_commitment = &mut ctx.accounts.bet_commitment;
+    let old_owner = bet_commitment.player;
+    // the PDA stays derived from original_player, only who can reveal and gets paid changes
+    bet_commitment.player = new_owner;
+
+    ctx.accounts.game.record_audit(
+        ctx.accounts.audit_log.as_mut(),
+        AuditEntry::new(
+            AuditInstruction::TransferOwnership,
+            old_owner,
+            0,
+            clock.unix_timestamp,
+        ),
+    )?;
+    emit!(BetOwnershipTransferred {
+        game: ctx.accounts.game.key(),
+        bet_commitment: bet_commitment.key(),
+        old_owner,
+        new_owner,
+    });
+    msg!(
+        "Bet {} transferred from {} to {}",
+        bet_commitment.key(),
+        old_owner,
+        new_owner
+    );
+    Ok(())
+}
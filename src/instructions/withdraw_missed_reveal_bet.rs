@@ -0,0 +1,62 @@
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::current_clock;
+use crate::instructions::verify_reveal;
+use crate::AuditEntry;
+use crate::AuditInstruction;
+use crate::GameError;
+use crate::WithdrawMissedRevealBet;
+use anchor_lang::prelude::*;
+
+pub fn withdraw_missed_reveal_bet(
+    ctx: Context<WithdrawMissedRevealBet>,
+    bet_value: u8,
+    salt: u64,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let commitment = &mut ctx.accounts.bet_commitment;
+    let player = *ctx.accounts.player.key;
+    verify_reveal(commitment, bet_value, salt)?;
+    // otherwise skipping the reveal would be a free way out of a losing bet
+    require!(
+        game.result_difference(bet_value).is_some(),
+        GameError::MissedRevealBetLost
+    );
+
+    // only the stake comes back, winnings were for revealing in time. the anti sybil stake stays
+    // forfeit for sweep_stale_commitment
+    let refund_amount = commitment.amount;
+    game.total_player_pot = game
+        .total_player_pot
+        .checked_sub(refund_amount)
+        .ok_or(GameError::PlayerPotUnderflow)?;
+    commitment.is_claimed = true;
+    game.record_settled_reveal(false)?;
+    commitment.revealed_bet_value = Some(bet_value);
+
+    let transferred = withdraw_from_treasury_to_player(
+        game,
+        &ctx.accounts.game_treasury,
+        &ctx.accounts.system_program,
+        &ctx.accounts.player,
+        refund_amount,
+    )?;
+    game.total_paid_out = game
+        .total_paid_out
+        .checked_add(transferred)
+        .ok_or(GameError::PayoutAmountOverflow)?;
+    game.record_audit(
+        ctx.accounts.audit_log.as_mut(),
+        AuditEntry::new(
+            AuditInstruction::WithdrawMissedReveal,
+            player,
+            -(transferred as i64),
+            current_clock()?.unix_timestamp,
+        ),
+    )?;
+    msg!(
+        "Player {} missed the reveal window, returned their bet of {} lamports",
+        player,
+        refund_amount
+    );
+    Ok(())
+}
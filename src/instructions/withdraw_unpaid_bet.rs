@@ -1,4 +1,8 @@
-use crate::withdraw_from_treasury_to_player;
+use crate::cpi_helpers::withdraw_from_treasury_to_player;
+use crate::current_clock;
+use crate::require_with_context;
+use crate::AuditEntry;
+use crate::AuditInstruction;
 use crate::GameError;
 use crate::WithdrawUnpaidBet;
 use anchor_lang::prelude::*;
@@ -8,34 +12,178 @@ pub fn withdraw_unpaid_bet(ctx: Context<WithdrawUnpaidBet>) -> Result<()> {
     let commitment = &mut ctx.accounts.bet_commitment;
     let player = *ctx.accounts.player.key;
 
-    let reclaim_amount = commitment.amount;
+    // only between the reveal deadline and the final claim deadline
+    let now = current_clock()?.unix_timestamp;
+    let reveal_deadline = game.reveal_deadline.ok_or(GameError::DeadlineNotSet)?;
+    let final_claim_deadline = game.final_claim_deadline.ok_or(GameError::DeadlineNotSet)?;
+    require_with_context!(
+        reveal_deadline < now && now < final_claim_deadline,
+        GameError::WithdrawPeriodNotReached,
+        expected = format!(
+            "withdraw after {} and before {}",
+            reveal_deadline, final_claim_deadline
+        ),
+        got = now,
+    );
+    // sanity check, total_player_pot should always hold every unsettled stake
+    require_with_context!(
+        game.total_player_pot >= commitment.amount,
+        GameError::InsufficientPlayerPot,
+        expected = format!("player pot at least {} lamports", commitment.amount),
+        got = game.total_player_pot,
+    );
+
     let treasury_balance = ctx.accounts.game_treasury.to_account_info().lamports();
+    // if the host has since funded enough to cover the payout, pay the full winnings instead of just the stake.
+    // same liquidity check as reveal_and_claim, skipped while disputed since none of it would be held back here
+    let host_liquidity = treasury_balance
+        .saturating_sub(game.total_player_pot)
+        .saturating_sub(game.treasury_rent_reserve);
+    let pays_full_payout = !game.is_disputed
+        && commitment.pending_payout_amount > 0
+        && commitment.pending_payout_amount <= host_liquidity;
+    let reclaim_amount = if pays_full_payout {
+        commitment.pending_payout_amount
+    } else {
+        commitment.amount
+    };
     // Check if player's original bet amount is still in the treasury
-    require!(
+    require_with_context!(
         treasury_balance >= reclaim_amount,
-        GameError::InsufficientTreasuryForReclaim
+        GameError::InsufficientTreasuryForReclaim,
+        expected = format!("treasury balance at least {} lamports", reclaim_amount),
+        got = treasury_balance,
     );
 
     // updating total_player_pot to reflect the payout, decrementing initial stake so remaining comes out of host's liquidity
     game.total_player_pot = game
         .total_player_pot
-        .checked_sub(reclaim_amount)
-        .ok_or(GameError::TotalPayoutPotDesynced)?;
+        .checked_sub(commitment.amount)
+        .ok_or(GameError::PlayerPotUnderflow)?;
 
     commitment.is_claimed = true;
+    game.record_settled_reveal(pays_full_payout)?;
+    if pays_full_payout {
+        commitment.payout_amount = reclaim_amount;
+    }
+    commitment.pending_payout_amount = 0;
 
-    // Transfer original bet back to player
-    withdraw_from_treasury_to_player(
+    // Transfer the payout, or the original bet if the host still can't cover it, back to player
+    let transferred = withdraw_from_treasury_to_player(
         game,
         &ctx.accounts.game_treasury,
         &ctx.accounts.system_program,
         &ctx.accounts.player,
         reclaim_amount,
     )?;
-    msg!(
-        "Host lacked liquidity. Withdrew original bet {} lamports for player {}.",
-        reclaim_amount,
-        player
-    );
+    game.total_paid_out = game
+        .total_paid_out
+        .checked_add(transferred)
+        .ok_or(GameError::PayoutAmountOverflow)?;
+    game.record_audit(
+        ctx.accounts.audit_log.as_mut(),
+        AuditEntry::new(
+            AuditInstruction::WithdrawUnpaid,
+            player,
+            -(transferred as i64),
+            now,
+        ),
+    )?;
+    if pays_full_payout {
+        msg!(
+            "Host has since funded the payout. Withdrew full payout {} lamports for player {}.",
+            reclaim_amount,
+            player
+        );
+    } else {
+        msg!(
+            "Host lacked liquidity. Withdrew original bet {} lamports for player {}.",
+            reclaim_amount,
+            player
+        );
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::reveal_and_claim::settle_reveal;
+    use crate::test_fixtures::{
+        default_test_game, empty_bet_commitment, set_test_clock, test_bet_commitment,
+        test_clock_sysvar, test_game, test_signer, test_system_program, test_treasury,
+    };
+    use crate::PayoutCurveType;
+    use crate::WithdrawUnpaidBetBumps;
+    use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+
+    #[test]
+    fn withdraw_after_critical_threshold_reveal_takes_the_stake_out_of_the_pot_once() {
+        let stake = LAMPORTS_PER_SOL;
+        let other_stakes = 2 * LAMPORTS_PER_SOL;
+        let mut game = default_test_game();
+        // 50 off on the linear curve pays half the stake back
+        game.payout_curve_type = PayoutCurveType::LinearDecay;
+        game.result = Some(60);
+        game.result_lower = 60;
+        game.result_upper = 60;
+        game.reveal_deadline = Some(100);
+        game.scheduled_final_claim_deadline = 1_000;
+        game.total_player_pot = stake + other_stakes;
+        game.treasury_rent_reserve = 1_000;
+        game.treasury_critical_threshold = 10 * LAMPORTS_PER_SOL;
+        let mut game = test_game(game);
+        let treasury = test_treasury(
+            &game.key(),
+            game.total_player_pot + game.treasury_rent_reserve + LAMPORTS_PER_SOL / 10,
+        );
+        let player = test_signer(0);
+        let mut bet_commitment = empty_bet_commitment();
+        bet_commitment.game = game.key();
+        bet_commitment.player = player.key();
+        bet_commitment.amount = stake;
+
+        set_test_clock(0, 50);
+        let paid = settle_reveal(
+            &mut game,
+            &mut bet_commitment,
+            &treasury,
+            &test_system_program(),
+            &player.to_account_info(),
+            None,
+            None,
+            10,
+            false,
+        )
+        .unwrap();
+        assert_eq!(paid, 0);
+        assert!(bet_commitment.attempted_reveal);
+        assert_eq!(bet_commitment.pending_payout_amount, stake / 2);
+        // the unpaid reveal leaves its stake in the pot for the withdrawal
+        assert_eq!(game.total_player_pot, stake + other_stakes);
+
+        set_test_clock(0, 200);
+        let treasury_before = treasury.lamports();
+        let mut accounts = WithdrawUnpaidBet {
+            game,
+            bet_commitment: test_bet_commitment(bet_commitment),
+            game_treasury: treasury,
+            player,
+            system_program: test_system_program(),
+            clock: test_clock_sysvar(),
+            audit_log: None,
+        };
+        withdraw_unpaid_bet(Context::new(
+            &crate::ID,
+            &mut accounts,
+            &[],
+            WithdrawUnpaidBetBumps::default(),
+        ))
+        .unwrap();
+        // host still can't cover the payout, the stake comes back and leaves the pot exactly once
+        assert_eq!(accounts.game.total_player_pot, other_stakes);
+        assert_eq!(accounts.player.lamports(), stake);
+        assert_eq!(accounts.game_treasury.lamports(), treasury_before - stake);
+        assert!(accounts.bet_commitment.is_claimed);
+    }
+}
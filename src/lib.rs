@@ -1,24 +1,242 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::system_instruction;
-use anchor_lang::solana_program::program::invoke_signed;
 use anchor_lang::solana_program::sysvar::clock::Clock;
 use anchor_lang::solana_program::sysvar;
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::incinerator;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::Metadata;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use solana_curve25519::ristretto::{
+    add_ristretto, multiply_ristretto, subtract_ristretto, validate_ristretto, PodRistrettoPoint,
+};
+use solana_curve25519::edwards::{multiply_edwards, subtract_edwards, validate_edwards, PodEdwardsPoint};
+use solana_curve25519::scalar::PodScalar;
+use sha2::{Digest, Sha512};
+pub mod cpi_helpers;
 pub mod instructions;
+#[cfg(test)]
+mod test_fixtures;
 
 declare_id!("FFbz83mccNiYLeUSK1GQBp17ezHp6H6jCKMKtYiGXgXV");
 
 // --- Hardcoded Constants ---
 pub const GLOBAL_GAME_SEED: &[u8] = b"ADRIAN_NUGGETS_MINECRAFT_MOVIE";
+// bump when the Game layout changes (GAME_ACCOUNT_LEN moving is the reminder) and keep the old layout as a
+// GameSnapshot variant so migrate_game can read it
+pub const GAME_VERSION: u8 = 2;
+// commitment scheme new bets are written with, each bet keeps the version it was committed under.
+// 1 is keccak(bet_value || salt), 2 prefixes COMMITMENT_DOMAIN_V2
+pub const COMMITMENT_VERSION: u8 = 1;
+pub const COMMITMENT_DOMAIN_V2: &[u8] = b"mcnuggies-wager-protocol:bet-commitment:v2";
 pub const GAME_AUTHORITY_PUBKEY: &str = "JDUcdJdTH8j352LvXhWbDKPb7WzTWH8VkfwXeBX2NT7U";
+pub const PROGRAM_STATS_SEED: &[u8] = b"program_stats";
+pub const HISTORY_SEED: &[u8] = b"history";
+pub const HISTORY_CAPACITY: usize = 10; // number of closed games kept in the results feed
+pub const PLAYER_PNL_SEED: &[u8] = b"player_pnl";
+pub const ANOMALY_SEED: &[u8] = b"anomaly";
+pub const ESCROW_SEED: &[u8] = b"escrow";
+pub const LEADERBOARD_SEED: &[u8] = b"leaderboard";
+pub const DISTRIBUTION_SEED: &[u8] = b"distribution";
+pub const RECEIPT_SEED: &[u8] = b"receipt";
+pub const AUDIT_SEED: &[u8] = b"audit";
+pub const UNIQUE_COMMITMENT_SEED: &[u8] = b"unique_commitment";
+pub const AUDIT_LOG_CAPACITY: usize = 8; // latest changes kept per bet
+pub const MAX_BATCH_FREEZE_COMMITMENTS: usize = 20; // players per batch_freeze_commitments call
+pub const TROPHY_SEED: &[u8] = b"trophy";
+pub const REFERRAL_CODE_SEED: &[u8] = b"refcode";
+pub const REPUTATION_SEED: &[u8] = b"reputation";
+pub const INSURANCE_FUND_SEED: &[u8] = b"insurance_fund";
+pub const PAYOUT_CURVE_SEED: &[u8] = b"curve";
+pub const ANTI_SYBIL_VAULT_SEED: &[u8] = b"anti_sybil_vault";
+pub const GLOBAL_JACKPOT_SEED: &[u8] = b"global_jackpot";
+pub const RESULT_DECRYPTION_SEED: &[u8] = b"decryption";
+// hidden result games split the ElGamal key between this many keyholders, any HIDDEN_RESULT_THRESHOLD of them decrypt
+pub const HIDDEN_RESULT_KEYHOLDERS: usize = 5;
+pub const HIDDEN_RESULT_THRESHOLD: usize = 3;
+// every Lagrange coefficient for 3 of keyholders 1 to 5 is a whole multiple of 1/24, so decryption works on 24x
+// the points and only needs small integer scalars. has to be redone if the quorum changes
+const LAGRANGE_SCALE: i64 = 24;
+const _: () = assert!(HIDDEN_RESULT_KEYHOLDERS == 5 && HIDDEN_RESULT_THRESHOLD == 3);
+// compressed Ristretto basepoint, hidden results are encrypted as result * G
+const RISTRETTO_BASEPOINT: [u8; 32] = [
+    0xe2, 0xf2, 0xae, 0x0a, 0x6a, 0xbc, 0x4e, 0x71, 0xa8, 0x84, 0xa9, 0x61, 0xc5, 0x00, 0x51, 0x5f,
+    0x58, 0xe3, 0x0b, 0x6a, 0xa5, 0x82, 0xdd, 0x8d, 0xb6, 0xa6, 0x59, 0x45, 0xe0, 0x8d, 0x2d, 0x76,
+];
+// reveals a player has had go unpaid for host liquidity before they can claim insurance
+pub const INSURANCE_MIN_UNPAID_REVEALS: u32 = 2;
+pub const REFERRAL_CODE_LENGTH: usize = 8; // ascii letters and digits
+
+// --- Trophy Constants ---
+// lets the authority mint a trophy NFT to players whose payout beat their stake, off by default
+pub const MINT_WINNER_NFT: bool = false;
+// every bet has to create a CommitmentMarker at its commitment hash, so a commitment can only be used once.
+// stops copying another player's commitment blind and revealing it once theirs is revealed
+pub const UNIQUE_COMMITMENTS: bool = false;
+pub const TROPHY_SYMBOL: &str = "NUGGIE";
+// off-chain metadata endpoint, the trophy details are passed as query params
+pub const TROPHY_METADATA_URI: &str = "https://mcnuggies.xyz/trophy";
+pub const PROOF_OF_BET_SEED: &[u8] = b"proof_of_bet";
+// proof of bet metadata, game and bet index are passed as query params. never the bet value
+pub const PROOF_OF_BET_METADATA_URI: &str = "https://mcnuggies.xyz/bet";
+pub const LEADERBOARD_CAPACITY: usize = 10; // biggest payouts kept per game
+// same key as the game authority for now, swap this out if the admin ever needs to be someone else
+pub const PROGRAM_ADMIN_PUBKEY: &str = "JDUcdJdTH8j352LvXhWbDKPb7WzTWH8VkfwXeBX2NT7U";
+pub const DEFAULT_MIN_COOLDOWN_SECONDS: i64 = 86400; // 1 day between a game closing and the next one starting
+pub const AUTHORITY_GAMES_SEED: &[u8] = b"authority_games";
+// open games one authority can have at once, the admin can raise it with set_program_params
+pub const DEFAULT_MAX_GAMES_PER_AUTHORITY: u8 = 1;
+// programs the admin has vetted as payout callbacks, a callback that fails would block reveals
+pub const MAX_CALLBACK_PROGRAMS: usize = 4;
+// anyone can close a game this long after its final claim deadline
+pub const STALE_GAME_SECONDS: i64 = 365 * 86400; // 1 year
+// authority can clawback bets nobody revealed this long after the last player deadline
+pub const CLAWBACK_DELAY_SECONDS: i64 = 30 * 86400; // 30 days
+// deposit commit_bet takes on top of the stake, refunded on reveal or timeout and otherwise swept by
+// sweep_stale_commitment as a bounty. 0 disables it
+pub const ANTI_SYBIL_STAKE_LAMPORTS: u64 = 0;
+// the vault is a plain system account, every deposit has to keep it rent exempt on its own
+pub const MIN_ANTI_SYBIL_STAKE_LAMPORTS: u64 = 890_880;
+const _: () = assert!(!matches!(ANTI_SYBIL_STAKE_LAMPORTS, 1..MIN_ANTI_SYBIL_STAKE_LAMPORTS));
+// leftover treasury of a stale game goes here rather than to whoever closed it, so closing isn't profitable
+// same key as the game authority for now
+pub const PROTOCOL_DEAD_LETTER_PUBKEY: &str = "JDUcdJdTH8j352LvXhWbDKPb7WzTWH8VkfwXeBX2NT7U";
+// Pyth SOL/USD price account (mainnet), commits can pass it to record a display USD value for the stake
+pub const PYTH_SOL_USD_PRICE_FEED: &str = "H6ARHf6YXhGYeQfUzQNGk6rDNnLBQKrenN712K4AQJEG";
 
 // ENSURE THESE ARE SET BEFORE GOING LIVE, IT SHOULD BE IN ORDER, 
 // OTHERWISE THE GAME WILL NOT WORK!!!!
 pub const SUBMISSION_DEADLINE_TIMESTAMP: i64 = 1745193599; // Sunday, 20th April 2025 11:59 PM GMT (or 9:59 AM AEDT Monday)
 pub const REVEAL_DEADLINE_TIMESTAMP: i64 = 1745798399; // Sunday, 27th April 2025 11:59 PM GMT (or 9:59 AM AEDT Sunday)
 pub const FINAL_CLAIM_DEADLINE_TIMESTAMP: i64 = 1746403199; // Sunday, 4th May 2025 11:59 PM GMT (or 9:59 AM AEDT Monday)
+// commits this close to the submission deadline log a WarningDeadlineImminent line for UIs watching the logs
+pub const SUBMISSION_DEADLINE_GRACE_ALERT_SECONDS: i64 = 300; // 5 minutes
+
+// --- Dispute Constants ---
+// results submitted with confidence below this are disputed
+pub const DEFAULT_DISPUTE_THRESHOLD: u8 = 80;
+pub const DISPUTE_GRACE_PERIOD_SECONDS: i64 = 259200; // 3 days added to the reveal deadline while disputed
+// percent of each payout held back until the dispute is finalized
+pub const DISPUTED_PAYOUT_HELD_PERCENT: u64 = 10;
+// how far either side of the submitted result still counts as the result, for counts that are hard to call
+// (49 or 51 nuggets?). bets inside the range get the top multiplier. 0 means the result is exact
+pub const RESULT_UNCERTAINTY_RANGE: u8 = 0;
+
+// --- Reveal Extension Constants ---
+// if too few players have revealed by the reveal deadline (e.g. reveal UX broke), anyone can extend it once
+pub const AUTO_EXTENSION_ENABLED: bool = true;
+pub const MIN_REVEAL_PCT_FOR_NO_EXTENSION: u8 = 50;
+pub const REVEAL_EXTENSION_SECONDS: i64 = 172800; // 2 days
+pub const MAX_REVEAL_EXTENSION_SECONDS: i64 = 604800; // 7 days
+const _: () = assert!(REVEAL_EXTENSION_SECONDS <= MAX_REVEAL_EXTENSION_SECONDS);
+// hard cap on pushing out the final claim deadline, so players waiting on withdraw_unpaid_bet aren't delayed forever
+pub const MAX_FINAL_CLAIM_DEADLINE_EXTENSIONS: u8 = 3;
+// buffer after the submission deadline where the authority can still submit and players can't reclaim yet,
+// covers a result script running a little late
+pub const GRACE_PERIOD_SECONDS: u64 = 60;
+pub const MAX_GRACE_PERIOD_SECONDS: u64 = 3600; // 1 hour
+const _: () = assert!(GRACE_PERIOD_SECONDS <= MAX_GRACE_PERIOD_SECONDS);
+// delay between proposing and executing an emergency withdrawal, gives players time to see it coming
+pub const EMERGENCY_WITHDRAW_TIMELOCK_SECONDS: i64 = 86400; // 24 hours
+// how long a proposed authority has to accept before the proposal lapses
+pub const AUTHORITY_TRANSFER_TIMEOUT_SECONDS: i64 = 172800; // 48 hours
+
+// --- Risk Constants ---
+pub const BPS_DENOMINATOR: u64 = 10_000;
+// player pot can't grow past this share of the treasury, so the host has to fund it before bets come in
+pub const MAX_TREASURY_EXPOSURE_BPS: u16 = 5000; // 50%
+// host liquidity under this after a payout emits TreasuryBelowAlert so a monitor can ping the host to top up
+pub const TREASURY_ALERT_THRESHOLD: u64 = LAMPORTS_PER_SOL;
+// host liquidity under this stops payouts entirely, 0 disables it. winners revealing below it are left owed
+// like when liquidity runs out, so they can still withdraw_unpaid_bet
+pub const TREASURY_CRITICAL_THRESHOLD: u64 = 0;
+// other side of the alert threshold, host liquidity over the player pot plus this share of it is over-funding
+// that rebalance_excess can take back once reveals close. 0 disables it
+pub const TREASURY_MIN_UTILIZATION_BPS: u16 = 0;
+// share of every bet paid into the GlobalJackpot on top of the stake, won by the first exact guess
+// across all games each jackpot epoch. 0 disables contributions
+pub const PROGRESSIVE_JACKPOT_CONTRIBUTION_BPS: u16 = 0;
+// lets the authority early_close_betting once total_player_pot reaches EARLY_CLOSE_MIN_POT instead of
+// waiting for submission_deadline, so late bets stop diluting the ones already in
+pub const EARLY_CLOSE_ENABLED: bool = false;
+pub const EARLY_CLOSE_MIN_POT: u64 = 0;
+// betting closes itself on the bet that reaches this many, so there's no last second rush for the deadline.
+// None for no cap
+pub const MAX_BET_COUNT: Option<u64> = None;
+// host liquidity has to cover this share (in basis points) of the worst case payout after every new bet,
+// so a host can't take bets it could never pay. 10000 is the whole worst case, 0 turns the check off
+pub const MINIMUM_HOST_LIQUIDITY_RATIO: u16 = 10_000;
+// set_commit_reveal_same_tx only exists with test-helpers, so this keeps game.commit_reveal_same_tx false on mainnet
+const _: () = assert!(!(cfg!(feature = "mainnet") && cfg!(feature = "test-helpers")));
+// winnings revealed after LATE_REVEAL_THRESHOLD_PCT of the window between the result and the scheduled reveal deadline
+// lose this share, so waiting to see how everyone else did costs something. 0 disables it
+pub const LATE_REVEAL_PENALTY_BPS: u16 = 0;
+pub const LATE_REVEAL_THRESHOLD_PCT: u8 = 90;
+const _: () = assert!(LATE_REVEAL_THRESHOLD_PCT <= 100 && LATE_REVEAL_PENALTY_BPS as u64 <= BPS_DENOMINATOR);
+// winnings vest in these tranches, each unlocking bps_unlocked of the payout at unlock_timestamp.
+// reveal_and_claim pays what's unlocked and claim_next_tranche the rest as it unlocks.
+// all zeroes pays winnings in full straight away, otherwise the bps have to add up to BPS_DENOMINATOR
+pub const PAYOUT_UNLOCK_TRANCHES: usize = 5;
+pub const PAYOUT_UNLOCK_SCHEDULE: [PayoutTranche; PAYOUT_UNLOCK_TRANCHES] =
+    [PayoutTranche { unlock_timestamp: 0, bps_unlocked: 0 }; PAYOUT_UNLOCK_TRANCHES];
+const _: () = assert!(matches!(unlock_schedule_total_bps(&PAYOUT_UNLOCK_SCHEDULE), 0 | BPS_DENOMINATOR));
+// the host can drain the treasury after the final claim deadline, so everything has to unlock before it
+const _: () = assert!(last_unlock_timestamp(&PAYOUT_UNLOCK_SCHEDULE) < FINAL_CLAIM_DEADLINE_TIMESTAMP);
+
+pub(crate) const fn unlock_schedule_total_bps(schedule: &[PayoutTranche; PAYOUT_UNLOCK_TRANCHES]) -> u64 {
+    let mut total = 0;
+    let mut i = 0;
+    while i < PAYOUT_UNLOCK_TRANCHES {
+        total += schedule[i].bps_unlocked as u64;
+        i += 1;
+    }
+    total
+}
+
+const fn last_unlock_timestamp(schedule: &[PayoutTranche; PAYOUT_UNLOCK_TRANCHES]) -> i64 {
+    let mut last = 0;
+    let mut i = 0;
+    while i < PAYOUT_UNLOCK_TRANCHES {
+        if schedule[i].unlock_timestamp > last {
+            last = schedule[i].unlock_timestamp;
+        }
+        i += 1;
+    }
+    last
+}
+// most claim_remaining_treasury sends in one call, a leaked authority key then has to drain it call by call.
+// u64::MAX leaves it uncapped
+pub const MAX_TREASURY_WITHDRAWAL_PER_TX: u64 = u64::MAX;
+// bets committed in the first 25% of the betting window get this much extra on a winning payout
+pub const EARLY_BIRD_BONUS_BPS: u16 = 500; // 5%
+// flat bonus for the single biggest bet if it guesses the result exactly, off by default
+pub const REWARD_HIGHEST_BET: bool = false;
+pub const HIGHEST_BET_BONUS_LAMPORTS: u64 = LAMPORTS_PER_SOL / 10;
+// consolation prize for the losing bet furthest above the result, paid from host liquidity by
+// claim_biggest_loser_prize after the reveal deadline. 0 disables it
+pub const BIGGEST_LOSER_PRIZE_LAMPORTS: u64 = 0;
+// slots a player has to wait between their own reveals so others can interleave with a batch of reveals.
+// the last reveal slot lives on PlayerPnL, so a cooldown makes every reveal pass one. 0 disables it
+pub const COOLDOWN_BETWEEN_REVEALS_SLOTS: u8 = 0;
+// winning payouts under this many lamports are treated as a loss and the stake stays with the host,
+// saves paying out dust that's barely worth the transaction fee. 0 disables it
+pub const MIN_PAYOUT_THRESHOLD_LAMPORTS: u64 = 0;
+// keep a CommitmentAuditLog per bet for deployments that need an audit trail, costs players the extra rent
+pub const AUDIT_LOG_ENABLED: bool = false;
+// false holds reveals after submit_result until the authority calls open_reveal_period,
+// gives players a window to get ready before the reveal rush
+pub const REVEAL_OPENS_IMMEDIATELY: bool = true;
+// seconds after the result is recorded before anyone can reveal, so the authority can look over it
+// for disputes before payouts go out. 0 lets reveals start straight away
+pub const RESULT_VERIFICATION_DELAY_SECONDS: u32 = 0;
+// send the stake of a bet over the result to BURN_ADDRESS instead of leaving it with the host.
+// the host then funds every payout out of their own liquidity
+pub const BURN_LOSING_BETS: bool = false;
+// lamports sent to the incinerator are burned at the end of the slot
+pub const BURN_ADDRESS: Pubkey = incinerator::ID;
 
 // --- Payout Curve Constants ---
-// Multiplier M(x) = 3.9 * exp(-0.1 * x) + 0.1 where x = result - guess
+// Multiplier M(x) = 3.9 * exp(-0.14 * x) + 0.1 where x = result - guess
 // We use a scaling factor to represent the multiplier as an integer
 pub const PAYOUT_SCALE: u32 = 1_000_000; // 6 decimal places precision
 
@@ -38,48 +256,805 @@ pub const PAYOUT_MULTIPLIER_LUT: [u32; 101] = [
     100_004, 100_004, 100_003,
 ];
 
-pub fn withdraw_from_treasury_to_player<'info>(
-    game: &Account<'info,Game>, 
-    game_treasury: &SystemAccount<'info>,
-    system_program: &Program<'info, System>,
-    player: &Signer<'info>, 
-    amount: u64, 
-) -> Result<()> {
-    let game_key = game.key();
-    let seeds = &[
-        b"treasury".as_ref(),
-        game_key.as_ref(),
-        &[game.treasury_bump],
-    ];
-    let signer_seeds = &[&seeds[..]];
-    invoke_signed(
-        &system_instruction::transfer(game_treasury.key, player.key, amount),
-        &[
-            game_treasury.to_account_info(),
-            player.to_account_info(),
-            system_program.to_account_info(),
-        ],
-        signer_seeds,
+// curve reveal_and_claim pays out on, the others take their parameters from set_payout_curve
+pub const PAYOUT_CURVE_TYPE: PayoutCurveType = PayoutCurveType::ExponentialDecay;
+
+// fixed point scale for compute_lut_entry, fine enough that 100 multiplies don't drift a rounded entry
+const LUT_FIXED_POINT_SCALE: u128 = 1_000_000_000_000_000_000;
+
+// round((3.9 * exp(-0.14 * x) + 0.1) * PAYOUT_SCALE) in integer math so it can run at compile time.
+// exp(0.14) from its taylor series, inverted, then raised to x by repeated multiplication
+pub const fn compute_lut_entry(x: u32) -> u64 {
+    let mut exp_pos = LUT_FIXED_POINT_SCALE;
+    let mut term = LUT_FIXED_POINT_SCALE;
+    let mut k = 1;
+    while term > 0 {
+        term = term * 14 / (100 * k);
+        exp_pos += term;
+        k += 1;
+    }
+    let decay = LUT_FIXED_POINT_SCALE * LUT_FIXED_POINT_SCALE / exp_pos;
+
+    let mut decay_pow_x = LUT_FIXED_POINT_SCALE;
+    let mut i = 0;
+    while i < x {
+        decay_pow_x = decay_pow_x * decay / LUT_FIXED_POINT_SCALE;
+        i += 1;
+    }
+    let scale = PAYOUT_SCALE as u128;
+    ((39 * scale / 10 * decay_pow_x + scale / 10 * LUT_FIXED_POINT_SCALE + LUT_FIXED_POINT_SCALE / 2)
+        / LUT_FIXED_POINT_SCALE) as u64
+}
+
+// spot check the pasted table against the curve so a transcription error fails the build
+const _: () = assert!(PAYOUT_MULTIPLIER_LUT[0] as u64 == compute_lut_entry(0));
+const _: () = assert!(PAYOUT_MULTIPLIER_LUT[10] as u64 == compute_lut_entry(10));
+const _: () = assert!(PAYOUT_MULTIPLIER_LUT[50] as u64 == compute_lut_entry(50));
+const _: () = assert!(PAYOUT_MULTIPLIER_LUT[100] as u64 == compute_lut_entry(100));
+
+// bounds for initialize_payout_curve, keeps every entry in a u32 and the taylor series in a u128
+pub const MAX_PAYOUT_CURVE_BASE_TIMES_100: u32 = 10_000; // 100x
+pub const MAX_PAYOUT_CURVE_EXPONENT_TIMES_100: u32 = 1_000; // exp(-10x)
+
+// round((base * exp(-exponent * x) + 0.1) * PAYOUT_SCALE) for x = 0 to 100, base and exponent given times 100.
+// same integer math as compute_lut_entry but carrying the power along, so on chain it's 100 multiplies
+// rather than one loop per entry
+pub const fn compute_payout_curve(base_times_100: u32, exponent_times_100: u32) -> [u32; 101] {
+    let mut exp_pos = LUT_FIXED_POINT_SCALE;
+    let mut term = LUT_FIXED_POINT_SCALE;
+    let mut k = 1;
+    while term > 0 {
+        term = term * exponent_times_100 as u128 / (100 * k);
+        exp_pos += term;
+        k += 1;
+    }
+    let decay = LUT_FIXED_POINT_SCALE * LUT_FIXED_POINT_SCALE / exp_pos;
+
+    let scale = PAYOUT_SCALE as u128;
+    let mut multipliers = [0u32; 101];
+    let mut decay_pow_x = LUT_FIXED_POINT_SCALE;
+    let mut x = 0;
+    while x < multipliers.len() {
+        multipliers[x] = ((base_times_100 as u128 * scale / 100 * decay_pow_x
+            + scale / 10 * LUT_FIXED_POINT_SCALE
+            + LUT_FIXED_POINT_SCALE / 2)
+            / LUT_FIXED_POINT_SCALE) as u32;
+        decay_pow_x = decay_pow_x * decay / LUT_FIXED_POINT_SCALE;
+        x += 1;
+    }
+    multipliers
+}
+
+// the default curve has to come out exactly as the table, not just within rounding
+const _: () = assert!(compute_payout_curve(390, 14)[0] == PAYOUT_MULTIPLIER_LUT[0]);
+const _: () = assert!(compute_payout_curve(390, 14)[10] == PAYOUT_MULTIPLIER_LUT[10]);
+const _: () = assert!(compute_payout_curve(390, 14)[50] == PAYOUT_MULTIPLIER_LUT[50]);
+const _: () = assert!(compute_payout_curve(390, 14)[100] == PAYOUT_MULTIPLIER_LUT[100]);
+
+// Payout for a winning bet given its scaled multiplier from PAYOUT_MULTIPLIER_LUT.
+// Done in u128 so the multiply can't overflow, erroring rather than truncating if the result doesn't fit back in a u64.
+pub fn compute_payout(bet_amount: u64, scaled_multiplier: u32) -> Result<u64> {
+    let payout_amount = (bet_amount as u128 * scaled_multiplier as u128) / (PAYOUT_SCALE as u128);
+    u64::try_from(payout_amount).map_err(|_| GameError::PayoutAmountOverflow.into())
+}
+
+// what settle_reveal needs to work out a reveal's payout and the player pot, free of accounts so the
+// fuzz target runs the same arithmetic the program does
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RevealAmounts {
+    pub bet_amount: u64,
+    // None when the bet is over the result
+    pub scaled_multiplier: Option<u32>,
+    // 0 unless the bet is an early bird
+    pub early_bird_bonus_bps: u16,
+    pub highest_bet_bonus: u64,
+    // 0 when the reveal is on time
+    pub late_reveal_penalty_bps: u16,
+    pub min_payout_threshold: u64,
+    pub total_player_pot: u64,
+    pub treasury_balance: u64,
+    pub treasury_rent_reserve: u64,
+    pub treasury_critical_threshold: u64,
+    pub is_disputed: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RevealOutcome {
+    // over the result or a zero payout, the host keeps the stake
+    Loss,
+    // won less than min_payout_threshold, settled like a loss
+    BelowThreshold,
+    // host liquidity is under the critical threshold or short of the payout, owed for withdraw_unpaid_bet
+    Unpaid,
+    Paid,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RevealSettlement {
+    pub outcome: RevealOutcome,
+    // bonuses and late penalty included, what the player is owed when Unpaid
+    pub payout_amount: u64,
+    pub early_bird_bonus: u64,
+    pub late_reveal_penalty: u64,
+    // 0 for losses, they never look at the treasury
+    pub host_liquidity: u64,
+    // part of a Paid payout held back while the result is disputed
+    pub held_payout: u64,
+    // player pot once the reveal is settled, unchanged when Unpaid
+    pub total_player_pot: u64,
+}
+
+pub fn settle_reveal_amounts(amounts: &RevealAmounts) -> Result<RevealSettlement> {
+    let bet_amount = amounts.bet_amount;
+    // the stake leaves the pot whenever the reveal settles. an unpaid reveal keeps it in there for
+    // withdraw_unpaid_bet to take out, so it's never taken out twice
+    let pot_without_stake = amounts
+        .total_player_pot
+        .checked_sub(bet_amount)
+        .ok_or(GameError::PlayerPotUnderflow)?;
+    let mut settlement = RevealSettlement {
+        outcome: RevealOutcome::Loss,
+        payout_amount: 0,
+        early_bird_bonus: 0,
+        late_reveal_penalty: 0,
+        host_liquidity: 0,
+        held_payout: 0,
+        total_player_pot: pot_without_stake,
+    };
+    let Some(scaled_multiplier) = amounts.scaled_multiplier else {
+        return Ok(settlement);
+    };
+    let payout_amount = compute_payout(bet_amount, scaled_multiplier)?;
+    if payout_amount == 0 {
+        return Ok(settlement);
+    }
+
+    // bonuses are funded from host liquidity like the rest of the winnings
+    settlement.early_bird_bonus = u64::try_from(
+        payout_amount as u128 * amounts.early_bird_bonus_bps as u128 / BPS_DENOMINATOR as u128,
+    )
+    .map_err(|_| GameError::PayoutAmountOverflow)?;
+    let payout_amount = payout_amount
+        .checked_add(settlement.early_bird_bonus)
+        .and_then(|payout_amount| payout_amount.checked_add(amounts.highest_bet_bonus))
+        .ok_or(GameError::PayoutAmountOverflow)?;
+    // the late penalty is a share of the winnings, bonuses included
+    settlement.late_reveal_penalty = (payout_amount as u128
+        * amounts.late_reveal_penalty_bps as u128
+        / BPS_DENOMINATOR as u128) as u64;
+    settlement.payout_amount = payout_amount
+        .checked_sub(settlement.late_reveal_penalty)
+        .ok_or(GameError::PayoutAmountOverflow)?;
+    let payout_amount = settlement.payout_amount;
+    if payout_amount < amounts.min_payout_threshold {
+        settlement.outcome = RevealOutcome::BelowThreshold;
+        return Ok(settlement);
+    }
+
+    // if the player's payout is <= initial stake, the stake covers it and doesn't need backing from the pot.
+    // otherwise the stake still backs the pot until the payout goes through
+    let backed_pot = if payout_amount <= bet_amount {
+        pot_without_stake
+    } else {
+        amounts.total_player_pot
+    };
+    // the rent reserve is never paid out either, so it isn't host liquidity
+    settlement.host_liquidity = amounts
+        .treasury_balance
+        .checked_sub(backed_pot)
+        .and_then(|balance| balance.checked_sub(amounts.treasury_rent_reserve))
+        .ok_or(GameError::HostLiquidityUnderflow)?;
+    if settlement.host_liquidity < amounts.treasury_critical_threshold
+        || payout_amount > settlement.host_liquidity
+    {
+        settlement.outcome = RevealOutcome::Unpaid;
+        settlement.total_player_pot = amounts.total_player_pot;
+        return Ok(settlement);
+    }
+
+    // the held part stays in the treasury and is tracked in the pot so the host can't claim it
+    settlement.held_payout = if amounts.is_disputed {
+        payout_amount
+            .checked_mul(DISPUTED_PAYOUT_HELD_PERCENT)
+            .ok_or(GameError::PayoutAmountOverflow)?
+            / 100
+    } else {
+        0
+    };
+    settlement.total_player_pot = pot_without_stake
+        .checked_add(settlement.held_payout)
+        .ok_or(GameError::PlayerPotOverflow)?;
+    settlement.outcome = RevealOutcome::Paid;
+    Ok(settlement)
+}
+
+// require! that also logs the allowed value and the rejected one before failing,
+// so a failed transaction says what was wrong and not just which error fired
+#[macro_export]
+macro_rules! require_with_context {
+    ($invariant:expr, $error:expr, expected = $expected:expr, got = $got:expr $(,)?) => {
+        if !($invariant) {
+            anchor_lang::prelude::msg!("Expected {}, got {}", $expected, $got);
+            return Err(anchor_lang::error!($error));
+        }
+    };
+}
+
+// Handlers read the clock through here rather than the clock account, so a harness
+// without the standard sysvars gets ClockUnavailable instead of an opaque error
+pub fn current_clock() -> Result<Clock> {
+    Clock::get().map_err(|err| {
+        msg!("Failed to read the Clock sysvar: {:?}", err);
+        GameError::ClockUnavailable.into()
+    })
+}
+
+// Hash a player commits to in commit_bet, keccak256 of the bet value then the salt, both little endian
+pub fn compute_commitment(bet_value: u8, salt: u64) -> [u8; 32] {
+    let mut hasher = keccak::Hasher::default();
+    hasher.hash(&bet_value.to_le_bytes());
+    hasher.hash(&salt.to_le_bytes());
+    hasher.result().to_bytes()
+}
+
+// Hash the authority pre-commits to with pre_commit_result_hash, keccak256 of the result then the
+// authority's salt little endian. the same scheme players commit their bets with
+pub fn compute_result_hash(result: u8, authority_salt: u64) -> [u8; 32] {
+    compute_commitment(result, authority_salt)
+}
+
+/// Suggests a salt for commit_bet from keccak(player || recent_blockhash), the first 8 bytes little endian.
+/// Client side convenience only, this is NOT on-chain randomness. It just saves players from picking
+/// a guessable salt like their birth year, keep the salt secret either way.
+pub fn suggest_salt(player: Pubkey, recent_blockhash: [u8; 32]) -> u64 {
+    let hash = keccak::hashv(&[player.as_ref(), &recent_blockhash]).to_bytes();
+    let mut lower_bytes = [0u8; 8];
+    lower_bytes.copy_from_slice(&hash[..8]);
+    u64::from_le_bytes(lower_bytes)
+}
+
+// Base payout a bet would get if the result came out as assumed_result, 0 for a loss.
+// Leaves out the early bird and highest bet bonuses since those depend on the game
+pub fn preview_payout_offchain(bet_value: u8, assumed_result: u8, bet_amount: u64) -> Result<u64> {
+    require!(
+        bet_value <= 100 && assumed_result <= 100,
+        GameError::InvalidBetValue
+    );
+    if bet_value > assumed_result {
+        return Ok(0);
+    }
+    compute_payout(
+        bet_amount,
+        PAYOUT_MULTIPLIER_LUT[(assumed_result - bet_value) as usize],
+    )
+}
+
+// ECVRF-EDWARDS25519-SHA512-TAI (RFC 9381) proofs are Gamma || c || s, a point, a 16 byte challenge and a scalar
+pub const ECVRF_PROOF_LEN: usize = 80;
+const ECVRF_SUITE: u8 = 0x03;
+const ED25519_BASEPOINT: PodEdwardsPoint = PodEdwardsPoint([
+    0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+]);
+// little endian, 2^252 + 27742317777372353535851937790883648493
+const ED25519_GROUP_ORDER: [u8; 32] = [
+    0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+];
+const ED25519_IDENTITY: PodEdwardsPoint = PodEdwardsPoint([
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+]);
+const ED25519_COFACTOR: PodScalar = PodScalar([
+    8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+]);
+
+// Verifies an ECVRF proof by public_key over alpha and returns its 64 byte output, None if it doesn't verify.
+// Unlike a signature there's exactly one valid proof per key and input, so whoever holds the key can't grind
+// proofs for the output they want
+pub fn ecvrf_verify(public_key: &[u8; 32], proof: &[u8; ECVRF_PROOF_LEN], alpha: &[u8]) -> Option<[u8; 64]> {
+    let y = PodEdwardsPoint(*public_key);
+    // a low order key would let one proof verify with several outputs
+    if !validate_edwards(&y) || multiply_edwards(&ED25519_COFACTOR, &y)? == ED25519_IDENTITY {
+        return None;
+    }
+    let gamma = PodEdwardsPoint(proof[..32].try_into().ok()?);
+    if !validate_edwards(&gamma) {
+        return None;
+    }
+    let mut c = [0u8; 32];
+    c[..16].copy_from_slice(&proof[32..48]);
+    let c = PodScalar(c);
+    let s: [u8; 32] = proof[48..].try_into().ok()?;
+    // s + the group order would verify just the same, only the reduced one counts
+    if s.iter().rev().cmp(ED25519_GROUP_ORDER.iter().rev()) != std::cmp::Ordering::Less {
+        return None;
+    }
+    let s = PodScalar(s);
+
+    let h = ecvrf_encode_to_curve(public_key, alpha)?;
+    // U = s * B - c * Y, V = s * H - c * Gamma
+    let u = subtract_edwards(
+        &multiply_edwards(&s, &ED25519_BASEPOINT)?,
+        &multiply_edwards(&c, &y)?,
     )?;
-    Ok(())
+    let v = subtract_edwards(&multiply_edwards(&s, &h)?, &multiply_edwards(&c, &gamma)?)?;
+    let challenge = Sha512::new()
+        .chain_update([ECVRF_SUITE, 0x02])
+        .chain_update(y.0)
+        .chain_update(h.0)
+        .chain_update(gamma.0)
+        .chain_update(u.0)
+        .chain_update(v.0)
+        .chain_update([0x00])
+        .finalize();
+    if challenge[..16] != proof[32..48] {
+        return None;
+    }
+    let beta = Sha512::new()
+        .chain_update([ECVRF_SUITE, 0x03])
+        .chain_update(multiply_edwards(&ED25519_COFACTOR, &gamma)?.0)
+        .chain_update([0x00])
+        .finalize();
+    Some(beta.into())
+}
+
+// try and increment, hashes with a counter until the first 32 bytes decode to a point, then clears the cofactor
+fn ecvrf_encode_to_curve(public_key: &[u8; 32], alpha: &[u8]) -> Option<PodEdwardsPoint> {
+    (0..=u8::MAX).find_map(|ctr| {
+        let hash = Sha512::new()
+            .chain_update([ECVRF_SUITE, 0x01])
+            .chain_update(public_key)
+            .chain_update(alpha)
+            .chain_update([ctr, 0x00])
+            .finalize();
+        let candidate = PodEdwardsPoint(hash[..32].try_into().ok()?);
+        if !validate_edwards(&candidate) {
+            return None;
+        }
+        multiply_edwards(&ED25519_COFACTOR, &candidate)
+    })
+}
+
+// Threshold ElGamal decryption of a hidden result. ciphertext is C1 || C2 = r * G || result * G + r * Y for the
+// keyholders' joint key Y = x * G, shares are (keyholder number from 1, x_i * C1) from HIDDEN_RESULT_THRESHOLD
+// keyholders. Interpolating the shares gives x * C1 = r * Y, leaving result * G, which is matched against 0 to 100.
+// None if the shares don't decrypt to a valid result
+pub fn decrypt_hidden_result(ciphertext: &[u8; 64], shares: &[(i64, [u8; 32])]) -> Option<u8> {
+    // C1 only goes into the shares
+    let c2 = PodRistrettoPoint(ciphertext[32..].try_into().ok()?);
+
+    // LAGRANGE_SCALE * x * C1
+    let shared_secret = interpolate_ristretto(shares, 0)?;
+    let scaled_message = subtract_ristretto(&ristretto_times(LAGRANGE_SCALE, &c2)?, &shared_secret)?;
+
+    let step = ristretto_times(LAGRANGE_SCALE, &PodRistrettoPoint(RISTRETTO_BASEPOINT))?;
+    let mut candidate = PodRistrettoPoint([0; 32]);
+    for result in 0..=100 {
+        if candidate == scaled_message {
+            return Some(result);
+        }
+        candidate = add_ristretto(&candidate, &step)?;
+    }
+    None
+}
+
+// Key shares from initialize_hidden_result are x_i * G for keyholder numbers 1 to HIDDEN_RESULT_KEYHOLDERS. They have
+// to come from one polynomial of degree HIDDEN_RESULT_THRESHOLD - 1, otherwise which keyholders show up decides what
+// the result decrypts to. The first HIDDEN_RESULT_THRESHOLD fix the polynomial, the rest must lie on it
+pub fn key_shares_consistent(key_shares: &[[u8; 32]; HIDDEN_RESULT_KEYHOLDERS]) -> bool {
+    if !key_shares
+        .iter()
+        .all(|key_share| validate_ristretto(&PodRistrettoPoint(*key_share)))
+    {
+        return false;
+    }
+    let numbered: Vec<(i64, [u8; 32])> = (1..).zip(*key_shares).collect();
+    let (fixed, rest) = numbered.split_at(HIDDEN_RESULT_THRESHOLD);
+    rest.iter().all(|(number, key_share)| {
+        let expected = ristretto_times(LAGRANGE_SCALE, &PodRistrettoPoint(*key_share));
+        expected.is_some() && interpolate_ristretto(fixed, *number) == expected
+    })
+}
+
+// decryption share proofs are c || z, a 16 byte challenge and a scalar
+pub const DECRYPTION_SHARE_PROOF_LEN: usize = 48;
+const DECRYPTION_SHARE_DOMAIN: &[u8] = b"nuggies_decryption_share_v1";
+
+// Chaum-Pedersen proof that share = x_i * C1 for the same x_i as key_share = x_i * G, without giving x_i away.
+// The keyholder picks a random k, c is the first 16 bytes of
+// SHA512(DECRYPTION_SHARE_DOMAIN || key_share || C1 || share || k * G || k * C1) and z = k + c * x_i mod the group order.
+// Checked as A = z * G - c * key_share, B = z * C1 - c * share hashing back to c
+pub fn verify_decryption_share(
+    key_share: &[u8; 32],
+    c1: &[u8; 32],
+    share: &[u8; 32],
+    proof: &[u8; DECRYPTION_SHARE_PROOF_LEN],
+) -> bool {
+    let verify = || -> Option<bool> {
+        let key_share = PodRistrettoPoint(*key_share);
+        let c1 = PodRistrettoPoint(*c1);
+        let share = PodRistrettoPoint(*share);
+        if ![key_share, c1, share].iter().all(validate_ristretto) {
+            return Some(false);
+        }
+        let mut c = [0u8; 32];
+        c[..16].copy_from_slice(&proof[..16]);
+        let c = PodScalar(c);
+        let z: [u8; 32] = proof[16..].try_into().ok()?;
+        // z + the group order would verify just the same, only the reduced one counts
+        if z.iter().rev().cmp(ED25519_GROUP_ORDER.iter().rev()) != std::cmp::Ordering::Less {
+            return Some(false);
+        }
+        let z = PodScalar(z);
+
+        let basepoint = PodRistrettoPoint(RISTRETTO_BASEPOINT);
+        let a = subtract_ristretto(
+            &multiply_ristretto(&z, &basepoint)?,
+            &multiply_ristretto(&c, &key_share)?,
+        )?;
+        let b = subtract_ristretto(&multiply_ristretto(&z, &c1)?, &multiply_ristretto(&c, &share)?)?;
+        let challenge = Sha512::new()
+            .chain_update(DECRYPTION_SHARE_DOMAIN)
+            .chain_update(key_share.0)
+            .chain_update(c1.0)
+            .chain_update(share.0)
+            .chain_update(a.0)
+            .chain_update(b.0)
+            .finalize();
+        Some(challenge[..16] == proof[..16])
+    };
+    verify().unwrap_or(false)
+}
+
+// LAGRANGE_SCALE * f(at) for the polynomial f whose values times G (or C1) are the given points at their keyholder
+// numbers. None if a coefficient isn't a whole number even after scaling, the all zero encoding is the identity
+fn interpolate_ristretto(points: &[(i64, [u8; 32])], at: i64) -> Option<PodRistrettoPoint> {
+    let mut sum = PodRistrettoPoint([0; 32]);
+    for &(number, point) in points {
+        let (numerator, denominator) = points
+            .iter()
+            .filter(|(other, _)| *other != number)
+            .fold((LAGRANGE_SCALE, 1), |(numerator, denominator), (other, _)| {
+                (numerator * (at - other), denominator * (number - other))
+            });
+        if numerator % denominator != 0 {
+            return None;
+        }
+        let coefficient = numerator / denominator;
+        let term = ristretto_times(coefficient, &PodRistrettoPoint(point))?;
+        sum = if coefficient < 0 {
+            subtract_ristretto(&sum, &term)?
+        } else {
+            add_ristretto(&sum, &term)?
+        };
+    }
+    Some(sum)
+}
+
+// |scalar| * point, callers subtract for negative scalars
+fn ristretto_times(scalar: i64, point: &PodRistrettoPoint) -> Option<PodRistrettoPoint> {
+    let mut scalar_bytes = [0u8; 32];
+    scalar_bytes[..8].copy_from_slice(&scalar.unsigned_abs().to_le_bytes());
+    multiply_ristretto(&PodScalar(scalar_bytes), point)
+}
+
+// What the treasury would owe if every bet still in the player pot turned out to be a perfect guess,
+// the pot times the top multiplier of the game's curve. Revealed bets have already left the pot.
+// Every bet is costed as an early bird and the highest bet bonus is counted once while anyone is in the pot.
+// A custom PayoutCurve isn't readable from the game alone so those games are costed on the default table
+pub fn worst_case_remaining_payout_obligation(game: &Game) -> u64 {
+    let top_multiplier = game
+        .payout_multiplier(0, None)
+        .unwrap_or(PAYOUT_MULTIPLIER_LUT[0]);
+    let base_obligation =
+        game.total_player_pot as u128 * top_multiplier as u128 / PAYOUT_SCALE as u128;
+    let early_bird_bonus =
+        base_obligation * game.early_bird_bonus_bps as u128 / BPS_DENOMINATOR as u128;
+    let highest_bet_bonus = if game.reward_highest_bet && game.total_player_pot > 0 {
+        game.highest_bet_bonus_lamports as u128
+    } else {
+        0
+    };
+    u64::try_from(base_obligation + early_bird_bonus + highest_bet_bonus).unwrap_or(u64::MAX)
+}
+
+// Version 2 commitment, domain separated so the hash can't be confused with any other keccak of the same bytes
+pub fn compute_commitment_v2(bet_value: u8, salt: u64) -> [u8; 32] {
+    let mut hasher = keccak::Hasher::default();
+    hasher.hash(COMMITMENT_DOMAIN_V2);
+    hasher.hash(&bet_value.to_le_bytes());
+    hasher.hash(&salt.to_le_bytes());
+    hasher.result().to_bytes()
+}
+
+// Commitment for a bet written under commitment_version, None for versions this build doesn't know
+pub fn compute_versioned_commitment(commitment_version: u8, bet_value: u8, salt: u64) -> Option<[u8; 32]> {
+    match commitment_version {
+        1 => Some(compute_commitment(bet_value, salt)),
+        2 => Some(compute_commitment_v2(bet_value, salt)),
+        _ => None,
+    }
+}
+
+// Same check reveal_and_claim does for version 1 bets, so players can confirm their inputs before sending a reveal
+pub fn verify_commitment_offchain(commitment: [u8; 32], bet_value: u8, salt: u64) -> bool {
+    compute_commitment(bet_value, salt) == commitment
 }
 
 #[program]
 pub mod nug_wager_protocol {
     use super::*;
 
-    pub fn initialize_game(ctx: Context<InitializeGame>) -> Result<()> {
-        instructions::initialize_game(ctx)
+    pub fn initialize_program_stats(ctx: Context<InitializeProgramStats>) -> Result<()> {
+        instructions::initialize_program_stats(ctx)
+    }
+
+    // Program admin creates the cross game jackpot once
+    pub fn initialize_global_jackpot(ctx: Context<InitializeGlobalJackpot>) -> Result<()> {
+        instructions::initialize_global_jackpot(ctx)
+    }
+
+    // Program admin starts a new jackpot epoch, letting the next exact guess win whatever has built up
+    pub fn new_jackpot_epoch(ctx: Context<NewJackpotEpoch>) -> Result<()> {
+        instructions::new_jackpot_epoch(ctx)
+    }
+
+    // Program admin halts the game if it's being exploited, only reclaim_bet_on_timeout still works
+    pub fn emergency_freeze_game(ctx: Context<EmergencyFreezeGame>) -> Result<()> {
+        instructions::emergency_freeze_game(ctx)
+    }
+
+    // Program admin and the game authority together lift an emergency freeze
+    pub fn emergency_unfreeze_game(ctx: Context<EmergencyUnfreezeGame>) -> Result<()> {
+        instructions::emergency_unfreeze_game(ctx)
+    }
+
+    // Program admin creates the protocol insurance fund once
+    pub fn initialize_insurance_fund(ctx: Context<InitializeInsuranceFund>) -> Result<()> {
+        instructions::initialize_insurance_fund(ctx)
+    }
+
+    // Program admin tops up the insurance fund
+    pub fn fund_insurance(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+        instructions::fund_insurance(ctx, amount)
+    }
+
+    // Player whose reveals have gone unpaid in INSURANCE_MIN_UNPAID_REVEALS games claims the winnings the host
+    // couldn't cover on this game's bet from the insurance fund, after the reveal deadline.
+    // The stake itself still comes back through withdraw_unpaid_bet
+    pub fn claim_insurance(ctx: Context<ClaimInsurance>) -> Result<()> {
+        instructions::claim_insurance(ctx)
+    }
+
+    // Program admin replaces the payout callback whitelist, at most MAX_CALLBACK_PROGRAMS programs.
+    // Delisting a program stops games calling it straight away
+    pub fn set_callback_program_whitelist(
+        ctx: Context<SetCallbackProgramWhitelist>,
+        whitelist: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_callback_program_whitelist(ctx, whitelist)
+    }
+
+    // Authority sets the whitelisted program reveal_and_claim notifies after each payout, None turns it off.
+    // Only before the first bet
+    pub fn set_payout_callback(
+        ctx: Context<SetPayoutCallback>,
+        callback_program: Option<Pubkey>,
+        callback_discriminant: [u8; 8],
+    ) -> Result<()> {
+        instructions::set_payout_callback(ctx, callback_program, callback_discriminant)
+    }
+
+    // Program admin updates global params shared by every game
+    pub fn set_program_params(
+        ctx: Context<SetProgramParams>,
+        min_cooldown_seconds: i64,
+        max_games_per_authority: u8,
+    ) -> Result<()> {
+        instructions::set_program_params(ctx, min_cooldown_seconds, max_games_per_authority)
+    }
+
+    // Anyone creates the open game counter for an authority, needed before that authority's game can be initialized
+    pub fn initialize_authority_games(ctx: Context<InitializeAuthorityGames>, authority: Pubkey) -> Result<()> {
+        instructions::initialize_authority_games(ctx, authority)
+    }
+
+    // is_test_mode allows set_test_result, rejected on mainnet builds.
+    // nft_gate_mint restricts betting to holders of that NFT through commit_bet_with_token_account_proof.
+    // result_oracle has to confirm_result every submitted result before it counts
+    pub fn initialize_game(
+        ctx: Context<InitializeGame>,
+        is_test_mode: bool,
+        nft_gate_mint: Option<Pubkey>,
+        result_oracle: Option<Pubkey>,
+        commitment_window_start: Option<i64>,
+        host_fee_escrow: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::initialize_game(
+            ctx,
+            is_test_mode,
+            nft_gate_mint,
+            result_oracle,
+            commitment_window_start,
+            host_fee_escrow,
+        )
+    }
+
+    // Same as initialize_game but with deadlines as offsets in seconds from now, so timeout paths are easy to test
+    #[cfg(feature = "test-helpers")]
+    pub fn initialize_test_game(
+        ctx: Context<InitializeTestGame>,
+        submission_deadline_offset: i64,
+        reveal_deadline_offset: i64,
+        final_claim_offset: i64,
+    ) -> Result<()> {
+        instructions::initialize_test_game(ctx, submission_deadline_offset, reveal_deadline_offset, final_claim_offset)
+    }
+
+    // Authority lets a test mode game reveal without is_open_for_reveals, so an integration test can
+    // commit, set_test_result and reveal in one transaction to check the hash scheme end to end
+    #[cfg(feature = "test-helpers")]
+    pub fn set_commit_reveal_same_tx(ctx: Context<SetCommitRevealSameTx>, enabled: bool) -> Result<()> {
+        instructions::set_commit_reveal_same_tx(ctx, enabled)
+    }
+
+    // Player commits a hash of their bet, salt, and the bet amount, optionally quoting a referral code.
+    // All commits take the Pyth SOL/USD price feed as an optional first remaining account, for the USD display value
+    pub fn commit_bet(
+        ctx: Context<CommitBet>,
+        commitment: [u8; 32],
+        amount: u64,
+        player_note: Option<[u8; 64]>,
+        referral_code: Option<[u8; REFERRAL_CODE_LENGTH]>,
+    ) -> Result<()> {
+        instructions::commit_bet(ctx, commitment, amount, player_note, referral_code)
+    }
+
+    // Player commits a bet on an NFT gated game, proving they hold the gate NFT
+    pub fn commit_bet_with_token_account_proof(
+        ctx: Context<CommitBetGated>,
+        commitment: [u8; 32],
+        amount: u64,
+        player_note: Option<[u8; 64]>,
+    ) -> Result<()> {
+        instructions::commit_bet_with_token_account_proof(ctx, commitment, amount, player_note)
+    }
+
+    // Escrow owner (e.g. a DAO multisig) commits a bet it pays for, with winnings going to the beneficiary
+    pub fn commit_via_escrow(
+        ctx: Context<CommitViaEscrow>,
+        escrow_id: u64,
+        beneficiary: Pubkey,
+        commitment: [u8; 32],
+        amount: u64,
+    ) -> Result<()> {
+        instructions::commit_via_escrow(ctx, escrow_id, beneficiary, commitment, amount)
+    }
+
+    // Escrow owner reveals the escrowed bet, the payout goes to the beneficiary.
+    // merkle_proof and leaf_index prove the bet is in the bet merkle root, empty when the game has none
+    pub fn reveal_via_escrow(
+        ctx: Context<RevealViaEscrow>,
+        bet_value: u8,
+        salt: u64,
+        merkle_proof: Vec<[u8; 32]>,
+        leaf_index: u32,
+    ) -> Result<()> {
+        instructions::reveal_via_escrow(ctx, bet_value, salt, merkle_proof, leaf_index)
     }
 
-    // Player commits a hash of their bet, salt, and the bet amount
-    pub fn commit_bet(ctx: Context<CommitBet>, commitment: [u8; 32], amount: u64) -> Result<()> {
-        instructions::commit_bet(ctx, commitment, amount)
+    // Host (Adrian), or the co-authority as backup, submits the final result, low confidence results are disputed
+    // The transaction must also carry an Ed25519 program instruction, right before this one, verifying
+    // result_signature over keccak(game || result) by the signer.
+    // Games with a result oracle only hold the result as pending until the oracle confirms it.
+    // authority_salt opens the result hash when one was pre-committed, ignored otherwise
+    pub fn submit_result(
+        ctx: Context<SubmitResult>,
+        result: u8,
+        result_confidence: u8,
+        result_signature: [u8; 64],
+        authority_salt: Option<u64>,
+    ) -> Result<()> {
+        instructions::submit_results(ctx, result, result_confidence, result_signature, authority_salt)
     }
 
-    // Host (Adrian) submits the final result
-    pub fn submit_result(ctx: Context<SubmitResult>, result: u8) -> Result<()> {
-        instructions::submit_results(ctx, result)
+    // Authority commits to keccak(result || authority_salt) before the first bet, see compute_result_hash,
+    // so the result can't be picked after seeing where the bets went. submit_result then has to open it
+    pub fn pre_commit_result_hash(ctx: Context<PreCommitResultHash>, result_hash: [u8; 32]) -> Result<()> {
+        instructions::pre_commit_result_hash(ctx, result_hash)
+    }
+
+    // Authority puts the game in hidden result mode before the first bet, naming the keyholders that
+    // share the ElGamal key and their public key shares x_i * G, see key_shares_consistent. The result is
+    // then submitted encrypted and needs HIDDEN_RESULT_THRESHOLD keyholders to decrypt, submit_result and
+    // submit_result_with_vrf are turned off. The result is only hidden from players and the chain until
+    // decryption, the host encrypts it themselves so they always know it
+    pub fn initialize_hidden_result(
+        ctx: Context<InitializeHiddenResult>,
+        keyholders: [Pubkey; HIDDEN_RESULT_KEYHOLDERS],
+        key_shares: [[u8; 32]; HIDDEN_RESULT_KEYHOLDERS],
+    ) -> Result<()> {
+        instructions::initialize_hidden_result(ctx, keyholders, key_shares)
+    }
+
+    // Host or co-authority submits the result encrypted to the keyholders' joint key, closing bets.
+    // ciphertext is C1 || C2 = r * G || result * G + r * Y as compressed Ristretto points. Nothing proves
+    // it was encrypted to the joint key, a ciphertext that isn't just fails to decrypt
+    pub fn submit_encrypted_result(ctx: Context<SubmitEncryptedResult>, ciphertext: [u8; 64]) -> Result<()> {
+        instructions::submit_encrypted_result(ctx, ciphertext)
+    }
+
+    // Keyholder submits x_i * C1, their decryption share of the encrypted result, with a proof it matches
+    // their key share, see verify_decryption_share
+    pub fn submit_decryption_share(
+        ctx: Context<SubmitDecryptionShare>,
+        share: [u8; 32],
+        proof: [u8; DECRYPTION_SHARE_PROOF_LEN],
+    ) -> Result<()> {
+        instructions::submit_decryption_share(ctx, share, proof)
+    }
+
+    // Anyone combines the first HIDDEN_RESULT_THRESHOLD decryption shares into the result, recording it
+    // like submit_result would. Every share was proven at submission so any of them will do.
+    // Has to happen before the result deadline like any other result
+    pub fn finalize_decryption(ctx: Context<FinalizeDecryption>) -> Result<()> {
+        instructions::finalize_decryption(ctx)
+    }
+
+    // Host or co-authority submits a random result from the game's VRF key instead of an external one.
+    // vrf_result.proof is an RFC 9381 ECVRF-EDWARDS25519-SHA512-TAI proof by vrf_result.public_key over
+    // keccak(game || "vrf" || bet_count), beta its output. Result is beta[0] % 101.
+    // Games with a VRF key can only take their result this way
+    pub fn submit_result_with_vrf(ctx: Context<SubmitResult>, vrf_result: VrfResult) -> Result<()> {
+        instructions::submit_result_with_vrf(ctx, vrf_result)
+    }
+
+    // Authority pins the VRF key submit_result_with_vrf checks against, None turns VRF results off.
+    // Only before the first bet, and it can't be the authority or co-authority's own key
+    pub fn set_vrf_public_key(ctx: Context<SetVrfPublicKey>, vrf_public_key: Option<Pubkey>) -> Result<()> {
+        instructions::set_vrf_public_key(ctx, vrf_public_key)
+    }
+
+    // Authority picks the payout curve and its parameter, step_threshold for StepFunction and
+    // constant_multiplier (scaled by PAYOUT_SCALE) for Constant. Only before the first bet
+    pub fn set_payout_curve(
+        ctx: Context<SetPayoutCurve>,
+        payout_curve_type: PayoutCurveType,
+        step_threshold: Option<u8>,
+        constant_multiplier: Option<u32>,
+    ) -> Result<()> {
+        instructions::set_payout_curve(ctx, payout_curve_type, step_threshold, constant_multiplier)
+    }
+
+    // Authority computes its own exponential curve, base * exp(-exponent * x) + 0.1 with both given times 100,
+    // into a PayoutCurve account that ExponentialDecay payouts use instead of PAYOUT_MULTIPLIER_LUT. Only before the first bet
+    pub fn initialize_payout_curve(
+        ctx: Context<InitializePayoutCurve>,
+        base_times_100: u32,
+        exponent_times_100: u32,
+    ) -> Result<()> {
+        instructions::initialize_payout_curve(ctx, base_times_100, exponent_times_100)
+    }
+
+    // Result oracle confirms the pending result, which then counts as submitted and opens reveals.
+    // result has to match the pending one so the oracle can't confirm a result it didn't see
+    pub fn confirm_result(ctx: Context<ConfirmResult>, result: u8) -> Result<()> {
+        instructions::confirm_result(ctx, result)
+    }
+
+    // Authority opens reveals for a game that doesn't open them on submit_result (reveal_opens_immediately off)
+    pub fn open_reveal_period(ctx: Context<OpenRevealPeriod>) -> Result<()> {
+        instructions::open_reveal_period(ctx)
+    }
+
+    // Authority sets the result of a test mode game whenever, ignoring the submission deadline
+    pub fn set_test_result(ctx: Context<SetTestResult>, result: u8) -> Result<()> {
+        instructions::set_test_result(ctx, result)
+    }
+
+    // Host resolves a disputed result, releasing the payouts held back while disputed
+    pub fn finalize_disputed_result(ctx: Context<FinalizeDisputedResult>) -> Result<()> {
+        instructions::finalize_disputed_result(ctx)
     }
 
     // Player reveals their bet, salt and claims reward in one step
@@ -87,6 +1062,55 @@ pub mod nug_wager_protocol {
         instructions::reveal_and_claim(ctx, bet_value, salt)
     }
 
+    // Authority commits to the set of accepted bets once betting has closed, so none can be slipped in afterwards
+    pub fn submit_bet_merkle_root(ctx: Context<SubmitBetMerkleRoot>, root: [u8; 32]) -> Result<()> {
+        instructions::submit_bet_merkle_root(ctx, root)
+    }
+
+    // Player proves their bet is in the committed merkle tree, then reveals and claims as usual
+    pub fn merkle_reveal_and_claim(
+        ctx: Context<RevealAndClaim>,
+        proof: Vec<[u8; 32]>,
+        leaf_index: u32,
+        bet_value: u8,
+        salt: u64,
+    ) -> Result<()> {
+        instructions::merkle_reveal_and_claim(ctx, proof, leaf_index, bet_value, salt)
+    }
+
+    // Anyone claims an unused referral code, players quote it in commit_bet
+    pub fn register_referral_code(
+        ctx: Context<RegisterReferralCode>,
+        code: [u8; REFERRAL_CODE_LENGTH],
+    ) -> Result<()> {
+        instructions::register_referral_code(ctx, code)
+    }
+
+    // Player creates their reputation record, reveals and timeout reclaims update it when passed in
+    pub fn initialize_player_reputation(ctx: Context<InitializePlayerReputation>) -> Result<()> {
+        instructions::initialize_player_reputation(ctx)
+    }
+
+    // Player creates their lifetime P&L record, reveals update it when passed in
+    pub fn initialize_player_pnl(ctx: Context<InitializePlayerPnl>) -> Result<()> {
+        instructions::initialize_player_pnl(ctx)
+    }
+
+    // Player claims the part of their vesting payout unlocked since they last claimed
+    pub fn claim_next_tranche(ctx: Context<ClaimNextTranche>) -> Result<()> {
+        instructions::claim_next_tranche(ctx)
+    }
+
+    // Biggest loser claims the consolation prize once the reveal deadline has passed
+    pub fn claim_biggest_loser_prize(ctx: Context<ClaimBiggestLoserPrize>) -> Result<()> {
+        instructions::claim_biggest_loser_prize(ctx)
+    }
+
+    // Player claims the part of their payout held back while the result was disputed
+    pub fn claim_held_payout(ctx: Context<ClaimHeldPayout>) -> Result<()> {
+        instructions::claim_held_payout(ctx)
+    }
+
     // Player withdraws original bet if host had INSUFFICIENT LIQUIDITY for payout AFTER REVEAL DEADLINE BEFORE FINAL CLAIM DEADLINE
     pub fn withdraw_unpaid_bet(ctx: Context<WithdrawUnpaidBet>) -> Result<()> {
         instructions::withdraw_unpaid_bet(ctx)
@@ -94,24 +1118,221 @@ pub mod nug_wager_protocol {
 
     // --- TIMEOUT INSTRUCTIONS ---
 
+    // Anyone takes the anti sybil stake of a bet that was never revealed, once every player window is over
+    pub fn sweep_stale_commitment(ctx: Context<SweepStaleCommitment>, player: Pubkey) -> Result<()> {
+        instructions::sweep_stale_commitment(ctx, player)
+    }
+
+    // Authority closes a commitment nobody revealed, CLAWBACK_DELAY_SECONDS after every player window is over.
+    // The stake becomes host liquidity and the rent goes to the authority. Its anti sybil stake has to be swept first
+    pub fn clawback_unrevealed_bet(ctx: Context<ClawbackUnrevealedBet>, player: Pubkey) -> Result<()> {
+        instructions::clawback_unrevealed_bet(ctx, player)
+    }
+
     // Player reclaims their original bet if authority missed submission deadline
     pub fn reclaim_bet_on_timeout(ctx: Context<ReclaimBetOnTimeout>) -> Result<()> {
         instructions::reclaim_bet_on_timeout(ctx)
     }
 
+    // Player who never revealed gets their original bet back after the reveal deadline, until the final
+    // claim deadline. The bet value and salt prove the bet, a losing bet gets nothing
+    pub fn withdraw_missed_reveal_bet(ctx: Context<WithdrawMissedRevealBet>, bet_value: u8, salt: u64) -> Result<()> {
+        instructions::withdraw_missed_reveal_bet(ctx, bet_value, salt)
+    }
+
+    // Anyone can emit how long is left to reveal, monitoring bots call it on a timer to remind players
+    pub fn reveal_deadline_heartbeat(ctx: Context<RevealDeadlineHeartbeat>) -> Result<()> {
+        instructions::reveal_deadline_heartbeat(ctx)
+    }
+
+    // Anyone can extend the reveal deadline once after it passes if too few players have revealed
+    pub fn check_and_extend_reveal_deadline(ctx: Context<CheckAndExtendRevealDeadline>) -> Result<()> {
+        instructions::check_and_extend_reveal_deadline(ctx)
+    }
+
+    // Authority flags a commitment made suspiciously close to a result leak, freezing it so it can't be revealed
+    pub fn flag_suspicious_commitment(ctx: Context<FlagSuspiciousCommitment>, player: Pubkey) -> Result<()> {
+        instructions::flag_suspicious_commitment(ctx, player)
+    }
+
+    // Authority proposes pulling host liquidity out mid game, e.g. SOL sent to the treasury by mistake.
+    // Executable after EMERGENCY_WITHDRAW_TIMELOCK_SECONDS, proposing again replaces the pending one
+    pub fn propose_emergency_withdraw(ctx: Context<ProposeEmergencyWithdraw>, amount: u64) -> Result<()> {
+        instructions::propose_emergency_withdraw(ctx, amount)
+    }
+
+    // Authority executes the proposed withdrawal once the timelock is up, never touches the player pot
+    pub fn execute_emergency_withdraw(ctx: Context<ExecuteEmergencyWithdraw>) -> Result<()> {
+        instructions::execute_emergency_withdraw(ctx)
+    }
+
+    // Read only, simulate it to see what a bet would pay for an assumed result without revealing the commitment.
+    // Emits PayoutEstimate so clients can read the estimate from the simulated logs
+    pub fn preview_payout(
+        ctx: Context<PreviewPayout>,
+        bet_value: u8,
+        assumed_result: u8,
+        bet_amount: u64,
+    ) -> Result<()> {
+        instructions::preview_payout(ctx, bet_value, assumed_result, bet_amount)
+    }
+
+    // Player hands their open bet to another wallet, who then reveals it and gets paid. Allowed until the reveal deadline
+    pub fn transfer_bet_ownership(ctx: Context<TransferBetOwnership>, new_owner: Pubkey) -> Result<()> {
+        instructions::transfer_bet_ownership(ctx, new_owner)
+    }
+
+    // Authority freezes up to MAX_BATCH_FREEZE_COMMITMENTS players' commitments at once, e.g. during a coordinated
+    // front-running attack. Commitments are passed in remaining_accounts in the same order as players
+    pub fn batch_freeze_commitments<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchFreezeCommitments<'info>>,
+        players: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::batch_freeze_commitments(ctx, players)
+    }
+
+    // Authority lifts a batch freeze, same accounts as batch_freeze_commitments
+    pub fn batch_unfreeze_commitments<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchFreezeCommitments<'info>>,
+        players: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::batch_unfreeze_commitments(ctx, players)
+    }
+
     // Authority claims after reveal deadline, or if someone flagged illiquidity then after final claim deadline 
     // (as this period between will allow players to claim back their initial stake preventing rug)
-    // This also cleans up game
+    // This also cleans up game. Capped at game.max_treasury_withdrawal_per_tx per call.
+    // Only once the player pot is empty or the final claim deadline has passed
     pub fn claim_remaining_treasury(ctx: Context<ClaimRemainingTreasury>) -> Result<()> {
         instructions::claim_remaining_treasury(ctx)
     }
+
+    // Authority takes back host liquidity over the player pot plus treasury_min_utilization_bps of it,
+    // from the reveal deadline on without waiting for the final claim deadline
+    pub fn rebalance_excess(ctx: Context<RebalanceExcess>) -> Result<()> {
+        instructions::rebalance_excess(ctx)
+    }
+
+    // Authority closes betting now, before submission_deadline, once the pot reaches early_close_min_pot
+    pub fn early_close_betting(ctx: Context<EarlyCloseBetting>) -> Result<()> {
+        instructions::early_close_betting(ctx)
+    }
+
+    // Same deadlines as claim_remaining_treasury but only takes host liquidity above the player pot,
+    // amount defaults to all of it. Can be called repeatedly
+    pub fn claim_host_liquidity_only(ctx: Context<ClaimRemainingTreasury>, amount: Option<u64>) -> Result<()> {
+        instructions::claim_host_liquidity_only(ctx, amount)
+    }
+
+    // Player mints an NFT for their bet (e.g. in the same transaction as the commit) so it can be held or
+    // traded, by smart contract wallets and DAOs too. The bet can't be revealed or reclaimed while it's out
+    pub fn mint_proof_of_bet(ctx: Context<MintProofOfBet>) -> Result<()> {
+        instructions::mint_proof_of_bet(ctx)
+    }
+
+    // Holder burns the proof of bet NFT and becomes the bet's owner, then reveals as usual
+    pub fn redeem_proof_of_bet(ctx: Context<RedeemProofOfBet>) -> Result<()> {
+        instructions::redeem_proof_of_bet(ctx)
+    }
+
+    // Authority mints a trophy NFT to a player whose payout beat their stake, once reveals are over.
+    // only when game.mint_winner_nft is on
+    pub fn mint_winner_trophy(ctx: Context<MintWinnerTrophy>, winner: Pubkey) -> Result<()> {
+        instructions::mint_winner_trophy(ctx, winner)
+    }
+
+    // Authority takes the rent reserve left in the treasury after claim_remaining_treasury, emptying it for close_game
+    pub fn close_empty_treasury(ctx: Context<CloseEmptyTreasury>) -> Result<()> {
+        instructions::close_empty_treasury(ctx)
+    }
+
+    // Authority creates the game's leaderboard, reveals update it when passed in
+    pub fn initialize_leaderboard(ctx: Context<InitializeLeaderboard>) -> Result<()> {
+        instructions::initialize_leaderboard(ctx)
+    }
+
+    // Authority freezes the leaderboard once the reveal deadline has passed, making it the final record
+    pub fn freeze_leaderboard(ctx: Context<FreezeLeaderboard>) -> Result<()> {
+        instructions::freeze_leaderboard(ctx)
+    }
+
+    // Authority creates the game's histogram of revealed guesses, reveals update it when passed in
+    pub fn initialize_bet_distribution(ctx: Context<InitializeBetDistribution>) -> Result<()> {
+        instructions::initialize_bet_distribution(ctx)
+    }
+
+    // Anyone can create the results feed once, close_game writes into it
+    pub fn initialize_results_feed(ctx: Context<InitializeResultsFeed>) -> Result<()> {
+        instructions::initialize_results_feed(ctx)
+    }
+
+    // Authority rewrites a game from the original deployment into the current layout.
+    // only games without bets, the old BetCommitment layout isn't migrated
+    pub fn migrate_game(ctx: Context<MigrateGame>) -> Result<()> {
+        instructions::migrate_game(ctx)
+    }
+
+    // Authority sets or clears the multisig that can act alongside it, the authority itself can't change here
+    pub fn set_treasury_multisig(
+        ctx: Context<SetTreasuryMultisig>,
+        new_treasury_multisig: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_treasury_multisig(ctx, new_treasury_multisig)
+    }
+
+    // Authority proposes a new authority, nothing changes until it's accepted. The only way to change the authority.
+    // Proposing again replaces the pending one
+    pub fn propose_authority_transfer(
+        ctx: Context<ProposeAuthorityTransfer>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_authority_transfer(ctx, new_authority)
+    }
+
+    // Pending authority takes over, has to sign within AUTHORITY_TRANSFER_TIMEOUT_SECONDS of the proposal
+    pub fn accept_authority_transfer(ctx: Context<AcceptAuthorityTransfer>) -> Result<()> {
+        instructions::accept_authority_transfer(ctx)
+    }
+
+    // Authority drops the pending proposal, e.g. it expired or named the wrong key
+    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+        instructions::cancel_authority_transfer(ctx)
+    }
+
+    // Authority turns charity mode on with the wallet losing stakes go to, None turns it off.
+    // Only before the first bet so players know where losses go when they commit
+    pub fn set_charity_mode(ctx: Context<SetCharityMode>, charity_wallet: Option<Pubkey>) -> Result<()> {
+        instructions::set_charity_mode(ctx, charity_wallet)
+    }
+
+    // Anyone sends the losing stakes owed to charity to the charity wallet, once the reveal deadline passes
+    pub fn distribute_to_charity(ctx: Context<DistributeToCharity>) -> Result<()> {
+        instructions::distribute_to_charity(ctx)
+    }
+
+    // Authority names a backup who can submit the result, None removes it
+    pub fn set_co_authority(ctx: Context<SetCoAuthority>, co_authority: Option<Pubkey>) -> Result<()> {
+        instructions::set_co_authority(ctx, co_authority)
+    }
+
+    // Authority closes the game once the treasury has been emptied, starting the cooldown before the next game
+    pub fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+        instructions::close_game(ctx)
+    }
+
+    // Anyone can close a game abandoned for a year past its final claim deadline, the caller gets the game rent
+    pub fn close_stale_game(ctx: Context<CloseStaleGame>) -> Result<()> {
+        instructions::close_stale_game(ctx)
+    }
 }
 
 // --- Account Structs ---
 
 #[account]
-#[derive(Default)]
+#[derive(Default, InitSpace)]
 pub struct Game {
+    // must stay the first field so GameSnapshot can read it before knowing the layout
+    pub game_version: u8,
     pub authority: Pubkey,
     pub result: Option<u8>,
     // we should use enums but im too far gone
@@ -125,255 +1346,2946 @@ pub struct Game {
     pub submission_deadline: Option<i64>,  // Unix timestamp
     pub reveal_deadline: Option<i64>,      // Unix timestamp
     pub final_claim_deadline: Option<i64>, // Unix timestamp
+    // deadlines picked at init, copied into reveal_deadline/final_claim_deadline when they kick in
+    pub scheduled_reveal_deadline: i64,       // Unix timestamp
+    pub scheduled_final_claim_deadline: i64,  // Unix timestamp
+    // lamports kept in the treasury so it stays rent exempt, not part of host liquidity
+    pub treasury_rent_reserve: u64,
+    // 0 to 100, how sure the host is about the result
+    pub result_confidence: u8,
+    pub dispute_threshold: u8,
+    pub dispute_grace_period_seconds: i64,
+    pub is_disputed: bool,
+    // cap on total_player_pot as basis points of the treasury balance
+    pub max_treasury_exposure_bps: u16,
+    // lifetime totals for the results feed, total_player_pot shrinks as players settle
+    pub total_wagered: u64,
+    pub total_paid_out: u64,
+    // sum_of_revealed_bet_values / revealed_bet_count is the mean revealed guess
+    pub sum_of_revealed_bet_values: u64,
+    pub revealed_bet_count: u64,
+    pub treasury_alert_threshold: u64,
+    pub treasury_critical_threshold: u64,
+    pub auto_extension_enabled: bool,
+    pub min_reveal_pct_for_no_extension: u8,
+    pub reveal_extension_seconds: i64,
+    // only extends once so the deadline can't be pushed out forever
+    pub reveal_deadline_extended: bool,
+    // times the final claim deadline has been pushed out, capped at MAX_FINAL_CLAIM_DEADLINE_EXTENSIONS
+    pub final_claim_deadline_extension_count: u8,
+    // optional multisig (e.g. a Squads vault) that can act as the authority alongside it
+    pub treasury_multisig: Option<Pubkey>,
+    // root of every accepted bet commitment, set by the authority once betting closes
+    pub bet_merkle_root: Option<[u8; 32]>,
+    pub cooldown_between_reveals_slots: u8,
+    // devnet games where the authority can set the result early with set_test_result
+    pub is_test_mode: bool,
+    pub early_bird_bonus_bps: u16,
+    // bets committed before this are early birds, first 25% of the window between init and submission_deadline
+    pub early_bird_cutoff: i64,
+    // only holders of this NFT can bet, through commit_bet_with_token_account_proof
+    pub nft_gate_mint: Option<Pubkey>,
+    pub reward_highest_bet: bool,
+    // biggest bet so far, ties go to whoever committed first
+    pub highest_bet_player: Option<Pubkey>,
+    pub highest_bet_amount: u64,
+    pub highest_bet_bonus_lamports: u64,
+    // authority's Ed25519 signature over keccak(game || result), proof of the result that stands on its own
+    pub host_result_signature: Option<[u8; 64]>,
+    // commitment scheme new bets on this game are written with, copied onto each BetCommitment
+    pub commitment_version: u8,
+    // bet_count and total_player_pot when the result was recorded, never written again after that
+    pub bet_count_at_submission: u64,
+    pub total_player_pot_at_submission: u64,
+    // whether mint_winner_trophy is allowed
+    pub mint_winner_nft: bool,
+    // backup who can submit the result if the authority is unavailable, nothing else
+    pub co_authority: Option<Pubkey>,
+    // every bet gets a CommitmentAuditLog that each instruction touching the bet has to append to
+    pub audit_log_enabled: bool,
+    // payouts below this are settled as a loss, 0 pays everything
+    pub min_payout_threshold: u64,
+    // see result_deadline
+    pub grace_period_seconds: u64,
+    // emergency withdrawal proposed by the authority, executable from pending_withdraw_unlocks_at
+    pub pending_withdraw_amount: Option<u64>,
+    pub pending_withdraw_unlocks_at: i64,
+    // two step authority transfer, the pending authority has to accept before it takes over
+    pub pending_authority: Option<Pubkey>,
+    pub pending_authority_proposed_at: i64,
+    pub max_treasury_withdrawal_per_tx: u64,
+    // lifetime lamports the authority has taken out of the treasury, for audits
+    pub total_claimed_by_authority: u64,
+    // see REVEAL_OPENS_IMMEDIATELY
+    pub reveal_opens_immediately: bool,
+    // see RESULT_UNCERTAINTY_RANGE. lower and upper are set with the result
+    pub result_uncertainty_range: u8,
+    pub result_lower: u8,
+    pub result_upper: u8,
+    // see BURN_LOSING_BETS
+    pub burn_losing_bets: bool,
+    pub burn_address: Pubkey,
+    // losing stakes go to charity_wallet through distribute_to_charity instead of the host, set with set_charity_mode
+    pub is_charity_mode: bool,
+    pub charity_wallet: Option<Pubkey>,
+    // lifetime losing stakes owed to charity, and how much of that has been sent
+    pub total_charity_amount: u64,
+    pub charity_distributed_amount: u64,
+    // see SUBMISSION_DEADLINE_GRACE_ALERT_SECONDS
+    pub submission_deadline_grace_alert_seconds: i64,
+    // key submit_result_with_vrf results must come from, set with set_vrf_public_key
+    pub vrf_public_key: Option<Pubkey>,
+    // see PayoutCurveType, the parameters are only set for the curve that uses them
+    pub payout_curve_type: PayoutCurveType,
+    pub step_threshold: Option<u8>,
+    pub constant_multiplier: Option<u32>,
+    // ExponentialDecay reads the game's PayoutCurve account instead of PAYOUT_MULTIPLIER_LUT
+    pub custom_payout_curve: bool,
+    // see ANTI_SYBIL_STAKE_LAMPORTS
+    pub anti_sybil_stake: u64,
+    // program CPI'd after each payout with callback_discriminant || game || player || payout, see set_payout_callback
+    pub callback_program: Option<Pubkey>,
+    pub callback_discriminant: [u8; 8],
+    // second party who has to confirm_result before a submitted result counts, set at init
+    pub result_oracle: Option<Pubkey>,
+    pub pending_result: Option<u8>,
+    pub pending_result_confidence: u8,
+    // see TREASURY_MIN_UTILIZATION_BPS
+    pub treasury_min_utilization_bps: u16,
+    // see PROGRESSIVE_JACKPOT_CONTRIBUTION_BPS
+    pub progressive_jackpot_contribution_bps: u16,
+    // see EARLY_CLOSE_ENABLED
+    pub early_close_enabled: bool,
+    pub early_close_min_pot: u64,
+    // see PAYOUT_UNLOCK_SCHEDULE
+    pub payout_unlock_schedule: [PayoutTranche; PAYOUT_UNLOCK_TRANCHES],
+    // keccak(result || authority_salt) from pre_commit_result_hash, all zeroes if the authority didn't pre-commit
+    pub result_submission_hash: [u8; 32],
+    // see MAX_BET_COUNT
+    pub max_bet_count: Option<u64>,
+    // see MINIMUM_HOST_LIQUIDITY_RATIO
+    pub minimum_host_liquidity_ratio: u16,
+    // test games only, see set_commit_reveal_same_tx
+    pub commit_reveal_same_tx: bool,
+    // result comes in encrypted and is decrypted by the keyholders, see initialize_hidden_result
+    pub hidden_result_mode: bool,
+    // see LATE_REVEAL_PENALTY_BPS
+    pub late_reveal_penalty_bps: u16,
+    pub late_reveal_threshold_pct: u8,
+    // when the result was recorded, the reveal window runs from here to scheduled_reveal_deadline.
+    // penalties taken stay in the treasury as host liquidity
+    pub submit_result_timestamp: Option<i64>,
+    // furthest a revealed losing bet landed above the result, ties go to whoever revealed first
+    pub biggest_loss_gap: u8,
+    pub biggest_loser: Option<Pubkey>,
+    // see BIGGEST_LOSER_PRIZE_LAMPORTS
+    pub biggest_loser_prize_lamports: u64,
+    pub biggest_loser_prize_claimed: bool,
+    // commits are rejected before this, so betting runs [commitment_window_start, submission_deadline)
+    pub commitment_window_start: Option<i64>,
+    // revealed bets that reached a final outcome (win, loss or reclaim), for "X of bet_count revealed" in UIs
+    pub revealed_count: u64,
+    // the ones of those that paid out something
+    pub winning_reveal_count: u64,
+    // see RESULT_VERIFICATION_DELAY_SECONDS
+    pub result_verification_delay_seconds: u32,
+    // reveals are rejected before this, set with the result
+    pub reveal_opens_at: i64,
+    // see UNIQUE_COMMITMENTS
+    pub unique_commitments: bool,
+    // set by the program admin if the game is being exploited, stops everything but reclaim_bet_on_timeout
+    pub emergency_frozen: bool,
+    // host's treasury claims go here instead of to the authority, e.g. a time locked multisig vault
+    pub host_fee_escrow: Option<Pubkey>,
+    // payouts held back while disputed and not claimed yet, claim_remaining_treasury never takes these
+    pub total_held_payout: u64,
 }
 
+// borsh encodes these primitives at their in-memory size, so derive the lengths from the types
+// rather than hand counting bytes
 const DISCRIMINATOR_LENGTH: usize = 8;
-const PUBKEY_LENGTH: usize = 32;
+const PUBKEY_LENGTH: usize = size_of::<Pubkey>();
 const OPTION_FLAG_LENGTH: usize = 1;
-const U8_LENGTH: usize = 1;
-const BOOL_LENGTH: usize = 1;
-const U64_LENGTH: usize = 8;
-const I64_LENGTH: usize = 8; // For UnixTimestamp (i64)
-const COMMITMENT_LENGTH: usize = 32;
-
-impl Game {
-    const LEN: usize = DISCRIMINATOR_LENGTH
-        + PUBKEY_LENGTH     // authority
-        + OPTION_FLAG_LENGTH + U8_LENGTH // result
-        + BOOL_LENGTH       // is_open_for_bets
-        + BOOL_LENGTH       // is_open_for_reveals
-        + U64_LENGTH        // bet_count
-        + U64_LENGTH        // total_player_pot
-        + U8_LENGTH         // bump
-        + U8_LENGTH         // treasury_bump
-        + OPTION_FLAG_LENGTH + I64_LENGTH // submission_deadline
-        + OPTION_FLAG_LENGTH + I64_LENGTH // reveal_deadline
-        + OPTION_FLAG_LENGTH + I64_LENGTH; // final_claim_deadline
-}
+const U8_LENGTH: usize = size_of::<u8>();
+const U32_LENGTH: usize = size_of::<u32>();
+const BOOL_LENGTH: usize = size_of::<bool>();
+const U64_LENGTH: usize = size_of::<u64>();
+const I64_LENGTH: usize = size_of::<i64>(); // For UnixTimestamp (i64)
+const COMMITMENT_LENGTH: usize = size_of::<[u8; 32]>();
 
-#[account]
-#[derive(Default)]
-pub struct BetCommitment {
-    pub player: Pubkey,
-    pub commitment: [u8; 32],
-    // not really needed for static game, but we'll keep it for now
-    pub game: Pubkey,
-    pub amount: u64,
+// Game and BetCommitment sizes come from #[derive(InitSpace)]. pinned here, starting from what the hand counted
+// lengths worked out to, so a layout change has to be made on purpose. update these with the struct
+const GAME_ACCOUNT_LEN: usize = 1020;
+const BET_COMMITMENT_ACCOUNT_LEN: usize = 316;
+const _: () = assert!(Game::INIT_SPACE + 8 == GAME_ACCOUNT_LEN);
+// fails alongside the size assert above, the version has to be bumped with the layout
+const _: () = assert!(GAME_VERSION == 2 && GAME_ACCOUNT_LEN == 1020);
+const _: () = assert!(BetCommitment::INIT_SPACE + 8 == BET_COMMITMENT_ACCOUNT_LEN);
 
-    pub is_claimed: bool,
-    // keeping track of players who have attempted to reveal their bet and claim their winnings
-    // but was unsuccessful due to the host not having enough liquidity.
-    // so we can prevent host from rugging them out of their rightful winnings,
-    // and they can still reclaim their bet later if host does not fund.
-    pub attempted_reveal: bool,
+// borsh Option<T> is a 1 byte flag followed by T
+const fn option_len(inner_len: usize) -> usize {
+    OPTION_FLAG_LENGTH + inner_len
 }
 
-impl BetCommitment {
-    const LEN: usize = DISCRIMINATOR_LENGTH
-        + PUBKEY_LENGTH      // player
-        + COMMITMENT_LENGTH  // commitment
-        + PUBKEY_LENGTH      // game
-        + U64_LENGTH         // amount
-        + BOOL_LENGTH        // is_claimed
-        + BOOL_LENGTH; // attempted_reveal
-}
+impl Game {
+    // authority instructions accept either the authority or the multisig as signer
+    pub fn is_authority(&self, signer: &Pubkey) -> bool {
+        self.authority == *signer || self.treasury_multisig.as_ref() == Some(signer)
+    }
 
-// --- Context Structs ---
+    // last moment the result can be submitted, players can reclaim with reclaim_bet_on_timeout after it
+    pub fn result_deadline(&self) -> Option<i64> {
+        let grace_period_seconds = self.grace_period_seconds.min(MAX_GRACE_PERIOD_SECONDS) as i64;
+        self.submission_deadline
+            .map(|submission_deadline| submission_deadline.saturating_add(grace_period_seconds))
+    }
+
+    // submit_result also takes the co-authority, it doesn't count for anything else
+    pub fn can_submit_result(&self, signer: &Pubkey) -> bool {
+        self.is_authority(signer) || self.co_authority.as_ref() == Some(signer)
+    }
+
+    // appends to the bet's audit log, which has to be passed whenever audit logging is on
+    pub fn record_audit(
+        &self,
+        audit_log: Option<&mut Account<CommitmentAuditLog>>,
+        entry: AuditEntry,
+    ) -> Result<()> {
+        match audit_log {
+            Some(audit_log) => audit_log.push(entry),
+            None => require!(!self.audit_log_enabled, GameError::AuditLogRequired),
+        }
+        Ok(())
+    }
+
+    // None until someone has revealed
+    pub fn average_revealed_bet(&self) -> Option<u64> {
+        self.sum_of_revealed_bet_values
+            .checked_div(self.revealed_bet_count)
+    }
+
+    // LUT index for a revealed bet, None when it's over the result range and lost.
+    // a bet under the range is measured from result_lower, the most favourable end
+    pub fn result_difference(&self, bet_value: u8) -> Option<u8> {
+        (bet_value <= self.result_upper).then(|| self.result_lower.saturating_sub(bet_value))
+    }
+
+    // check_and_extend_reveal_deadline would still push the reveal deadline out, so the reveal period
+    // isn't really over yet. same percentage check, done in integers without the division
+    pub fn reveal_extension_pending(&self) -> bool {
+        self.auto_extension_enabled
+            && !self.reveal_deadline_extended
+            && self.final_claim_deadline_extension_count < MAX_FINAL_CLAIM_DEADLINE_EXTENSIONS
+            && self.bet_count > 0
+            && (self.revealed_bet_count as u128 * 100)
+                < self.min_reveal_pct_for_no_extension as u128 * self.bet_count as u128
+    }
+
+    // payouts vest when any tranche unlocks something, an all zero schedule pays out in full
+    pub fn has_payout_unlock_schedule(&self) -> bool {
+        self.payout_unlock_schedule.iter().any(|tranche| tranche.bps_unlocked > 0)
+    }
+
+    // share of vesting_payout unlocked by now, all of it without a schedule
+    pub fn unlocked_payout(&self, vesting_payout: u64, now: i64) -> u64 {
+        if !self.has_payout_unlock_schedule() {
+            return vesting_payout;
+        }
+        let unlocked_bps = self
+            .payout_unlock_schedule
+            .iter()
+            .filter(|tranche| tranche.unlock_timestamp <= now)
+            .map(|tranche| tranche.bps_unlocked as u64)
+            .sum::<u64>()
+            .min(BPS_DENOMINATOR);
+        (vesting_payout as u128 * unlocked_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    // multiplier for a winning bet difference on the game's payout curve, scaled by PAYOUT_SCALE.
+    // payout_curve has to be passed when the game has a custom curve
+    pub fn payout_multiplier(&self, difference: u8, payout_curve: Option<&PayoutCurve>) -> Result<u32> {
+        require!(difference <= 100, GameError::InvalidBetValue);
+        Ok(match self.payout_curve_type {
+            PayoutCurveType::ExponentialDecay if self.custom_payout_curve => {
+                let payout_curve = payout_curve.ok_or(GameError::PayoutCurveRequired)?;
+                payout_curve.multipliers[difference as usize]
+            }
+            PayoutCurveType::ExponentialDecay => PAYOUT_MULTIPLIER_LUT[difference as usize],
+            PayoutCurveType::LinearDecay => PAYOUT_SCALE / 100 * (100 - difference as u32),
+            PayoutCurveType::StepFunction => {
+                let step_threshold = self.step_threshold.ok_or(GameError::InvalidPayoutCurve)?;
+                if difference <= step_threshold {
+                    PAYOUT_SCALE
+                } else {
+                    0
+                }
+            }
+            PayoutCurveType::Constant => self.constant_multiplier.ok_or(GameError::InvalidPayoutCurve)?,
+        })
+    }
+
+    // counts a revealed bet once it's settled for good, a reveal the host couldn't pay isn't until it's withdrawn
+    pub fn record_settled_reveal(&mut self, paid_out: bool) -> Result<()> {
+        self.revealed_count = self.revealed_count.checked_add(1).ok_or(GameError::Overflow)?;
+        if paid_out {
+            self.winning_reveal_count = self
+                .winning_reveal_count
+                .checked_add(1)
+                .ok_or(GameError::Overflow)?;
+        }
+        Ok(())
+    }
+
+    // share of a payout lost for being revealed late in the reveal window, 0 when it's on time.
+    // measured against the scheduled deadline, a dispute or auto extension moving reveal_deadline
+    // gives players more time without making on time reveals late
+    pub fn applied_late_reveal_penalty_bps(&self, now: i64) -> u16 {
+        let Some(submitted_at) = self.submit_result_timestamp else {
+            return 0;
+        };
+        let reveal_window = self.scheduled_reveal_deadline.saturating_sub(submitted_at);
+        if self.late_reveal_penalty_bps == 0 || reveal_window <= 0 {
+            return 0;
+        }
+        let reveal_pct = now.saturating_sub(submitted_at) as i128 * 100 / reveal_window as i128;
+        if reveal_pct <= self.late_reveal_threshold_pct as i128 {
+            return 0;
+        }
+        self.late_reveal_penalty_bps
+    }
+
+    // losing stakes still sitting in the treasury for charity, host withdrawals leave these behind
+    pub fn undistributed_charity_amount(&self) -> u64 {
+        self.total_charity_amount
+            .saturating_sub(self.charity_distributed_amount)
+    }
+
+    // last moment a player can still do anything with their bet, the final claim deadline if
+    // illiquidity was flagged, otherwise the reveal deadline. None until the result is in
+    pub fn last_player_deadline(&self) -> Option<i64> {
+        self.final_claim_deadline.or(self.reveal_deadline)
+    }
+
+    pub fn phase(&self) -> Result<BetPhase> {
+        BetPhase::try_from((self.is_open_for_bets, self.is_open_for_reveals)).map_err(Into::into)
+    }
+
+    // always move between phases through here so the two bools can't end up in an invalid combination
+    pub fn set_phase(&mut self, phase: BetPhase) {
+        (self.is_open_for_bets, self.is_open_for_reveals) = match phase {
+            BetPhase::Open => (true, false),
+            BetPhase::Revealing => (false, true),
+            BetPhase::Closed => (false, false),
+        };
+    }
+}
+
+// Game layout from before game_version existed (the original deployment).
+// It has no version byte so it's told apart from later layouts by its size.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct GameV1 {
+    pub authority: Pubkey,
+    pub result: Option<u8>,
+    pub is_open_for_bets: bool,
+    pub is_open_for_reveals: bool,
+    pub bet_count: u64,
+    pub total_player_pot: u64,
+    pub bump: u8,
+    pub treasury_bump: u8,
+    pub submission_deadline: Option<i64>,
+    pub reveal_deadline: Option<i64>,
+    pub final_claim_deadline: Option<i64>,
+}
+
+impl GameV1 {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH               // authority
+            + option_len(U8_LENGTH)       // result
+            + BOOL_LENGTH                 // is_open_for_bets
+            + BOOL_LENGTH                 // is_open_for_reveals
+            + U64_LENGTH                  // bet_count
+            + U64_LENGTH                  // total_player_pot
+            + U8_LENGTH                   // bump
+            + U8_LENGTH                   // treasury_bump
+            + option_len(I64_LENGTH)      // submission_deadline
+            + option_len(I64_LENGTH)      // reveal_deadline
+            + option_len(I64_LENGTH) // final_claim_deadline
+    }
+
+    pub const LEN: usize = GameV1::len();
+}
+
+// Any version of the Game account, for clients that need to read accounts written by older program versions
+#[derive(Clone)]
+pub enum GameSnapshot {
+    V1(GameV1),
+    // boxed, Game is much bigger than the old layout
+    V2(Box<Game>),
+}
+
+impl GameSnapshot {
+    pub fn from_account_info(info: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let data = info.try_borrow_data()?;
+        Self::try_deserialize(&mut &data[..])
+    }
+}
+
+impl AccountDeserialize for GameSnapshot {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        require!(
+            buf.len() >= DISCRIMINATOR_LENGTH && buf[..DISCRIMINATOR_LENGTH] == *Game::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        // v1 predates the version byte, only its size gives it away
+        if buf.len() == GameV1::LEN {
+            let mut data = &buf[DISCRIMINATOR_LENGTH..];
+            let game = GameV1::deserialize(&mut data)
+                .map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+            return Ok(GameSnapshot::V1(game));
+        }
+        match buf.get(DISCRIMINATOR_LENGTH) {
+            Some(&GAME_VERSION) => Ok(GameSnapshot::V2(Box::new(Game::try_deserialize_unchecked(buf)?))),
+            _ => err!(GameError::UnsupportedGameVersion),
+        }
+    }
+}
+
+impl AccountSerialize for GameSnapshot {
+    fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        match self {
+            GameSnapshot::V1(game) => {
+                writer
+                    .write_all(Game::DISCRIMINATOR)
+                    .map_err(|_| ErrorCode::AccountDidNotSerialize)?;
+                game.serialize(writer)
+                    .map_err(|_| ErrorCode::AccountDidNotSerialize.into())
+            }
+            GameSnapshot::V2(game) => game.try_serialize(writer),
+        }
+    }
+}
+
+// one step of a game's payout vesting schedule
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PayoutTranche {
+    pub unlock_timestamp: i64,
+    pub bps_unlocked: u16,
+}
+
+// how a winning bet's payout falls off with its difference from the result
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PayoutCurveType {
+    // PAYOUT_MULTIPLIER_LUT, 4x on the result down to about 1x
+    #[default]
+    ExponentialDecay,
+    // bet * (1 - difference / 100)
+    LinearDecay,
+    // the stake back within step_threshold of the result, nothing outside it
+    StepFunction,
+    // constant_multiplier * bet for any winning bet
+    Constant,
+}
+
+// Explicit view of the (is_open_for_bets, is_open_for_reveals) pair stored on Game.
+// (true, true) isn't a valid phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BetPhase {
+    Open,
+    Revealing,
+    Closed,
+}
+
+impl TryFrom<(bool, bool)> for BetPhase {
+    type Error = GameError;
+
+    fn try_from(
+        (is_open_for_bets, is_open_for_reveals): (bool, bool),
+    ) -> std::result::Result<Self, Self::Error> {
+        match (is_open_for_bets, is_open_for_reveals) {
+            (true, false) => Ok(BetPhase::Open),
+            (false, true) => Ok(BetPhase::Revealing),
+            (false, false) => Ok(BetPhase::Closed),
+            (true, true) => Err(GameError::InvalidGamePhase),
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct BetCommitment {
+    // current owner, the one who reveals and gets paid. changed by transfer_bet_ownership
+    pub player: Pubkey,
+    pub commitment: [u8; 32],
+    // not really needed for static game, but we'll keep it for now
+    pub game: Pubkey,
+    pub amount: u64,
+
+    pub is_claimed: bool,
+    // keeping track of players who have attempted to reveal their bet and claim their winnings
+    // but was unsuccessful due to the host not having enough liquidity.
+    // so we can prevent host from rugging them out of their rightful winnings,
+    // and they can still reclaim their bet later if host does not fund.
+    pub attempted_reveal: bool,
+    // part of the payout held back because the result was disputed when they revealed
+    pub held_payout: u64,
+    // slot the bet was committed in, for spotting bets placed right after a result leak
+    pub committed_at_slot: u64,
+    // set by the authority on suspicious commitments, frozen bets can't be revealed
+    pub is_frozen: bool,
+    // payout owed when a reveal failed on host liquidity, withdraw_unpaid_bet pays this if the host has since funded
+    pub pending_payout_amount: u64,
+    // committed before game.early_bird_cutoff, gets game.early_bird_bonus_bps on top of a winning payout
+    pub is_early_bird: bool,
+    // public message left with the bet, safe since the commitment hides the bet value.
+    // set once at commit, all zeroes means no note
+    pub player_note: [u8; 64],
+    // game.commitment_version at commit time, picks the hash reveal checks against
+    pub commitment_version: u8,
+    // set on reveal so the outcome outlives the transaction, e.g. for trophies
+    pub revealed_bet_value: Option<u8>,
+    // full payout once paid, held part included. 0 for losses and unpaid reveals
+    pub payout_amount: u64,
+    // player who committed, the PDA is derived from this so it stays put when ownership is transferred
+    pub original_player: Pubkey,
+    // stake in US cents from the Pyth price at commit time, display only. 0 if no price feed was passed
+    pub committed_amount_usd_cents: u32,
+    // code quoted at commit, all zeroes if none
+    pub referral_code: [u8; REFERRAL_CODE_LENGTH],
+    // position among the game's bets, 0 for the first
+    pub bet_index: u64,
+    // set while a proof of bet NFT is out, whoever holds it owns the bet. see mint_proof_of_bet
+    pub proof_of_bet_mint: Option<Pubkey>,
+    // anti sybil deposit still sitting in the game's vault for this bet
+    pub anti_sybil_stake: u64,
+    // part of the payout that vests on game.payout_unlock_schedule, pending_payout_amount is what's still locked of it
+    pub vesting_payout: u64,
+}
+
+#[account]
+#[derive(Default)]
+pub struct ProgramStats {
+    pub program_admin: Pubkey,
+    // 0 if no game has been closed yet
+    pub last_game_closed_at: i64, // Unix timestamp
+    pub min_cooldown_seconds: i64,
+    pub bump: u8,
+    // cap on ActiveGamesByAuthority::active_count, checked when a game is initialized
+    pub max_games_per_authority: u8,
+    // programs games may use as payout callbacks, unused slots are the default pubkey
+    pub callback_program_whitelist: [Pubkey; MAX_CALLBACK_PROGRAMS],
+}
+
+impl ProgramStats {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH      // program_admin
+            + I64_LENGTH         // last_game_closed_at
+            + I64_LENGTH         // min_cooldown_seconds
+            + U8_LENGTH          // bump
+            + U8_LENGTH          // max_games_per_authority
+            + PUBKEY_LENGTH * MAX_CALLBACK_PROGRAMS // callback_program_whitelist
+    }
+
+    pub const LEN: usize = ProgramStats::len();
+
+    pub fn is_callback_whitelisted(&self, program: &Pubkey) -> bool {
+        *program != Pubkey::default() && self.callback_program_whitelist.contains(program)
+    }
+}
+
+// how many open games an authority has, so one operator can't take up all the protocol's games
+#[account]
+#[derive(Default)]
+pub struct ActiveGamesByAuthority {
+    pub authority: Pubkey,
+    pub active_count: u32,
+    pub bump: u8,
+}
+
+impl ActiveGamesByAuthority {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH      // authority
+            + U32_LENGTH         // active_count
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = ActiveGamesByAuthority::len();
+}
+
+// output of the game's VRF key for submit_result_with_vrf. proof is the ECVRF proof over the game's VRF input,
+// ECVRF_PROOF_LEN bytes, and beta the output it hashes to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VrfResult {
+    pub proof: Vec<u8>,
+    pub public_key: [u8; 32],
+    pub beta: [u8; 64],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct HistoricalEntry {
+    pub game_pubkey: Pubkey,
+    pub result: u8,
+    pub total_wagered: u64,
+    pub total_paid_out: u64,
+    pub closed_at: i64, // Unix timestamp
+}
+
+impl HistoricalEntry {
+    const LEN: usize = PUBKEY_LENGTH // game_pubkey
+        + U8_LENGTH                  // result
+        + U64_LENGTH                 // total_wagered
+        + U64_LENGTH                 // total_paid_out
+        + I64_LENGTH; // closed_at
+}
+
+// results of the last HISTORY_CAPACITY closed games, so they're still readable once the game account is gone
+#[account]
+#[derive(Default)]
+pub struct HistoricalResultsFeed {
+    pub entries: [HistoricalEntry; HISTORY_CAPACITY],
+    // slot the next entry is written to, wraps around overwriting the oldest
+    pub write_index: u8,
+    pub bump: u8,
+}
+
+impl HistoricalResultsFeed {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + HistoricalEntry::LEN * HISTORY_CAPACITY // entries
+            + U8_LENGTH                               // write_index
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = HistoricalResultsFeed::len();
+
+    pub fn push(&mut self, entry: HistoricalEntry) {
+        self.entries[self.write_index as usize] = entry;
+        self.write_index = ((self.write_index as usize + 1) % HISTORY_CAPACITY) as u8;
+    }
+}
+
+// instruction that wrote an AuditEntry, stored as its u8
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AuditInstruction {
+    Commit,
+    Freeze,
+    Reveal,
+    WithdrawUnpaid,
+    ReclaimOnTimeout,
+    ClaimHeldPayout,
+    Unfreeze,
+    TransferOwnership,
+    Clawback,
+    WithdrawMissedReveal,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct AuditEntry {
+    pub instruction: u8,
+    pub timestamp: i64,
+    pub actor: Pubkey,
+    // lamports the bet put into (+) or took out of (-) the treasury
+    pub amount_delta: i64,
+}
+
+impl AuditEntry {
+    const LEN: usize = U8_LENGTH // instruction
+        + I64_LENGTH             // timestamp
+        + PUBKEY_LENGTH          // actor
+        + I64_LENGTH; // amount_delta
+
+    pub fn new(instruction: AuditInstruction, actor: Pubkey, amount_delta: i64, timestamp: i64) -> Self {
+        AuditEntry {
+            instruction: instruction as u8,
+            timestamp,
+            actor,
+            amount_delta,
+        }
+    }
+}
+
+// every change to a bet, last AUDIT_LOG_CAPACITY kept. only written when game.audit_log_enabled, never closed
+#[account]
+#[derive(Default)]
+pub struct CommitmentAuditLog {
+    pub bet_commitment: Pubkey,
+    pub entries: [AuditEntry; AUDIT_LOG_CAPACITY],
+    // total entries ever written, the next one goes to entry_count % AUDIT_LOG_CAPACITY
+    pub entry_count: u32,
+    pub bump: u8,
+}
+
+impl CommitmentAuditLog {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH                           // bet_commitment
+            + AuditEntry::LEN * AUDIT_LOG_CAPACITY    // entries
+            + U32_LENGTH                              // entry_count
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = CommitmentAuditLog::len();
+
+    pub fn push(&mut self, entry: AuditEntry) {
+        self.entries[self.entry_count as usize % AUDIT_LOG_CAPACITY] = entry;
+        self.entry_count = self.entry_count.wrapping_add(1);
+    }
+}
+
+// a player's results across every game they've revealed in
+#[account]
+#[derive(Default)]
+pub struct PlayerPnL {
+    pub player: Pubkey,
+    pub total_wagered: u64,
+    pub total_won: u64,
+    pub games_played: u32,
+    // games where the payout beat the stake
+    pub games_won: u32,
+    pub biggest_win_lamports: u64,
+    pub bump: u8,
+    // slot of the player's last reveal, for Game::cooldown_between_reveals_slots
+    pub last_reveal_slot: u64,
+}
+
+impl PlayerPnL {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH      // player
+            + U64_LENGTH         // total_wagered
+            + U64_LENGTH         // total_won
+            + U32_LENGTH         // games_played
+            + U32_LENGTH         // games_won
+            + U64_LENGTH         // biggest_win_lamports
+            + U8_LENGTH          // bump
+            + U64_LENGTH // last_reveal_slot
+    }
+
+    pub const LEN: usize = PlayerPnL::len();
+
+    // payout_amount is 0 for a loss
+    pub fn record_reveal(&mut self, bet_amount: u64, payout_amount: u64) -> Result<()> {
+        self.total_wagered = self
+            .total_wagered
+            .checked_add(bet_amount)
+            .ok_or(GameError::Overflow)?;
+        self.games_played = self.games_played.checked_add(1).ok_or(GameError::Overflow)?;
+        if payout_amount > bet_amount {
+            self.total_won = self
+                .total_won
+                .checked_add(payout_amount)
+                .ok_or(GameError::Overflow)?;
+            self.games_won = self.games_won.checked_add(1).ok_or(GameError::Overflow)?;
+            self.biggest_win_lamports = self.biggest_win_lamports.max(payout_amount);
+        }
+        Ok(())
+    }
+}
+
+// keyholders and decryption progress of a hidden result game
+#[account]
+pub struct ResultDecryption {
+    pub game: Pubkey,
+    // keyholder i holds the share for interpolation point i + 1
+    pub keyholders: [Pubkey; HIDDEN_RESULT_KEYHOLDERS],
+    // x_i * G for each keyholder, decryption shares are proven against these
+    pub key_shares: [[u8; 32]; HIDDEN_RESULT_KEYHOLDERS],
+    pub encrypted_result: Option<[u8; 64]>,
+    pub decryption_shares: [[u8; 32]; HIDDEN_RESULT_KEYHOLDERS],
+    pub share_submitted: [bool; HIDDEN_RESULT_KEYHOLDERS],
+    pub bump: u8,
+}
+
+impl ResultDecryption {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH                              // game
+            + PUBKEY_LENGTH * HIDDEN_RESULT_KEYHOLDERS   // keyholders
+            + 32 * HIDDEN_RESULT_KEYHOLDERS              // key_shares
+            + option_len(64)                             // encrypted_result
+            + 32 * HIDDEN_RESULT_KEYHOLDERS              // decryption_shares
+            + BOOL_LENGTH * HIDDEN_RESULT_KEYHOLDERS     // share_submitted
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = ResultDecryption::len();
+
+    pub fn keyholder_index(&self, keyholder: &Pubkey) -> Option<usize> {
+        self.keyholders.iter().position(|candidate| candidate == keyholder)
+    }
+}
+
+// a game's own exponential payout curve from initialize_payout_curve, M(x) * PAYOUT_SCALE for x = 0 to 100
+#[account]
+pub struct PayoutCurve {
+    pub game: Pubkey,
+    pub base_times_100: u32,
+    pub exponent_times_100: u32,
+    pub multipliers: [u32; 101],
+    pub bump: u8,
+}
+
+impl PayoutCurve {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH      // game
+            + U32_LENGTH         // base_times_100
+            + U32_LENGTH         // exponent_times_100
+            + U32_LENGTH * 101   // multipliers
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = PayoutCurve::len();
+}
+
+// how reliably a player sees their bets through, across every game
+#[account]
+#[derive(Default)]
+pub struct PlayerReputation {
+    pub player: Pubkey,
+    pub games_participated: u32,
+    pub games_revealed: u32,
+    pub games_timed_out: u32,
+    // revealed * 100 + participated * 10 - timed out * 50, floored at 0
+    pub reputation_score: u32,
+    pub bump: u8,
+    // reveals the host couldn't pay, counts towards claim_insurance
+    pub unpaid_reveals: u32,
+}
+
+impl PlayerReputation {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH // player
+            + U32_LENGTH    // games_participated
+            + U32_LENGTH    // games_revealed
+            + U32_LENGTH    // games_timed_out
+            + U32_LENGTH    // reputation_score
+            + U8_LENGTH     // bump
+            + U32_LENGTH // unpaid_reveals
+    }
+
+    pub const LEN: usize = PlayerReputation::len();
+
+    pub fn record_unpaid_reveal(&mut self) -> Result<()> {
+        self.unpaid_reveals = self.unpaid_reveals.checked_add(1).ok_or(GameError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn record_reveal(&mut self) -> Result<()> {
+        self.games_revealed = self.games_revealed.checked_add(1).ok_or(GameError::Overflow)?;
+        self.record_participation()
+    }
+
+    pub fn record_timeout(&mut self) -> Result<()> {
+        self.games_timed_out = self.games_timed_out.checked_add(1).ok_or(GameError::Overflow)?;
+        self.record_participation()
+    }
+
+    fn record_participation(&mut self) -> Result<()> {
+        self.games_participated = self
+            .games_participated
+            .checked_add(1)
+            .ok_or(GameError::Overflow)?;
+        let score = self.games_revealed as i64 * 100 + self.games_participated as i64 * 10
+            - self.games_timed_out as i64 * 50;
+        self.reputation_score = u32::try_from(score.max(0)).unwrap_or(u32::MAX);
+        emit!(ReputationUpdated {
+            player: self.player,
+            games_participated: self.games_participated,
+            games_revealed: self.games_revealed,
+            games_timed_out: self.games_timed_out,
+            reputation_score: self.reputation_score,
+        });
+        Ok(())
+    }
+}
+
+// jackpot fed by every game's bets, holds its lamports itself. one exact guess wins it per epoch
+#[account]
+#[derive(Default)]
+pub struct GlobalJackpot {
+    pub pot: u64,
+    pub epoch: u64,
+    pub claimed_this_epoch: bool,
+    pub last_winner: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl GlobalJackpot {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + U64_LENGTH // pot
+            + U64_LENGTH // epoch
+            + BOOL_LENGTH // claimed_this_epoch
+            + option_len(PUBKEY_LENGTH) // last_winner
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = GlobalJackpot::len();
+}
+
+// protocol wide fund covering winnings hosts couldn't pay, holds its lamports itself
+#[account]
+#[derive(Default)]
+pub struct ProtocolInsuranceFund {
+    pub total_funded: u64,
+    pub total_paid: u64,
+    pub bump: u8,
+}
+
+impl ProtocolInsuranceFund {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + U64_LENGTH // total_funded
+            + U64_LENGTH // total_paid
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = ProtocolInsuranceFund::len();
+}
+
+// a bet placed and revealed by owner on behalf of beneficiary, who receives the payout.
+// bet.player is the beneficiary
+#[account]
+pub struct BetEscrow {
+    pub owner: Pubkey,
+    pub beneficiary: Pubkey,
+    pub escrow_id: u64,
+    pub bump: u8,
+    pub bet: BetCommitment,
+}
+
+impl BetEscrow {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH                                // owner
+            + PUBKEY_LENGTH                                // beneficiary
+            + U64_LENGTH                                   // escrow_id
+            + U8_LENGTH                                    // bump
+            + BetCommitment::INIT_SPACE // bet
+    }
+
+    pub const LEN: usize = BetEscrow::len();
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct LeaderboardEntry {
+    pub player: Pubkey,
+    pub payout: u64,
+}
+
+impl LeaderboardEntry {
+    const LEN: usize = PUBKEY_LENGTH // player
+        + U64_LENGTH; // payout
+}
+
+// biggest payouts of a game, frozen by the authority after the reveal deadline
+#[account]
+#[derive(Default)]
+pub struct Leaderboard {
+    pub game: Pubkey,
+    // sorted by payout, biggest first. empty slots have a payout of 0
+    pub entries: [LeaderboardEntry; LEADERBOARD_CAPACITY],
+    pub is_frozen: bool,
+    pub snapshot_taken_at: i64, // Unix timestamp, 0 until frozen
+    pub snapshot_taken_by: Pubkey,
+    pub bump: u8,
+}
+
+impl Leaderboard {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH                                 // game
+            + LeaderboardEntry::LEN * LEADERBOARD_CAPACITY  // entries
+            + BOOL_LENGTH                                   // is_frozen
+            + I64_LENGTH                                    // snapshot_taken_at
+            + PUBKEY_LENGTH                                 // snapshot_taken_by
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = Leaderboard::len();
+
+    // slots the payout in, dropping the smallest entry if it's full
+    pub fn record(&mut self, player: Pubkey, payout: u64) {
+        let Some(position) = self.entries.iter().position(|entry| payout > entry.payout) else {
+            return;
+        };
+        self.entries[position..].rotate_right(1);
+        self.entries[position] = LeaderboardEntry { player, payout };
+    }
+}
+
+// how many revealed bets guessed each value, kept out of Game since it's 404 bytes
+#[account]
+pub struct BetDistribution {
+    pub game: Pubkey,
+    // indexed by bet value
+    pub revealed_bet_distribution: [u32; 101],
+    pub bump: u8,
+}
+
+impl BetDistribution {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH          // game
+            + U32_LENGTH * 101       // revealed_bet_distribution
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = BetDistribution::len();
+
+    pub fn record(&mut self, bet_value: u8) -> Result<()> {
+        let count = self
+            .revealed_bet_distribution
+            .get_mut(bet_value as usize)
+            .ok_or(GameError::InvalidBetValue)?;
+        *count = count.checked_add(1).ok_or(GameError::Overflow)?;
+        Ok(())
+    }
+}
+
+// proof a player committed to this game, written alongside the BetCommitment and never closed
+// so it outlives the reveal. nothing writes to it after commit. the game PDA is reused by the next game,
+// so it's keyed by the commitment hash as well, a returning player's fresh salt gives a fresh receipt
+#[account]
+pub struct CommitmentReceipt {
+    pub player: Pubkey,
+    pub commitment: [u8; 32],
+    pub amount: u64,
+    pub committed_at: i64,
+    pub game: Pubkey,
+    pub bump: u8,
+}
+
+impl CommitmentReceipt {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH     // player
+            + COMMITMENT_LENGTH // commitment
+            + U64_LENGTH        // amount
+            + I64_LENGTH        // committed_at
+            + PUBKEY_LENGTH     // game
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = CommitmentReceipt::len();
+}
+
+// claims a commitment hash for one bet on this game, see UNIQUE_COMMITMENTS. never closed
+#[account]
+pub struct CommitmentMarker {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub bump: u8,
+}
+
+impl CommitmentMarker {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH // game
+            + PUBKEY_LENGTH // player
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = CommitmentMarker::len();
+}
+
+// referral code claimed with register_referral_code, PDA of the code so each one has a single owner
+#[account]
+pub struct ReferralCode {
+    pub code: [u8; REFERRAL_CODE_LENGTH],
+    pub owner: Pubkey,
+    // bets committed with the code
+    pub uses: u32,
+    // nothing pays referrals yet, kept for when it does
+    pub total_referral_paid: u64,
+    pub bump: u8,
+}
+
+impl ReferralCode {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + REFERRAL_CODE_LENGTH // code
+            + PUBKEY_LENGTH        // owner
+            + U32_LENGTH           // uses
+            + U64_LENGTH           // total_referral_paid
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = ReferralCode::len();
+}
+
+// record of a commitment the authority flagged as suspicious
+#[account]
+#[derive(Default)]
+pub struct BettingAnomaly {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub committed_at_slot: u64,
+    pub flagged_at: i64, // Unix timestamp
+    pub bump: u8,
+}
+
+impl BettingAnomaly {
+    pub const fn len() -> usize {
+        DISCRIMINATOR_LENGTH
+            + PUBKEY_LENGTH      // game
+            + PUBKEY_LENGTH      // player
+            + U64_LENGTH         // committed_at_slot
+            + I64_LENGTH         // flagged_at
+            + U8_LENGTH // bump
+    }
+
+    pub const LEN: usize = BettingAnomaly::len();
+}
+
+// --- Context Structs ---
+
+#[derive(Accounts)]
+pub struct InitializeProgramStats<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = ProgramStats::LEN,
+        seeds = [PROGRAM_STATS_SEED],
+        bump
+    )]
+    pub program_stats: Account<'info, ProgramStats>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(authority: Pubkey)]
+pub struct InitializeAuthorityGames<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = ActiveGamesByAuthority::LEN,
+        seeds = [AUTHORITY_GAMES_SEED, authority.as_ref()],
+        bump
+    )]
+    pub authority_games: Account<'info, ActiveGamesByAuthority>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(min_cooldown_seconds: i64, max_games_per_authority: u8)]
+pub struct SetProgramParams<'info> {
+    #[account(
+        mut,
+        seeds = [PROGRAM_STATS_SEED],
+        bump = program_stats.bump,
+        has_one = program_admin @ GameError::InvalidAuthority,
+    )]
+    pub program_stats: Account<'info, ProgramStats>,
+    pub program_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCallbackProgramWhitelist<'info> {
+    #[account(
+        mut,
+        seeds = [PROGRAM_STATS_SEED],
+        bump = program_stats.bump,
+        has_one = program_admin @ GameError::InvalidAuthority,
+    )]
+    pub program_stats: Account<'info, ProgramStats>,
+    pub program_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPayoutCallback<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.bet_count == 0 @ GameError::BetsAlreadyCommitted,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(seeds = [PROGRAM_STATS_SEED], bump = program_stats.bump)]
+    pub program_stats: Account<'info, ProgramStats>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGlobalJackpot<'info> {
+    #[account(
+        seeds = [PROGRAM_STATS_SEED],
+        bump = program_stats.bump,
+        has_one = program_admin @ GameError::InvalidAuthority,
+    )]
+    pub program_stats: Account<'info, ProgramStats>,
+    #[account(
+        init,
+        payer = program_admin,
+        space = GlobalJackpot::LEN,
+        seeds = [GLOBAL_JACKPOT_SEED],
+        bump
+    )]
+    pub global_jackpot: Account<'info, GlobalJackpot>,
+    #[account(mut)]
+    pub program_admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct NewJackpotEpoch<'info> {
+    #[account(
+        seeds = [PROGRAM_STATS_SEED],
+        bump = program_stats.bump,
+        has_one = program_admin @ GameError::InvalidAuthority,
+    )]
+    pub program_stats: Account<'info, ProgramStats>,
+    #[account(mut, seeds = [GLOBAL_JACKPOT_SEED], bump = global_jackpot.bump)]
+    pub global_jackpot: Account<'info, GlobalJackpot>,
+    pub program_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyFreezeGame<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        seeds = [PROGRAM_STATS_SEED],
+        bump = program_stats.bump,
+        has_one = program_admin @ GameError::InvalidAuthority,
+    )]
+    pub program_stats: Account<'info, ProgramStats>,
+    pub program_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyUnfreezeGame<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.emergency_frozen @ GameError::GameNotEmergencyFrozen,
+        // the admin can't unfreeze alone, the host has to agree the game is safe to resume
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        seeds = [PROGRAM_STATS_SEED],
+        bump = program_stats.bump,
+        has_one = program_admin @ GameError::InvalidAuthority,
+    )]
+    pub program_stats: Account<'info, ProgramStats>,
+    pub program_admin: Signer<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeInsuranceFund<'info> {
+    #[account(
+        seeds = [PROGRAM_STATS_SEED],
+        bump = program_stats.bump,
+        has_one = program_admin @ GameError::InvalidAuthority,
+    )]
+    pub program_stats: Account<'info, ProgramStats>,
+    #[account(
+        init,
+        payer = program_admin,
+        space = ProtocolInsuranceFund::LEN,
+        seeds = [INSURANCE_FUND_SEED],
+        bump
+    )]
+    pub insurance_fund: Account<'info, ProtocolInsuranceFund>,
+    #[account(mut)]
+    pub program_admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundInsurance<'info> {
+    #[account(
+        seeds = [PROGRAM_STATS_SEED],
+        bump = program_stats.bump,
+        has_one = program_admin @ GameError::InvalidAuthority,
+    )]
+    pub program_stats: Account<'info, ProgramStats>,
+    #[account(mut, seeds = [INSURANCE_FUND_SEED], bump = insurance_fund.bump)]
+    pub insurance_fund: Account<'info, ProtocolInsuranceFund>,
+    #[account(mut)]
+    pub program_admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimInsurance<'info> {
+    #[account(
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        // the host had the whole reveal window to top up
+        constraint = game.reveal_deadline.is_some_and(|reveal_deadline| clock.unix_timestamp >= reveal_deadline) @ GameError::RevealDeadlineNotReached,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        seeds = [b"commitment", game.key().as_ref(), bet_commitment.original_player.as_ref()],
+        bump,
+        constraint = bet_commitment.player == player.key() @ GameError::InvalidPlayerForCommitment,
+        constraint = bet_commitment.attempted_reveal && !bet_commitment.is_claimed @ GameError::NothingToInsure,
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(
+        seeds = [REPUTATION_SEED, player.key().as_ref()],
+        bump = player_reputation.bump,
+        constraint = player_reputation.unpaid_reveals >= INSURANCE_MIN_UNPAID_REVEALS @ GameError::NotEnoughUnpaidReveals,
+    )]
+    pub player_reputation: Account<'info, PlayerReputation>,
+    #[account(mut, seeds = [INSURANCE_FUND_SEED], bump = insurance_fund.bump)]
+    pub insurance_fund: Account<'info, ProtocolInsuranceFund>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+#[instruction()]
+pub struct InitializeGame<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Game::INIT_SPACE,
+        seeds = [GLOBAL_GAME_SEED],
+        bump
+    )]
+    pub game: Account<'info, Game>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump)]
+    pub game_treasury: SystemAccount<'info>,
+    #[account(
+        seeds = [PROGRAM_STATS_SEED],
+        bump = program_stats.bump,
+        // stops a new game being spun up straight after the previous one closed
+        constraint = program_stats
+            .last_game_closed_at
+            .checked_add(program_stats.min_cooldown_seconds)
+            .is_some_and(|cooldown_ends_at| clock.unix_timestamp >= cooldown_ends_at) @ GameError::GameCooldownNotElapsed,
+    )]
+    pub program_stats: Account<'info, ProgramStats>,
+    // game authority's open game count, checked against game.authority in the handler
+    #[account(
+        mut,
+        seeds = [AUTHORITY_GAMES_SEED, authority_games.authority.as_ref()],
+        bump = authority_games.bump,
+    )]
+    pub authority_games: Account<'info, ActiveGamesByAuthority>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// same accounts as initialize_game, its own type so the cpi client still gets InitializeGame without test-helpers
+#[cfg(feature = "test-helpers")]
+#[derive(Accounts)]
+pub struct InitializeTestGame<'info> {
+    pub initialize_game: InitializeGame<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32], amount: u64)]
+pub struct CommitBet<'info> {
+    #[account(
+        mut, 
+        seeds = [GLOBAL_GAME_SEED], 
+        bump = game.bump, 
+        constraint = game.phase()? == BetPhase::Open @ GameError::ResultAlreadySubmitted,
+        constraint = game.result.is_none() @ GameError::ResultAlreadySubmitted,
+        // bet count, window start and submission deadline are checked in require_betting_window
+        // gated games have to go through commit_bet_with_token_account_proof
+        constraint = game.nft_gate_mint.is_none() @ GameError::NftGateRequired,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        init,
+        payer = player,
+        space = 8 + BetCommitment::INIT_SPACE,
+        seeds = [b"commitment", game.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(
+        init,
+        payer = player,
+        space = CommitmentReceipt::LEN,
+        seeds = [RECEIPT_SEED, game.key().as_ref(), player.key().as_ref(), commitment.as_ref()],
+        bump
+    )]
+    pub commitment_receipt: Account<'info, CommitmentReceipt>,
+    // created with the bet when game.audit_log_enabled, never closed
+    #[account(
+        init,
+        payer = player,
+        space = CommitmentAuditLog::LEN,
+        seeds = [AUDIT_SEED, bet_commitment.key().as_ref()],
+        bump
+    )]
+    pub audit_log: Option<Account<'info, CommitmentAuditLog>>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when a referral code is quoted, checked against it in the handler
+    #[account(mut)]
+    pub referral_code: Option<Account<'info, ReferralCode>>,
+    // required when game.anti_sybil_stake is set
+    #[account(mut, seeds = [ANTI_SYBIL_VAULT_SEED, game.key().as_ref()], bump)]
+    pub anti_sybil_vault: Option<SystemAccount<'info>>,
+    // required when game.progressive_jackpot_contribution_bps is set
+    #[account(mut, seeds = [GLOBAL_JACKPOT_SEED], bump = global_jackpot.bump)]
+    pub global_jackpot: Option<Box<Account<'info, GlobalJackpot>>>,
+    // required when game.unique_commitments is set, init fails if another bet already used the commitment
+    #[account(
+        init,
+        payer = player,
+        space = CommitmentMarker::LEN,
+        seeds = [UNIQUE_COMMITMENT_SEED, game.key().as_ref(), commitment.as_ref()],
+        bump
+    )]
+    pub commitment_marker: Option<Box<Account<'info, CommitmentMarker>>>,
+}
+
+// same as CommitBet plus the player's token account holding the gate NFT
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct CommitBetGated<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.phase()? == BetPhase::Open @ GameError::ResultAlreadySubmitted,
+        constraint = game.result.is_none() @ GameError::ResultAlreadySubmitted,
+        // bet count, window start and submission deadline are checked in require_betting_window
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        init,
+        payer = player,
+        space = 8 + BetCommitment::INIT_SPACE,
+        seeds = [b"commitment", game.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(
+        init,
+        payer = player,
+        space = CommitmentReceipt::LEN,
+        seeds = [RECEIPT_SEED, game.key().as_ref(), player.key().as_ref(), commitment.as_ref()],
+        bump
+    )]
+    pub commitment_receipt: Account<'info, CommitmentReceipt>,
+    // created with the bet when game.audit_log_enabled, never closed
+    #[account(
+        init,
+        payer = player,
+        space = CommitmentAuditLog::LEN,
+        seeds = [AUDIT_SEED, bet_commitment.key().as_ref()],
+        bump
+    )]
+    pub audit_log: Option<Account<'info, CommitmentAuditLog>>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    #[account(
+        constraint = nft_token_account.owner == player.key() @ GameError::InvalidNftGateToken,
+        // ungated games don't need this, use commit_bet
+        constraint = Some(nft_token_account.mint) == game.nft_gate_mint @ GameError::InvalidNftGateToken,
+        constraint = nft_token_account.amount >= 1 @ GameError::InvalidNftGateToken,
+    )]
+    pub nft_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when game.anti_sybil_stake is set
+    #[account(mut, seeds = [ANTI_SYBIL_VAULT_SEED, game.key().as_ref()], bump)]
+    pub anti_sybil_vault: Option<SystemAccount<'info>>,
+    // required when game.progressive_jackpot_contribution_bps is set
+    #[account(mut, seeds = [GLOBAL_JACKPOT_SEED], bump = global_jackpot.bump)]
+    pub global_jackpot: Option<Box<Account<'info, GlobalJackpot>>>,
+    // required when game.unique_commitments is set, init fails if another bet already used the commitment
+    #[account(
+        init,
+        payer = player,
+        space = CommitmentMarker::LEN,
+        seeds = [UNIQUE_COMMITMENT_SEED, game.key().as_ref(), commitment.as_ref()],
+        bump
+    )]
+    pub commitment_marker: Option<Box<Account<'info, CommitmentMarker>>>,
+}
+
+#[derive(Accounts)]
+#[instruction(escrow_id: u64, beneficiary: Pubkey, commitment: [u8; 32])]
+pub struct CommitViaEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.phase()? == BetPhase::Open @ GameError::ResultAlreadySubmitted,
+        constraint = game.result.is_none() @ GameError::ResultAlreadySubmitted,
+        // bet count, window start and submission deadline are checked in require_betting_window
+        // escrows can't prove the beneficiary holds the gate NFT
+        constraint = game.nft_gate_mint.is_none() @ GameError::NftGateRequired,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        init,
+        payer = owner,
+        space = BetEscrow::LEN,
+        seeds = [ESCROW_SEED, game.key().as_ref(), &escrow_id.to_le_bytes()],
+        bump
+    )]
+    pub bet_escrow: Account<'info, BetEscrow>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    // pays the stake and is the only one who can reveal
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when game.unique_commitments is set, init fails if another bet already used the commitment
+    #[account(
+        init,
+        payer = owner,
+        space = CommitmentMarker::LEN,
+        seeds = [UNIQUE_COMMITMENT_SEED, game.key().as_ref(), commitment.as_ref()],
+        bump
+    )]
+    pub commitment_marker: Option<Box<Account<'info, CommitmentMarker>>>,
+    // required when game.anti_sybil_stake is set
+    #[account(mut, seeds = [ANTI_SYBIL_VAULT_SEED, game.key().as_ref()], bump)]
+    pub anti_sybil_vault: Option<SystemAccount<'info>>,
+    // required when game.progressive_jackpot_contribution_bps is set
+    #[account(mut, seeds = [GLOBAL_JACKPOT_SEED], bump = global_jackpot.bump)]
+    pub global_jackpot: Option<Box<Account<'info, GlobalJackpot>>>,
+}
+
+#[derive(Accounts)]
+#[instruction(result: u8)] // Removed timestamp instruction parameter
+pub struct SubmitResult<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.can_submit_result(signer.key) @ GameError::UnauthorizedSigner,
+        constraint = !game.hidden_result_mode @ GameError::HiddenResultMode,
+        // early_close_betting closes bets before there's a result, a pending oracle result is the only other way
+        constraint = (game.is_open_for_bets || game.pending_result.is_none()) @ GameError::RevealPeriodClosed,
+        constraint = game.result.is_none() @ GameError::ResultAlreadySubmitted,
+        constraint = game.submission_deadline.is_some() @ GameError::DeadlineNotSet,
+        constraint = game.result_deadline().is_some_and(|result_deadline| clock.unix_timestamp < result_deadline) @ GameError::SubmissionPeriodExpired,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    // authority, multisig or co-authority
+    pub signer: Signer<'info>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: address checked, read to find the Ed25519 instruction verifying the result signature
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenRevealPeriod<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.result.is_some() @ GameError::ResultNotSubmitted,
+        constraint = game.phase()? == BetPhase::Closed @ GameError::InvalidGamePhase,
+        constraint = game.reveal_deadline.is_some_and(|reveal_deadline| clock.unix_timestamp < reveal_deadline) @ GameError::RevealPeriodClosed,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitBetMerkleRoot<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = !game.is_open_for_bets @ GameError::InvalidGamePhase,
+        constraint = game.bet_merkle_root.is_none() @ GameError::BetMerkleRootAlreadySet,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTestResult<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.is_test_mode @ GameError::NotTestMode,
+        constraint = game.result.is_none() @ GameError::ResultAlreadySubmitted,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCommitRevealSameTx<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.is_test_mode @ GameError::NotTestMode,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeDisputedResult<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.is_disputed @ GameError::ResultNotDisputed,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePlayerPnl<'info> {
+    #[account(
+        init,
+        payer = player,
+        space = PlayerPnL::LEN,
+        seeds = [PLAYER_PNL_SEED, player.key().as_ref()],
+        bump
+    )]
+    pub player_pnl: Account<'info, PlayerPnL>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(code: [u8; REFERRAL_CODE_LENGTH])]
+pub struct RegisterReferralCode<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = ReferralCode::LEN,
+        seeds = [REFERRAL_CODE_SEED, code.as_ref()],
+        bump
+    )]
+    pub referral_code: Account<'info, ReferralCode>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePlayerReputation<'info> {
+    #[account(
+        init,
+        payer = player,
+        space = PlayerReputation::LEN,
+        seeds = [REPUTATION_SEED, player.key().as_ref()],
+        bump
+    )]
+    pub player_reputation: Account<'info, PlayerReputation>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(bet_value: u8, salt: u64)]
+pub struct RevealAndClaim<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.pending_result.is_none() @ GameError::ResultPendingConfirmation,
+        constraint = (game.is_open_for_reveals || game.commit_reveal_same_tx) @ GameError::RevealPeriodClosed,
+        // reveal window and player pot are checked in require_reveal_window
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        // close = player,
+        seeds = [b"commitment", game.key().as_ref(), bet_commitment.original_player.as_ref()],
+        bump,
+        constraint = bet_commitment.player == player.key() @ GameError::InvalidPlayerForCommitment,
+        // a minted proof of bet owns the bet until redeem_proof_of_bet burns it
+        constraint = bet_commitment.proof_of_bet_mint.is_none() @ GameError::ProofOfBetOutstanding,
+        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
+        // bet must not be claimed
+        constraint = !bet_commitment.is_claimed @ GameError::BetAlreadySettled,
+        constraint = !bet_commitment.is_frozen @ GameError::CommitmentFrozen,
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when game.audit_log_enabled
+    #[account(mut, seeds = [AUDIT_SEED, bet_commitment.key().as_ref()], bump = audit_log.bump)]
+    pub audit_log: Option<Account<'info, CommitmentAuditLog>>,
+    // create with initialize_player_pnl. required while game.cooldown_between_reveals_slots is set,
+    // it keeps the slot of the player's last reveal
+    #[account(mut, seeds = [PLAYER_PNL_SEED, player.key().as_ref()], bump = player_pnl.bump)]
+    pub player_pnl: Option<Account<'info, PlayerPnL>>,
+    // optional, only updated if the player has created one with initialize_player_reputation
+    #[account(mut, seeds = [REPUTATION_SEED, player.key().as_ref()], bump = player_reputation.bump)]
+    pub player_reputation: Option<Account<'info, PlayerReputation>>,
+    // required when game.custom_payout_curve
+    #[account(seeds = [PAYOUT_CURVE_SEED, game.key().as_ref()], bump = payout_curve.bump)]
+    pub payout_curve: Option<Account<'info, PayoutCurve>>,
+    // required when the bet has an anti sybil stake to refund
+    #[account(mut, seeds = [ANTI_SYBIL_VAULT_SEED, game.key().as_ref()], bump)]
+    pub anti_sybil_vault: Option<SystemAccount<'info>>,
+    // both required when game.callback_program is set, the whitelist is checked before every callback
+    #[account(seeds = [PROGRAM_STATS_SEED], bump = program_stats.bump)]
+    pub program_stats: Option<Account<'info, ProgramStats>>,
+    /// CHECK: only invoked, address checked against the game's callback program
+    #[account(executable, constraint = game.callback_program == Some(callback_program.key()) @ GameError::InvalidCallbackProgram)]
+    pub callback_program: Option<UncheckedAccount<'info>>,
+    // optional, an exact guess wins the jackpot if it's passed and not yet won this epoch
+    #[account(mut, seeds = [GLOBAL_JACKPOT_SEED], bump = global_jackpot.bump)]
+    pub global_jackpot: Option<Box<Account<'info, GlobalJackpot>>>,
+    // optional, only updated while it isn't frozen
+    #[account(mut, seeds = [LEADERBOARD_SEED, game.key().as_ref()], bump = leaderboard.bump)]
+    pub leaderboard: Option<Account<'info, Leaderboard>>,
+    // optional, reveals that pass it in are counted in the histogram
+    #[account(mut, seeds = [DISTRIBUTION_SEED, game.key().as_ref()], bump = bet_distribution.bump)]
+    pub bet_distribution: Option<Account<'info, BetDistribution>>,
+    /// CHECK: only receives lamports, address checked against the game's burn address
+    // required when game.burn_losing_bets and the bet lost
+    #[account(mut, address = game.burn_address @ GameError::InvalidBurnAddress)]
+    pub burn_address: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct RevealViaEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.pending_result.is_none() @ GameError::ResultPendingConfirmation,
+        constraint = game.is_open_for_reveals @ GameError::RevealPeriodClosed,
+        // reveal window and player pot are checked in require_reveal_window
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, game.key().as_ref(), &bet_escrow.escrow_id.to_le_bytes()],
+        bump = bet_escrow.bump,
+        has_one = owner @ GameError::InvalidEscrowOwner,
+        constraint = bet_escrow.bet.game == game.key() @ GameError::InvalidGameReference,
+        constraint = !bet_escrow.bet.is_claimed @ GameError::BetAlreadySettled,
+        constraint = !bet_escrow.bet.is_frozen @ GameError::CommitmentFrozen,
+    )]
+    pub bet_escrow: Account<'info, BetEscrow>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    // receives the payout
+    #[account(mut, address = bet_escrow.beneficiary @ GameError::InvalidBeneficiary)]
+    pub beneficiary: SystemAccount<'info>,
+    // gets the anti sybil stake back
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when game.custom_payout_curve
+    #[account(seeds = [PAYOUT_CURVE_SEED, game.key().as_ref()], bump = payout_curve.bump)]
+    pub payout_curve: Option<Account<'info, PayoutCurve>>,
+    /// CHECK: only receives lamports, address checked against the game's burn address
+    // required when game.burn_losing_bets and the bet lost
+    #[account(mut, address = game.burn_address @ GameError::InvalidBurnAddress)]
+    pub burn_address: Option<UncheckedAccount<'info>>,
+    // owner's PlayerPnL, required while game.cooldown_between_reveals_slots is set. only the last reveal slot is used
+    #[account(mut, seeds = [PLAYER_PNL_SEED, owner.key().as_ref()], bump = owner_pnl.bump)]
+    pub owner_pnl: Option<Account<'info, PlayerPnL>>,
+    // required when the bet has an anti sybil stake to refund
+    #[account(mut, seeds = [ANTI_SYBIL_VAULT_SEED, game.key().as_ref()], bump)]
+    pub anti_sybil_vault: Option<SystemAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimNextTranche<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.total_player_pot >= bet_commitment.pending_payout_amount @ GameError::InsufficientPlayerPot,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        seeds = [b"commitment", game.key().as_ref(), bet_commitment.original_player.as_ref()],
+        bump,
+        constraint = bet_commitment.player == player.key() @ GameError::InvalidPlayerForCommitment,
+        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
+        // an unclaimed bet's pending_payout_amount is an unpaid reveal, that's withdraw_unpaid_bet
+        constraint = bet_commitment.is_claimed && bet_commitment.pending_payout_amount > 0 @ GameError::NoLockedPayout,
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimBiggestLoserPrize<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.reveal_deadline.is_some() @ GameError::DeadlineNotSet,
+        // the biggest loser can still change until every reveal is in
+        constraint = Some(clock.unix_timestamp) >= game.reveal_deadline @ GameError::RevealDeadlineNotReached,
+        constraint = game.biggest_loser_prize_lamports > 0 @ GameError::NoBiggestLoserPrize,
+        constraint = game.biggest_loser == Some(player.key()) @ GameError::NotBiggestLoser,
+        constraint = !game.biggest_loser_prize_claimed @ GameError::BiggestLoserPrizeAlreadyClaimed,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimHeldPayout<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.reveal_deadline.is_some() @ GameError::DeadlineNotSet,
+        // held payouts release once the authority finalizes the result, or on their own at the reveal
+        // deadline so an authority that never finalizes can't keep them
+        constraint = (!game.is_disputed || Some(clock.unix_timestamp) >= game.reveal_deadline) @ GameError::ResultDisputed,
+        constraint = game.total_player_pot >= bet_commitment.held_payout @ GameError::InsufficientPlayerPot,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        seeds = [b"commitment", game.key().as_ref(), bet_commitment.original_player.as_ref()],
+        bump,
+        constraint = bet_commitment.player == player.key() @ GameError::InvalidPlayerForCommitment,
+        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
+        constraint = bet_commitment.held_payout > 0 @ GameError::NoHeldPayout,
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when game.audit_log_enabled
+    #[account(mut, seeds = [AUDIT_SEED, bet_commitment.key().as_ref()], bump = audit_log.bump)]
+    pub audit_log: Option<Account<'info, CommitmentAuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnpaidBet<'info> {
+    #[account(mut, seeds = [GLOBAL_GAME_SEED], bump = game.bump, constraint = game.is_open_for_reveals @ GameError::RevealPeriodClosed, constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen)]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        // close = player,
+        seeds = [b"commitment", game.key().as_ref(), bet_commitment.original_player.as_ref()],
+        bump,
+        constraint = bet_commitment.player == player.key() @ GameError::InvalidPlayerForCommitment,
+        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
+        constraint = bet_commitment.attempted_reveal @ GameError::BetAlreadySettled,
+        // bet must not be claimed
+        constraint = !bet_commitment.is_claimed @ GameError::BetAlreadySettled,
+        // withdraw window and player pot are checked in the handler
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(
+        mut,
+        seeds = [b"treasury", game.key().as_ref()],
+        bump = game.treasury_bump
+    )]
+    pub game_treasury: SystemAccount<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when game.audit_log_enabled
+    #[account(mut, seeds = [AUDIT_SEED, bet_commitment.key().as_ref()], bump = audit_log.bump)]
+    pub audit_log: Option<Account<'info, CommitmentAuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimBetOnTimeout<'info> {
+    // Game account needed to check deadline and authority for seeds
+    #[account(
+        mut, 
+        seeds = [GLOBAL_GAME_SEED], 
+        bump = game.bump, 
+        constraint = game.result.is_none() @ GameError::ResultAlreadySubmitted, 
+        constraint = game.submission_deadline.is_some() @ GameError::DeadlineNotSet,
+        // authority gets the grace period on top of the deadline before refunds open
+        constraint = game.result_deadline().is_some_and(|result_deadline| clock.unix_timestamp > result_deadline) @ GameError::SubmissionPeriodExpired,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        // close = player, // Return rent to player
+        seeds = [b"commitment", game.key().as_ref(), bet_commitment.original_player.as_ref()],
+        bump,
+        constraint = bet_commitment.player == player.key() @ GameError::InvalidPlayerForCommitment,
+        // a minted proof of bet owns the bet until redeem_proof_of_bet burns it
+        constraint = bet_commitment.proof_of_bet_mint.is_none() @ GameError::ProofOfBetOutstanding,
+        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
+        constraint = !bet_commitment.is_claimed @ GameError::BetAlreadySettled,
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(
+        mut,
+        seeds = [b"treasury", game.key().as_ref()],
+        bump = game.treasury_bump
+    )]
+    pub game_treasury: SystemAccount<'info>,
+    #[account(mut)] // Player signs to reclaim and receive rent/funds
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when game.audit_log_enabled
+    #[account(mut, seeds = [AUDIT_SEED, bet_commitment.key().as_ref()], bump = audit_log.bump)]
+    pub audit_log: Option<Account<'info, CommitmentAuditLog>>,
+    // optional, only updated if the player has created one with initialize_player_reputation
+    #[account(mut, seeds = [REPUTATION_SEED, player.key().as_ref()], bump = player_reputation.bump)]
+    pub player_reputation: Option<Account<'info, PlayerReputation>>,
+    // required when the bet has an anti sybil stake to refund
+    #[account(mut, seeds = [ANTI_SYBIL_VAULT_SEED, game.key().as_ref()], bump)]
+    pub anti_sybil_vault: Option<SystemAccount<'info>>,
+}
+
+
+#[derive(Accounts)]
+pub struct WithdrawMissedRevealBet<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.result.is_some() @ GameError::ResultNotSubmitted,
+        constraint = game.reveal_deadline.is_some_and(|reveal_deadline| clock.unix_timestamp >= reveal_deadline) @ GameError::RevealDeadlineNotReached,
+        constraint = clock.unix_timestamp < game.scheduled_final_claim_deadline @ GameError::WithdrawPeriodNotReached,
+        constraint = game.total_player_pot >= bet_commitment.amount @ GameError::InsufficientPlayerPot,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        seeds = [b"commitment", game.key().as_ref(), bet_commitment.original_player.as_ref()],
+        bump,
+        constraint = bet_commitment.player == player.key() @ GameError::InvalidPlayerForCommitment,
+        // a minted proof of bet owns the bet until redeem_proof_of_bet burns it
+        constraint = bet_commitment.proof_of_bet_mint.is_none() @ GameError::ProofOfBetOutstanding,
+        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
+        // a reveal that failed on liquidity goes through withdraw_unpaid_bet instead
+        constraint = !bet_commitment.is_claimed && !bet_commitment.attempted_reveal @ GameError::BetAlreadySettled,
+        constraint = !bet_commitment.is_frozen @ GameError::CommitmentFrozen,
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when game.audit_log_enabled
+    #[account(mut, seeds = [AUDIT_SEED, bet_commitment.key().as_ref()], bump = audit_log.bump)]
+    pub audit_log: Option<Account<'info, CommitmentAuditLog>>,
+}
+
+#[derive(Accounts)]
+#[instruction(player: Pubkey)]
+pub struct SweepStaleCommitment<'info> {
+    #[account(
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.last_player_deadline().is_some_and(|deadline| clock.unix_timestamp >= deadline) @ GameError::SweepPeriodNotReached,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        seeds = [b"commitment", game.key().as_ref(), player.as_ref()],
+        bump,
+        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
+        // refunded on reveal and timeout, so anything left was never revealed
+        constraint = bet_commitment.anti_sybil_stake > 0 @ GameError::NoAntiSybilStake,
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(mut, seeds = [ANTI_SYBIL_VAULT_SEED, game.key().as_ref()], bump)]
+    pub anti_sybil_vault: SystemAccount<'info>,
+    #[account(mut)]
+    pub sweeper: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+#[instruction(player: Pubkey)]
+pub struct ClawbackUnrevealedBet<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.last_player_deadline().is_some_and(|deadline| clock.unix_timestamp >= deadline.saturating_add(CLAWBACK_DELAY_SECONDS)) @ GameError::ClawbackPeriodNotReached,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"commitment", game.key().as_ref(), player.as_ref()],
+        bump,
+        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
+        // revealed but unpaid bets are still owed to the player
+        constraint = !bet_commitment.is_claimed && !bet_commitment.attempted_reveal @ GameError::BetAlreadySettled,
+        // closing it would strand the stake in the vault
+        constraint = bet_commitment.anti_sybil_stake == 0 @ GameError::AntiSybilStakeOutstanding,
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when game.audit_log_enabled
+    #[account(mut, seeds = [AUDIT_SEED, bet_commitment.key().as_ref()], bump = audit_log.bump)]
+    pub audit_log: Option<Account<'info, CommitmentAuditLog>>,
+}
+
+// read only, anyone can call it
+#[derive(Accounts)]
+pub struct RevealDeadlineHeartbeat<'info> {
+    #[account(seeds = [GLOBAL_GAME_SEED], bump = game.bump, constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen)]
+    pub game: Account<'info, Game>,
+}
+
+#[derive(Accounts)]
+pub struct CheckAndExtendRevealDeadline<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.auto_extension_enabled @ GameError::AutoExtensionDisabled,
+        constraint = !game.reveal_deadline_extended @ GameError::RevealDeadlineAlreadyExtended,
+        constraint = game.final_claim_deadline_extension_count < MAX_FINAL_CLAIM_DEADLINE_EXTENSIONS @ GameError::FinalClaimDeadlineExtensionLimit,
+        constraint = game.reveal_deadline.is_some() @ GameError::DeadlineNotSet,
+        constraint = Some(clock.unix_timestamp) >= game.reveal_deadline @ GameError::RevealDeadlineNotReached,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+#[instruction(player: Pubkey)]
+pub struct FlagSuspiciousCommitment<'info> {
+    #[account(
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        seeds = [b"commitment", game.key().as_ref(), player.as_ref()],
+        bump,
+        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
+        constraint = !bet_commitment.is_claimed @ GameError::BetAlreadySettled,
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    #[account(
+        init,
+        payer = authority,
+        space = BettingAnomaly::LEN,
+        seeds = [ANOMALY_SEED, game.key().as_ref(), player.as_ref()],
+        bump
+    )]
+    pub betting_anomaly: Account<'info, BettingAnomaly>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when game.audit_log_enabled
+    #[account(mut, seeds = [AUDIT_SEED, bet_commitment.key().as_ref()], bump = audit_log.bump)]
+    pub audit_log: Option<Account<'info, CommitmentAuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeEmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteEmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// pure computation, the clock is only there so the cpi client gets an accounts struct with a lifetime
+#[derive(Accounts)]
+pub struct PreviewPayout<'info> {
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct TransferBetOwnership<'info> {
+    #[account(
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        // open until reveals close, including before the result is in
+        constraint = game.reveal_deadline.is_none_or(|reveal_deadline| clock.unix_timestamp < reveal_deadline) @ GameError::RevealPeriodClosed,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        seeds = [b"commitment", game.key().as_ref(), bet_commitment.original_player.as_ref()],
+        bump,
+        constraint = bet_commitment.player == owner.key() @ GameError::InvalidPlayerForCommitment,
+        // a minted proof of bet owns the bet until redeem_proof_of_bet burns it
+        constraint = bet_commitment.proof_of_bet_mint.is_none() @ GameError::ProofOfBetOutstanding,
+        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
+        constraint = !bet_commitment.is_claimed @ GameError::BetAlreadySettled,
+        constraint = !bet_commitment.is_frozen @ GameError::CommitmentFrozen,
+    )]
+    pub bet_commitment: Account<'info, BetCommitment>,
+    // current bet_commitment.player
+    pub owner: Signer<'info>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    // required when game.audit_log_enabled
+    #[account(mut, seeds = [AUDIT_SEED, bet_commitment.key().as_ref()], bump = audit_log.bump)]
+    pub audit_log: Option<Account<'info, CommitmentAuditLog>>,
+}
+
+// the commitments themselves come in remaining_accounts, see set_commitments_frozen
+#[derive(Accounts)]
+pub struct BatchFreezeCommitments<'info> {
+    #[account(
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRemainingTreasury<'info> {
+    #[account(
+        mut,
+        // close = authority,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        seeds = [GLOBAL_GAME_SEED],
+        constraint = game.result.is_some() @ GameError::ResultAlreadySubmitted,
+        constraint = game.reveal_deadline.is_some() @ GameError::DeadlineNotSet,
+        constraint = Some(clock.unix_timestamp) >= game.reveal_deadline @ GameError::SubmissionPeriodExpired,
+        constraint = game.final_claim_deadline.is_none() || Some(clock.unix_timestamp) >= game.final_claim_deadline @ GameError::TreasuryClaimPeriodNotReached,
+        // check_and_extend_reveal_deadline has to get its chance before the host can drain the treasury
+        constraint = !game.reveal_extension_pending() @ GameError::RevealExtensionPending,
+        bump = game.bump,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(mut)] // Authority signs to trigger claim
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"treasury", game.key().as_ref()],
+        bump = game.treasury_bump
+    )]
+    pub game_treasury: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: only receives lamports, address checked against the game's host fee escrow
+    // required when game.host_fee_escrow is set
+    #[account(mut, constraint = game.host_fee_escrow == Some(host_fee_escrow.key()) @ GameError::InvalidHostFeeEscrow)]
+    pub host_fee_escrow: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> ClaimRemainingTreasury<'info> {
+    // where the host's claims are paid, the escrow when the game has one
+    pub fn host_claim_recipient(&self) -> Result<AccountInfo<'info>> {
+        match (self.game.host_fee_escrow, &self.host_fee_escrow) {
+            (None, _) => Ok(self.authority.to_account_info()),
+            (Some(_), Some(host_fee_escrow)) => Ok(host_fee_escrow.to_account_info()),
+            (Some(_), None) => err!(GameError::HostFeeEscrowRequired),
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct EarlyCloseBetting<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.early_close_enabled @ GameError::EarlyCloseNotEnabled,
+        constraint = game.phase()? == BetPhase::Open @ GameError::ResultAlreadySubmitted,
+        constraint = game.submission_deadline.is_some_and(|submission_deadline| clock.unix_timestamp < submission_deadline) @ GameError::SubmissionDeadlineNotReached,
+        constraint = game.total_player_pot >= game.early_close_min_pot @ GameError::PotBelowMinForEarlyClose,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct RebalanceExcess<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.treasury_min_utilization_bps > 0 @ GameError::RebalanceDisabled,
+        constraint = game.reveal_deadline.is_some_and(|reveal_deadline| clock.unix_timestamp >= reveal_deadline) @ GameError::RevealDeadlineNotReached,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+#[instruction(winner: Pubkey)]
+pub struct MintWinnerTrophy<'info> {
+    #[account(
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.mint_winner_nft @ GameError::TrophiesDisabled,
+        constraint = game.result.is_some() @ GameError::ResultNotSubmitted,
+        constraint = game.reveal_deadline.is_some() @ GameError::DeadlineNotSet,
+        constraint = Some(clock.unix_timestamp) >= game.reveal_deadline @ GameError::RevealDeadlineNotReached,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Box<Account<'info, Game>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"commitment", game.key().as_ref(), winner.as_ref()],
+        bump,
+        constraint = bet_commitment.is_claimed @ GameError::NotAWinner,
+        constraint = bet_commitment.payout_amount > bet_commitment.amount @ GameError::NotAWinner,
+    )]
+    pub bet_commitment: Box<Account<'info, BetCommitment>>,
+    /// CHECK: only receives the trophy, must match the winner arg
+    #[account(address = winner)]
+    pub winner_account: UncheckedAccount<'info>,
+    // mint and update authority of every trophy
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    // one trophy per player per game, init fails on a second mint
+    #[account(
+        init,
+        payer = authority,
+        seeds = [TROPHY_SEED, game.key().as_ref(), winner.as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = game_treasury,
+        mint::freeze_authority = game_treasury,
+    )]
+    pub trophy_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = trophy_mint,
+        associated_token::authority = winner_account,
+    )]
+    pub winner_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: created by the token metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), trophy_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    /// CHECK: created by the token metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), trophy_mint.key().as_ref(), b"edition"],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
 
 #[derive(Accounts)]
-#[instruction()]
-pub struct InitializeGame<'info> {
+pub struct MintProofOfBet<'info> {
+    #[account(
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.reveal_deadline.is_none_or(|reveal_deadline| clock.unix_timestamp < reveal_deadline) @ GameError::RevealPeriodClosed,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Box<Account<'info, Game>>,
+    #[account(
+        mut,
+        seeds = [b"commitment", game.key().as_ref(), bet_commitment.original_player.as_ref()],
+        bump,
+        constraint = bet_commitment.player == player.key() @ GameError::InvalidPlayerForCommitment,
+        constraint = !bet_commitment.is_claimed && !bet_commitment.attempted_reveal @ GameError::BetAlreadySettled,
+        constraint = bet_commitment.proof_of_bet_mint.is_none() @ GameError::ProofOfBetOutstanding,
+    )]
+    pub bet_commitment: Box<Account<'info, BetCommitment>>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    // mint and update authority of every proof of bet
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
+    pub game_treasury: SystemAccount<'info>,
+    // one per bet, init fails on a second mint even after the first is redeemed
+    #[account(
+        init,
+        payer = player,
+        seeds = [PROOF_OF_BET_SEED, bet_commitment.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = game_treasury,
+        mint::freeze_authority = game_treasury,
+    )]
+    pub proof_of_bet_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = player,
+        associated_token::mint = proof_of_bet_mint,
+        associated_token::authority = player,
+    )]
+    pub player_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: created by the token metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), proof_of_bet_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    /// CHECK: created by the token metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), proof_of_bet_mint.key().as_ref(), b"edition"],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemProofOfBet<'info> {
+    #[account(seeds = [GLOBAL_GAME_SEED], bump = game.bump, constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen)]
+    pub game: Box<Account<'info, Game>>,
+    #[account(
+        mut,
+        seeds = [b"commitment", game.key().as_ref(), bet_commitment.original_player.as_ref()],
+        bump,
+        constraint = bet_commitment.proof_of_bet_mint == Some(proof_of_bet_mint.key()) @ GameError::InvalidProofOfBet,
+    )]
+    pub bet_commitment: Box<Account<'info, BetCommitment>>,
+    #[account(mut)]
+    pub proof_of_bet_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        token::mint = proof_of_bet_mint,
+        token::authority = holder,
+        constraint = holder_token_account.amount == 1 @ GameError::InvalidProofOfBet,
+    )]
+    pub holder_token_account: Box<Account<'info, TokenAccount>>,
+    pub holder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    // required when game.audit_log_enabled
+    #[account(mut, seeds = [AUDIT_SEED, bet_commitment.key().as_ref()], bump = audit_log.bump)]
+    pub audit_log: Option<Account<'info, CommitmentAuditLog>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLeaderboard<'info> {
+    #[account(
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        init,
+        payer = authority,
+        space = Leaderboard::LEN,
+        seeds = [LEADERBOARD_SEED, game.key().as_ref()],
+        bump
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeLeaderboard<'info> {
+    #[account(
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.reveal_deadline.is_some() @ GameError::DeadlineNotSet,
+        constraint = Some(clock.unix_timestamp) >= game.reveal_deadline @ GameError::RevealDeadlineNotReached,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        mut,
+        seeds = [LEADERBOARD_SEED, game.key().as_ref()],
+        bump = leaderboard.bump,
+        constraint = !leaderboard.is_frozen @ GameError::LeaderboardFrozen,
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+    pub authority: Signer<'info>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeBetDistribution<'info> {
+    #[account(
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        init,
+        payer = authority,
+        space = BetDistribution::LEN,
+        seeds = [DISTRIBUTION_SEED, game.key().as_ref()],
+        bump
+    )]
+    pub bet_distribution: Account<'info, BetDistribution>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeResultsFeed<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = HistoricalResultsFeed::LEN,
+        seeds = [HISTORY_SEED],
+        bump
+    )]
+    pub results_feed: Account<'info, HistoricalResultsFeed>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateGame<'info> {
+    /// CHECK: still in the v1 layout so it can't be loaded as Account<Game>, read through GameSnapshot instead
+    #[account(mut, seeds = [GLOBAL_GAME_SEED], bump, owner = crate::ID)]
+    pub game: UncheckedAccount<'info>,
+    // gets the rent reserve, v1 games never funded one
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump)]
+    pub game_treasury: SystemAccount<'info>,
+    // pays the rent for the bigger layout and the treasury rent reserve
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetTreasuryMultisig<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthorityTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthorityTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.pending_authority == Some(new_authority.key()) @ GameError::NoPendingAuthority,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuthorityTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCharityMode<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.bet_count == 0 @ GameError::BetsAlreadyCommitted,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePayoutCurve<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.bet_count == 0 @ GameError::BetsAlreadyCommitted,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        init,
+        payer = authority,
+        space = PayoutCurve::LEN,
+        seeds = [PAYOUT_CURVE_SEED, game.key().as_ref()],
+        bump
+    )]
+    pub payout_curve: Account<'info, PayoutCurve>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPayoutCurve<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.bet_count == 0 @ GameError::BetsAlreadyCommitted,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmResult<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.result_oracle == Some(result_oracle.key()) @ GameError::InvalidResultOracle,
+        constraint = game.result.is_none() @ GameError::ResultAlreadySubmitted,
+        constraint = game.pending_result.is_some() @ GameError::NoPendingResult,
+        // too late once players can reclaim on timeout
+        constraint = game.result_deadline().is_some_and(|result_deadline| clock.unix_timestamp < result_deadline) @ GameError::SubmissionPeriodExpired,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub result_oracle: Signer<'info>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct PreCommitResultHash<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.bet_count == 0 @ GameError::BetsAlreadyCommitted,
+        constraint = game.submission_deadline.is_some_and(|submission_deadline| clock.unix_timestamp < submission_deadline) @ GameError::SubmissionPeriodExpired,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeHiddenResult<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.bet_count == 0 @ GameError::BetsAlreadyCommitted,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
+    pub game: Account<'info, Game>,
+    #[account(
+        init,
+        payer = authority,
+        space = ResultDecryption::LEN,
+        seeds = [RESULT_DECRYPTION_SEED, game.key().as_ref()],
+        bump
+    )]
+    pub result_decryption: Account<'info, ResultDecryption>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitEncryptedResult<'info> {
     #[account(
-        init,
-        payer = payer,
-        space = Game::LEN,
+        mut,
         seeds = [GLOBAL_GAME_SEED],
-        bump
+        bump = game.bump,
+        constraint = game.can_submit_result(signer.key) @ GameError::UnauthorizedSigner,
+        constraint = game.hidden_result_mode @ GameError::NotHiddenResultMode,
+        constraint = (game.is_open_for_bets || game.pending_result.is_none()) @ GameError::RevealPeriodClosed,
+        constraint = game.result.is_none() @ GameError::ResultAlreadySubmitted,
+        constraint = game.result_deadline().is_some_and(|result_deadline| clock.unix_timestamp < result_deadline) @ GameError::SubmissionPeriodExpired,
+        // there's no salt to open it with, the decrypted result can't be checked against it
+        constraint = game.result_submission_hash == [0; 32] @ GameError::ResultPreCommitted,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
     )]
     pub game: Account<'info, Game>,
-    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump)]
-    pub game_treasury: SystemAccount<'info>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        seeds = [RESULT_DECRYPTION_SEED, game.key().as_ref()],
+        bump = result_decryption.bump,
+        constraint = result_decryption.encrypted_result.is_none() @ GameError::ResultAlreadySubmitted,
+    )]
+    pub result_decryption: Account<'info, ResultDecryption>,
+    // authority or co-authority
+    pub signer: Signer<'info>,
+    #[account(address = sysvar::clock::ID)]
+    pub clock: Sysvar<'info, Clock>,
 }
 
 #[derive(Accounts)]
-#[instruction(commitment: [u8; 32], amount: u64)]
-pub struct CommitBet<'info> {
+pub struct SubmitDecryptionShare<'info> {
     #[account(
-        mut, 
-        seeds = [GLOBAL_GAME_SEED], 
-        bump = game.bump, 
-        constraint = game.is_open_for_bets && !game.is_open_for_reveals @ GameError::ResultAlreadySubmitted, 
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
         constraint = game.result.is_none() @ GameError::ResultAlreadySubmitted,
-        constraint = game.submission_deadline.is_some() @ GameError::DeadlineNotSet,
-        constraint = Some(clock.unix_timestamp) < game.submission_deadline @ GameError::SubmissionDeadlineNotReached,
+        constraint = game.result_deadline().is_some_and(|result_deadline| clock.unix_timestamp < result_deadline) @ GameError::SubmissionPeriodExpired,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
     )]
     pub game: Account<'info, Game>,
     #[account(
-        init,
-        payer = player,
-        space = BetCommitment::LEN,
-        seeds = [b"commitment", game.key().as_ref(), player.key().as_ref()],
-        bump
+        mut,
+        seeds = [RESULT_DECRYPTION_SEED, game.key().as_ref()],
+        bump = result_decryption.bump,
+        constraint = result_decryption.encrypted_result.is_some() @ GameError::EncryptedResultNotSubmitted,
     )]
-    pub bet_commitment: Account<'info, BetCommitment>,
-    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
-    pub game_treasury: SystemAccount<'info>,
-    #[account(mut)]
-    pub player: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub result_decryption: Account<'info, ResultDecryption>,
+    pub keyholder: Signer<'info>,
     #[account(address = sysvar::clock::ID)]
     pub clock: Sysvar<'info, Clock>,
 }
 
+// anyone can call it once enough shares are in
 #[derive(Accounts)]
-#[instruction(result: u8)] // Removed timestamp instruction parameter
-pub struct SubmitResult<'info> {
+pub struct FinalizeDecryption<'info> {
     #[account(
         mut,
         seeds = [GLOBAL_GAME_SEED],
         bump = game.bump,
-        has_one = authority @ GameError::InvalidAuthority,
-        constraint = game.is_open_for_bets @ GameError::RevealPeriodClosed,
         constraint = game.result.is_none() @ GameError::ResultAlreadySubmitted,
-        constraint = game.submission_deadline.is_some() @ GameError::DeadlineNotSet,
-        constraint = Some(clock.unix_timestamp) < game.submission_deadline @ GameError::SubmissionPeriodExpired,
+        constraint = game.pending_result.is_none() @ GameError::ResultPendingConfirmation,
+        constraint = game.result_deadline().is_some_and(|result_deadline| clock.unix_timestamp < result_deadline) @ GameError::SubmissionPeriodExpired,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
     )]
     pub game: Account<'info, Game>,
-    pub authority: Signer<'info>,
+    #[account(
+        seeds = [RESULT_DECRYPTION_SEED, game.key().as_ref()],
+        bump = result_decryption.bump,
+        constraint = result_decryption.encrypted_result.is_some() @ GameError::EncryptedResultNotSubmitted,
+    )]
+    pub result_decryption: Account<'info, ResultDecryption>,
     #[account(address = sysvar::clock::ID)]
     pub clock: Sysvar<'info, Clock>,
 }
 
 #[derive(Accounts)]
-#[instruction(bet_value: u8, salt: u64)]
-pub struct RevealAndClaim<'info> {
+pub struct SetVrfPublicKey<'info> {
     #[account(
         mut,
         seeds = [GLOBAL_GAME_SEED],
         bump = game.bump,
-        constraint = game.is_open_for_reveals @ GameError::RevealPeriodClosed,
-        constraint = game.reveal_deadline.is_some() @ GameError::DeadlineNotSet,
-        constraint = Some(clock.unix_timestamp) < game.reveal_deadline @ GameError::RevealDeadlineNotReached,
-        // checking if total pot has the initial stakes. sanity check as total_player_pot should be in sync with player's initial stakes.
-        constraint = game.total_player_pot >= bet_commitment.amount @ GameError::InsufficientPlayerPot,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = game.bet_count == 0 @ GameError::BetsAlreadyCommitted,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
     )]
     pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeToCharity<'info> {
     #[account(
         mut,
-        // close = player,
-        seeds = [b"commitment", game.key().as_ref(), player.key().as_ref()],
-        bump,
-        constraint = bet_commitment.player == player.key() @ GameError::InvalidPlayerForCommitment,
-        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
-        // bet must not be claimed
-        constraint = !bet_commitment.is_claimed @ GameError::BetAlreadySettled,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_charity_mode @ GameError::CharityModeDisabled,
+        constraint = game.reveal_deadline.is_some_and(|reveal_deadline| clock.unix_timestamp >= reveal_deadline) @ GameError::RevealDeadlineNotReached,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
     )]
-    pub bet_commitment: Account<'info, BetCommitment>,
+    pub game: Account<'info, Game>,
     #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
     pub game_treasury: SystemAccount<'info>,
-    #[account(mut)]
-    pub player: Signer<'info>,
+    #[account(mut, constraint = game.charity_wallet == Some(charity_wallet.key()) @ GameError::InvalidCharityWallet)]
+    pub charity_wallet: SystemAccount<'info>,
     pub system_program: Program<'info, System>,
     #[account(address = sysvar::clock::ID)]
     pub clock: Sysvar<'info, Clock>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawUnpaidBet<'info> {
-    #[account(mut, seeds = [GLOBAL_GAME_SEED], bump = game.bump, constraint = game.is_open_for_reveals @ GameError::RevealPeriodClosed)]
+pub struct SetCoAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
+    )]
     pub game: Account<'info, Game>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseGame<'info> {
     #[account(
         mut,
-        // close = player,
-        seeds = [b"commitment", game.key().as_ref(), player.key().as_ref()],
-        bump,
-        constraint = bet_commitment.player == player.key() @ GameError::InvalidPlayerForCommitment,
-        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
-        constraint = bet_commitment.attempted_reveal @ GameError::BetAlreadySettled,
-        // withdraw period must be passed
+        close = authority,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
+        seeds = [GLOBAL_GAME_SEED],
+        constraint = game.result.is_some() @ GameError::ResultNotSubmitted,
         constraint = game.reveal_deadline.is_some() @ GameError::DeadlineNotSet,
-        constraint = Some(clock.unix_timestamp) > game.reveal_deadline @ GameError::WithdrawPeriodNotReached,
-        // final claim deadline must NOT be passed
-        constraint = game.final_claim_deadline.is_some() @ GameError::DeadlineNotSet,
-        constraint = Some(clock.unix_timestamp) < game.final_claim_deadline @ GameError::WithdrawPeriodNotReached,
-        // bet must not be claimed
-        constraint = !bet_commitment.is_claimed @ GameError::BetAlreadySettled,
-        // checking if total pot has the initial stakes. sanity check as total_player_pot should be in sync with player's initial stakes.
-        constraint = game.total_player_pot >= bet_commitment.amount @ GameError::InsufficientPlayerPot,
+        constraint = Some(clock.unix_timestamp) >= game.reveal_deadline @ GameError::RevealDeadlineNotReached,
+        constraint = game.final_claim_deadline.is_none() || Some(clock.unix_timestamp) >= game.final_claim_deadline @ GameError::TreasuryClaimPeriodNotReached,
+        constraint = !game.reveal_extension_pending() @ GameError::RevealExtensionPending,
+        bump = game.bump,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
     )]
-    pub bet_commitment: Account<'info, BetCommitment>,
+    pub game: Account<'info, Game>,
+    // treasury must be drained with claim_remaining_treasury first, otherwise those lamports are stranded
     #[account(
-        mut,
         seeds = [b"treasury", game.key().as_ref()],
-        bump = game.treasury_bump
+        bump = game.treasury_bump,
+        constraint = game_treasury.lamports() == 0 @ GameError::TreasuryNotEmpty,
     )]
     pub game_treasury: SystemAccount<'info>,
-    #[account(mut)]
-    pub player: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [PROGRAM_STATS_SEED], bump = program_stats.bump)]
+    pub program_stats: Account<'info, ProgramStats>,
+    #[account(mut, seeds = [HISTORY_SEED], bump = results_feed.bump)]
+    pub results_feed: Account<'info, HistoricalResultsFeed>,
+    #[account(mut, seeds = [AUTHORITY_GAMES_SEED, game.authority.as_ref()], bump = authority_games.bump)]
+    pub authority_games: Account<'info, ActiveGamesByAuthority>,
+    #[account(mut)] // Authority signs and receives the game account rent
+    pub authority: Signer<'info>,
     #[account(address = sysvar::clock::ID)]
     pub clock: Sysvar<'info, Clock>,
 }
 
 #[derive(Accounts)]
-pub struct ReclaimBetOnTimeout<'info> {
-    // Game account needed to check deadline and authority for seeds
-    #[account(
-        mut, 
-        seeds = [GLOBAL_GAME_SEED], 
-        bump = game.bump, 
-        constraint = game.result.is_none() @ GameError::ResultAlreadySubmitted, 
-        constraint = game.submission_deadline.is_some() @ GameError::DeadlineNotSet,
-        constraint = Some(clock.unix_timestamp) > game.submission_deadline @ GameError::SubmissionPeriodExpired,
-    )]
-    pub game: Account<'info, Game>,
-    #[account(
-        mut,
-        // close = player, // Return rent to player
-        seeds = [b"commitment", game.key().as_ref(), player.key().as_ref()],
-        bump,
-        constraint = bet_commitment.player == player.key() @ GameError::InvalidPlayerForCommitment,
-        constraint = bet_commitment.game == game.key() @ GameError::InvalidGameReference,
-        constraint = !bet_commitment.is_claimed @ GameError::BetAlreadySettled,
-    )]
-    pub bet_commitment: Account<'info, BetCommitment>,
+pub struct CloseStaleGame<'info> {
     #[account(
         mut,
-        seeds = [b"treasury", game.key().as_ref()],
-        bump = game.treasury_bump
+        close = caller,
+        seeds = [GLOBAL_GAME_SEED],
+        bump = game.bump,
+        constraint = clock.unix_timestamp > game.final_claim_deadline.unwrap_or(game.scheduled_final_claim_deadline).saturating_add(STALE_GAME_SECONDS) @ GameError::GameNotStale,
+        // nothing is owed to players anymore
+        constraint = game.total_player_pot == 0 @ GameError::PlayerPotNotEmpty,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
     )]
+    pub game: Account<'info, Game>,
+    #[account(mut, seeds = [b"treasury", game.key().as_ref()], bump = game.treasury_bump)]
     pub game_treasury: SystemAccount<'info>,
-    #[account(mut)] // Player signs to reclaim and receive rent/funds
-    pub player: Signer<'info>,
+    // must be PROTOCOL_DEAD_LETTER_PUBKEY, checked in the handler
+    #[account(mut)]
+    pub dead_letter: SystemAccount<'info>,
+    #[account(mut, seeds = [HISTORY_SEED], bump = results_feed.bump)]
+    pub results_feed: Account<'info, HistoricalResultsFeed>,
+    #[account(mut, seeds = [AUTHORITY_GAMES_SEED, game.authority.as_ref()], bump = authority_games.bump)]
+    pub authority_games: Account<'info, ActiveGamesByAuthority>,
+    // anyone, receives the game account rent
+    #[account(mut)]
+    pub caller: Signer<'info>,
     pub system_program: Program<'info, System>,
     #[account(address = sysvar::clock::ID)]
     pub clock: Sysvar<'info, Clock>,
 }
 
-
 #[derive(Accounts)]
-pub struct ClaimRemainingTreasury<'info> {
+pub struct CloseEmptyTreasury<'info> {
     #[account(
-        mut,
-        // close = authority,
-        has_one = authority @ GameError::InvalidAuthority,
+        constraint = game.is_authority(authority.key) @ GameError::InvalidAuthority,
         seeds = [GLOBAL_GAME_SEED],
-        constraint = game.result.is_some() @ GameError::ResultAlreadySubmitted,
+        constraint = game.result.is_some() @ GameError::ResultNotSubmitted,
         constraint = game.reveal_deadline.is_some() @ GameError::DeadlineNotSet,
-        constraint = Some(clock.unix_timestamp) >= game.reveal_deadline @ GameError::SubmissionPeriodExpired,
+        constraint = Some(clock.unix_timestamp) >= game.reveal_deadline @ GameError::RevealDeadlineNotReached,
         constraint = game.final_claim_deadline.is_none() || Some(clock.unix_timestamp) >= game.final_claim_deadline @ GameError::TreasuryClaimPeriodNotReached,
-        bump = game.bump
+        bump = game.bump,
+        constraint = !game.emergency_frozen @ GameError::GameEmergencyFrozen,
     )]
     pub game: Account<'info, Game>,
-    #[account(mut)] // Authority signs to trigger claim
+    #[account(mut)]
     pub authority: Signer<'info>,
+    // only the rent reserve may be left, host liquidity goes out through claim_remaining_treasury
     #[account(
         mut,
         seeds = [b"treasury", game.key().as_ref()],
-        bump = game.treasury_bump
+        bump = game.treasury_bump,
+        constraint = game_treasury.lamports() <= game.treasury_rent_reserve @ GameError::TreasuryNotEmpty,
     )]
     pub game_treasury: SystemAccount<'info>,
     pub system_program: Program<'info, System>,
@@ -381,6 +4293,192 @@ pub struct ClaimRemainingTreasury<'info> {
     pub clock: Sysvar<'info, Clock>,
 }
 
+// --- Events ---
+
+#[event]
+pub struct SuspiciousCommitmentFlagged {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub committed_at_slot: u64,
+    pub flagged_at: i64,
+}
+
+#[event]
+pub struct TreasuryBelowAlert {
+    pub host_liquidity: u64,
+    pub threshold: u64,
+    // how many more average sized payouts the host liquidity covers
+    pub remaining_claims_at_avg_payout: u64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub game: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct TreasuryMultisigChanged {
+    pub game: Pubkey,
+    pub authority: Pubkey,
+    pub old_treasury_multisig: Option<Pubkey>,
+    pub new_treasury_multisig: Option<Pubkey>,
+}
+
+#[event]
+pub struct StaleGameClosed {
+    pub game: Pubkey,
+    pub closed_by: Pubkey,
+    // treasury balance swept to the dead letter account
+    pub swept_lamports: u64,
+    pub closed_at: i64,
+}
+
+#[event]
+pub struct HighestBetBonusPaid {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub bet_amount: u64,
+    pub bonus_lamports: u64,
+}
+
+#[event]
+pub struct BulkCommitmentsFrozen {
+    pub count: u8,
+    pub game: Pubkey,
+}
+
+#[event]
+pub struct BulkCommitmentsUnfrozen {
+    pub count: u8,
+    pub game: Pubkey,
+}
+
+#[event]
+pub struct GameFrozen {
+    pub game: Pubkey,
+    pub program_admin: Pubkey,
+}
+
+#[event]
+pub struct GameUnfrozen {
+    pub game: Pubkey,
+    pub program_admin: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct BetOwnershipTransferred {
+    pub game: Pubkey,
+    pub bet_commitment: Pubkey,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct HeartbeatEvent {
+    pub game: Pubkey,
+    // negative once the deadline has passed, and before the result is in as there's no deadline yet
+    pub remaining_seconds: i64,
+    pub unrevealed_bet_count: u64,
+    pub total_player_pot: u64,
+    // see worst_case_remaining_payout_obligation
+    pub worst_case_payout_obligation: u64,
+}
+
+#[event]
+pub struct CharityDistributed {
+    pub game: Pubkey,
+    pub charity_wallet: Pubkey,
+    pub amount: u64,
+    pub total_charity_amount: u64,
+}
+
+#[event]
+pub struct ReputationUpdated {
+    pub player: Pubkey,
+    pub games_participated: u32,
+    pub games_revealed: u32,
+    pub games_timed_out: u32,
+    pub reputation_score: u32,
+}
+
+#[event]
+pub struct PayoutEstimate {
+    // assumed result minus the bet value, negative is a bet over the result and pays nothing
+    pub assumed_diff: i16,
+    // 0 for a loss
+    pub multiplier_scaled: u32,
+    pub estimated_payout: u64,
+}
+
+#[event]
+pub struct GlobalJackpotWon {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub epoch: u64,
+}
+
+#[event]
+pub struct InsuranceClaimPaid {
+    pub player: Pubkey,
+    pub game: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct StaleCommitmentSwept {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub sweeper: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TreasuryOverFunded {
+    pub game: Pubkey,
+    pub excess_amount: u64,
+    // player pot as a share of host liquidity
+    pub current_utilization_bps: u64,
+}
+
+#[event]
+pub struct UnrevealedBetClawedBack {
+    pub player: Pubkey,
+    pub amount: u64,
+    pub game: Pubkey,
+}
+
+#[event]
+pub struct GameCreated {
+    pub authority: Pubkey,
+    pub active_count: u32,
+}
+
+#[event]
+pub struct GameClosed {
+    pub authority: Pubkey,
+    pub active_count: u32,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub game: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub remaining_balance: u64,
+    pub total_claimed_by_authority: u64,
+}
+
+#[event]
+pub struct TreasuryClaimedToEscrow {
+    pub game: Pubkey,
+    pub escrow_address: Pubkey,
+    pub amount: u64,
+}
+
 // --- Error Enum ---
 
 #[error_code]
@@ -402,6 +4500,8 @@ pub enum GameError {
     RevealDeadlineNotReached,
     DeadlineNotSet,
     InsufficientTreasuryForReclaim,
+    // no longer raised, superseded by the PlayerPot/HostLiquidity/PayoutAmount variants below.
+    // kept so the error codes after it don't shift
     #[msg("Total Payout Pot Desynced??? Some bug must have happened.")]
     TotalPayoutPotDesynced,
     BetAlreadySettled,
@@ -409,4 +4509,499 @@ pub enum GameError {
     WithdrawPeriodNotReached,
     TreasuryClaimPeriodNotReached,
     InsufficientPlayerPot,
+    #[msg("Cooldown since the last game closed has not elapsed.")]
+    GameCooldownNotElapsed,
+    InvalidCooldown,
+    TreasuryNotEmpty,
+    #[msg("Total player pot underflowed removing a player's stake. Pot is desynced from the bets.")]
+    PlayerPotUnderflow,
+    #[msg("Total player pot overflowed adding a player's stake.")]
+    PlayerPotOverflow,
+    #[msg("Treasury balance is below the total player pot. Host liquidity underflowed.")]
+    HostLiquidityUnderflow,
+    #[msg("Payout amount overflowed.")]
+    PayoutAmountOverflow,
+    #[msg("Game is both open for bets and reveals.")]
+    InvalidGamePhase,
+    #[msg("Withdrawal would take the treasury below its rent exempt reserve.")]
+    TreasuryBelowRentReserve,
+    #[msg("Result confidence must be between 0 and 100.")]
+    InvalidResultConfidence,
+    ResultNotDisputed,
+    #[msg("Result is disputed, held payouts can't be claimed until it is finalized.")]
+    ResultDisputed,
+    NoHeldPayout,
+    #[msg("Bet would push the player pot over the game's max treasury exposure.")]
+    ExceedsMaxExposure,
+    #[msg("Commitment was flagged as suspicious and is frozen.")]
+    CommitmentFrozen,
+    #[msg("Host liquidity is below the critical threshold, no payouts until the host tops up.")]
+    TreasuryBelowCritical,
+    AutoExtensionDisabled,
+    RevealDeadlineAlreadyExtended,
+    UnsupportedGameVersion,
+    #[msg("Game is already on the current layout.")]
+    GameAlreadyMigrated,
+    BetMerkleRootAlreadySet,
+    BetMerkleRootNotSet,
+    #[msg("Merkle proof does not match the committed bet root.")]
+    InvalidMerkleProof,
+    #[msg("Wait a few slots before revealing again.")]
+    RevealCooldownActive,
+    #[msg("Game can only be closed by anyone a year after its final claim deadline.")]
+    GameNotStale,
+    PlayerPotNotEmpty,
+    InvalidDeadLetterAccount,
+    #[msg("Test mode games can't be created on mainnet.")]
+    TestModeUnavailable,
+    NotTestMode,
+    InvalidEscrowOwner,
+    InvalidBeneficiary,
+    #[msg("Clock sysvar is unavailable, make sure the runtime provides it.")]
+    ClockUnavailable,
+    LeaderboardFrozen,
+    #[msg("This game is NFT gated, use commit_bet_with_token_account_proof.")]
+    NftGateRequired,
+    #[msg("Token account must be the player's and hold the game's gate NFT.")]
+    InvalidNftGateToken,
+    #[msg("Expected an Ed25519 instruction verifying the authority's signature over the result right before submit_result.")]
+    InvalidResultSignature,
+    #[msg("Bet was committed with a commitment version this program doesn't support.")]
+    UnsupportedCommitmentVersion,
+    #[msg("Trophy NFTs are not enabled for this game.")]
+    TrophiesDisabled,
+    #[msg("Player's payout did not beat their stake.")]
+    NotAWinner,
+    #[msg("Signer is neither the authority nor the co-authority.")]
+    UnauthorizedSigner,
+    #[msg("Authority already has the maximum number of active games.")]
+    TooManyActiveGames,
+    #[msg("Game has audit logging on, pass the bet's audit log.")]
+    AuditLogRequired,
+    #[msg("Too many commitments in one batch.")]
+    TooManyCommitments,
+    #[msg("Remaining accounts don't match the commitments derived from the players.")]
+    InvalidRemainingAccounts,
+    #[msg("No emergency withdrawal has been proposed.")]
+    NoPendingWithdraw,
+    #[msg("Emergency withdrawal timelock has not passed yet.")]
+    WithdrawTimelockActive,
+    #[msg("No authority transfer is pending for this signer.")]
+    NoPendingAuthority,
+    #[msg("Authority transfer proposal has expired.")]
+    AuthorityTransferExpired,
+    #[msg("Burn address account is required to settle a losing bet while burn_losing_bets is on.")]
+    BurnAddressRequired,
+    #[msg("Burn address account doesn't match the game's burn address.")]
+    InvalidBurnAddress,
+    #[msg("Price feed account isn't the Pyth SOL/USD price feed.")]
+    InvalidPriceFeed,
+    #[msg("Unrevealed bets can only be clawed back 30 days after the last player deadline.")]
+    ClawbackPeriodNotReached,
+    #[msg("Referral codes are 8 ASCII letters or digits.")]
+    InvalidReferralCode,
+    #[msg("Referral code account is missing, doesn't match the quoted code, or belongs to the player.")]
+    InvalidReferralCodeAccount,
+    #[msg("Can't change this once bets have been committed.")]
+    BetsAlreadyCommitted,
+    #[msg("Charity mode is off for this game.")]
+    CharityModeDisabled,
+    #[msg("Charity wallet account doesn't match the game's charity wallet.")]
+    InvalidCharityWallet,
+    #[msg("A proof of bet NFT is out for this bet, redeem it first.")]
+    ProofOfBetOutstanding,
+    #[msg("Proof of bet mint or token account doesn't match the bet.")]
+    InvalidProofOfBet,
+    #[msg("Players still have funds in the pot, wait for the final claim deadline.")]
+    PlayerObligationsOutstanding,
+    #[msg("Bet has no unpaid winnings to insure.")]
+    NothingToInsure,
+    #[msg("Not enough unpaid reveals to claim insurance.")]
+    NotEnoughUnpaidReveals,
+    #[msg("Insurance fund can't cover the claim.")]
+    InsufficientInsuranceFund,
+    #[msg("Game has no VRF key set.")]
+    VrfNotEnabled,
+    #[msg("VRF key can't be the authority or co-authority.")]
+    InvalidVrfPublicKey,
+    #[msg("VRF proof doesn't verify against the game's VRF key.")]
+    InvalidVrfProof,
+    #[msg("Final claim deadline has already been extended the maximum number of times.")]
+    FinalClaimDeadlineExtensionLimit,
+    #[msg("Payout curve is missing its parameter, or was given one it doesn't use.")]
+    InvalidPayoutCurve,
+    #[msg("Game has a custom payout curve, its PayoutCurve account must be passed.")]
+    PayoutCurveRequired,
+    #[msg("Game takes an anti sybil stake, its vault must be passed.")]
+    AntiSybilVaultRequired,
+    #[msg("Commitment has no anti sybil stake left.")]
+    NoAntiSybilStake,
+    #[msg("Commitments can only be swept once every player deadline has passed.")]
+    SweepPeriodNotReached,
+    #[msg("Commitment's anti sybil stake has to be swept first.")]
+    AntiSybilStakeOutstanding,
+    #[msg("Callback program isn't whitelisted or doesn't match the game's callback program.")]
+    InvalidCallbackProgram,
+    #[msg("Too many programs for the callback whitelist.")]
+    CallbackWhitelistTooLong,
+    #[msg("Result is waiting on the result oracle to confirm it.")]
+    ResultPendingConfirmation,
+    #[msg("Signer isn't the game's result oracle, or the oracle is the authority.")]
+    InvalidResultOracle,
+    #[msg("No result waiting for confirmation.")]
+    NoPendingResult,
+    #[msg("Confirmed result doesn't match the pending result.")]
+    PendingResultMismatch,
+    #[msg("Rebalancing is disabled, treasury_min_utilization_bps is 0.")]
+    RebalanceDisabled,
+    #[msg("Game takes jackpot contributions, the global jackpot must be passed.")]
+    GlobalJackpotRequired,
+    #[msg("Early close is not enabled for this game.")]
+    EarlyCloseNotEnabled,
+    #[msg("Player pot is below the minimum for an early close.")]
+    PotBelowMinForEarlyClose,
+    #[msg("Bet has no locked payout left to claim.")]
+    NoLockedPayout,
+    #[msg("No new payout tranche has unlocked yet.")]
+    TrancheNotUnlocked,
+    #[msg("Result and authority salt don't match the pre-committed result hash.")]
+    ResultHashMismatch,
+    #[msg("Result hash can't be all zeroes.")]
+    InvalidResultHash,
+    #[msg("Game has a pre-committed result, it can't take a VRF result.")]
+    ResultPreCommitted,
+    #[msg("Game has taken its maximum number of bets.")]
+    MaxBetCountReached,
+    #[msg("Host liquidity doesn't cover the worst case payout with this bet.")]
+    InsufficientHostLiquidityForNewBet,
+    #[msg("Bet was over the result, a missed reveal of a losing bet has nothing to withdraw.")]
+    MissedRevealBetLost,
+    #[msg("Game uses hidden results, submit the result encrypted.")]
+    HiddenResultMode,
+    #[msg("Game isn't in hidden result mode.")]
+    NotHiddenResultMode,
+    #[msg("Keyholders must be distinct and set.")]
+    InvalidKeyholders,
+    #[msg("Encrypted result hasn't been submitted.")]
+    EncryptedResultNotSubmitted,
+    #[msg("Signer isn't one of the game's keyholders.")]
+    NotAKeyholder,
+    #[msg("Keyholder already submitted a decryption share.")]
+    DecryptionShareAlreadySubmitted,
+    #[msg("Ciphertext or decryption share isn't a valid Ristretto point.")]
+    InvalidRistrettoPoint,
+    #[msg("Not enough decryption shares to decrypt the result.")]
+    NotEnoughDecryptionShares,
+    #[msg("Decryption shares don't decrypt to a result between 0 and 100.")]
+    DecryptionFailed,
+    #[msg("This game has no biggest loser prize.")]
+    NoBiggestLoserPrize,
+    #[msg("Only the player with the biggest losing bet can claim this prize.")]
+    NotBiggestLoser,
+    #[msg("The biggest loser prize has already been claimed.")]
+    BiggestLoserPrizeAlreadyClaimed,
+    #[msg("Host liquidity in the treasury doesn't cover the biggest loser prize.")]
+    InsufficientHostLiquidityForPrize,
+    #[msg("Betting hasn't opened yet, wait for the commitment window to start.")]
+    BettingNotYetOpen,
+    #[msg("Game configuration is inconsistent, see the logs for the failed check.")]
+    InvalidGameConfiguration,
+    #[msg("Reveals haven't opened yet, the result is still in its verification delay.")]
+    VerificationDelayNotElapsed,
+    #[msg("This game requires unique commitments, pass the commitment marker account.")]
+    CommitmentMarkerRequired,
+    #[msg("This game routes host claims to an escrow, pass the host fee escrow account.")]
+    HostFeeEscrowRequired,
+    #[msg("Account doesn't match the game's host fee escrow.")]
+    InvalidHostFeeEscrow,
+    #[msg("Game has been frozen by the program admin, only reclaim_bet_on_timeout is available.")]
+    GameEmergencyFrozen,
+    #[msg("Game isn't emergency frozen.")]
+    GameNotEmergencyFrozen,
+    #[msg("A bet merkle root is set, reveal with merkle_reveal_and_claim and a proof.")]
+    BetMerkleProofRequired,
+    #[msg("This game has a reveal cooldown, pass the signer's PlayerPnL account.")]
+    PlayerPnlRequired,
+    #[msg("Too few bets were revealed, call check_and_extend_reveal_deadline first.")]
+    RevealExtensionPending,
+    #[msg("Game has a VRF key, its result has to come from submit_result_with_vrf.")]
+    VrfResultRequired,
+    #[msg("Game has bets in the old BetCommitment layout, close it out on the old program instead of migrating.")]
+    MigrationWithBets,
+    #[msg("Key shares must be valid Ristretto points on one polynomial of the threshold's degree.")]
+    InvalidKeyShares,
+    #[msg("Decryption share proof doesn't match the keyholder's key share.")]
+    InvalidDecryptionShareProof,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::default_test_game;
+
+    fn hex<const N: usize>(hex: &str) -> [u8; N] {
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        bytes.try_into().unwrap()
+    }
+
+    // RFC 9381 appendix B.3, example 16
+    #[test]
+    fn ecvrf_verify_matches_the_rfc_test_vector() {
+        let public_key = hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+        let proof: [u8; ECVRF_PROOF_LEN] = hex("8657106690b5526245a92b003bb079ccd1a92130477671f6fc01ad16f26f723f26f8a57ccaed74ee1b190bed1f479d9727d2d0f9b005a6e456a35d4fb0daab1268a1b0db10836d9826a528ca76567805");
+        let beta: [u8; 64] = hex("90cf1df3b703cce59e2a35b925d411164068269d7b2d29f3301c03dd757876ff66b71dda49d2de59d03450451af026798e8f81cd2e333de5cdf4f3e140fdd8ae");
+        assert_eq!(ecvrf_verify(&public_key, &proof, b""), Some(beta));
+        assert_eq!(ecvrf_verify(&public_key, &proof, b"x"), None);
+
+        let mut tampered = proof;
+        tampered[40] ^= 1;
+        assert_eq!(ecvrf_verify(&public_key, &tampered, b""), None);
+
+        // s plus the group order is the same scalar, it mustn't give a second valid proof
+        let mut unreduced = proof;
+        let mut carry = 0u16;
+        for (byte, order_byte) in unreduced[48..].iter_mut().zip(ED25519_GROUP_ORDER) {
+            let sum = *byte as u16 + order_byte as u16 + carry;
+            *byte = sum as u8;
+            carry = sum >> 8;
+        }
+        assert_eq!(ecvrf_verify(&public_key, &unreduced, b""), None);
+    }
+
+    // small scalars throughout so z = k + c * x_i never needs reducing
+    fn prove_decryption_share(x_i: u64, k: u64, c1: &[u8; 32], share: &[u8; 32]) -> [u8; DECRYPTION_SHARE_PROOF_LEN] {
+        let basepoint = PodRistrettoPoint(RISTRETTO_BASEPOINT);
+        let c1_point = PodRistrettoPoint(*c1);
+        let key_share = ristretto_times(x_i as i64, &basepoint).unwrap();
+        let challenge = Sha512::new()
+            .chain_update(DECRYPTION_SHARE_DOMAIN)
+            .chain_update(key_share.0)
+            .chain_update(c1)
+            .chain_update(share)
+            .chain_update(ristretto_times(k as i64, &basepoint).unwrap().0)
+            .chain_update(ristretto_times(k as i64, &c1_point).unwrap().0)
+            .finalize();
+        let c = u128::from_le_bytes(challenge[..16].try_into().unwrap());
+        let low = (c as u64 as u128) * x_i as u128 + k as u128;
+        let high = (c >> 64) * x_i as u128 + (low >> 64);
+        let mut proof = [0u8; DECRYPTION_SHARE_PROOF_LEN];
+        proof[..16].copy_from_slice(&challenge[..16]);
+        proof[16..24].copy_from_slice(&(low as u64).to_le_bytes());
+        proof[24..40].copy_from_slice(&high.to_le_bytes());
+        proof
+    }
+
+    #[test]
+    fn decryption_shares_are_proven_against_key_shares() {
+        let basepoint = PodRistrettoPoint(RISTRETTO_BASEPOINT);
+        // f(t) = 7 + 3t + 5t^2, so the joint key is 7 * G
+        let secrets: [u64; HIDDEN_RESULT_KEYHOLDERS] = [15, 33, 61, 99, 147];
+        let key_shares = secrets.map(|x_i| ristretto_times(x_i as i64, &basepoint).unwrap().0);
+        assert!(key_shares_consistent(&key_shares));
+        let mut off_polynomial = key_shares;
+        off_polynomial[4] = ristretto_times(148, &basepoint).unwrap().0;
+        assert!(!key_shares_consistent(&off_polynomial));
+
+        // result 42 with r = 11: C1 = 11 * G, C2 = (42 + 11 * 7) * G
+        let c1 = ristretto_times(11, &basepoint).unwrap().0;
+        let mut ciphertext = [0u8; 64];
+        ciphertext[..32].copy_from_slice(&c1);
+        ciphertext[32..].copy_from_slice(&ristretto_times(119, &basepoint).unwrap().0);
+        let shares = secrets.map(|x_i| ristretto_times(x_i as i64, &PodRistrettoPoint(c1)).unwrap().0);
+
+        for (index, (&x_i, share)) in secrets.iter().zip(&shares).enumerate() {
+            let proof = prove_decryption_share(x_i, 1_234 + index as u64, &c1, share);
+            assert!(verify_decryption_share(&key_shares[index], &c1, share, &proof));
+            // proven against someone else's key share
+            assert!(!verify_decryption_share(&key_shares[(index + 1) % HIDDEN_RESULT_KEYHOLDERS], &c1, share, &proof));
+
+            let mut tampered = proof;
+            tampered[20] ^= 1;
+            assert!(!verify_decryption_share(&key_shares[index], &c1, share, &tampered));
+
+            let mut unreduced = proof;
+            let mut carry = 0u16;
+            for (byte, order_byte) in unreduced[16..].iter_mut().zip(ED25519_GROUP_ORDER) {
+                let sum = *byte as u16 + order_byte as u16 + carry;
+                *byte = sum as u8;
+                carry = sum >> 8;
+            }
+            assert!(!verify_decryption_share(&key_shares[index], &c1, share, &unreduced));
+        }
+
+        // a share that isn't x_i * C1 can't be proven with x_i
+        let wrong_share = ristretto_times(16 * 11, &basepoint).unwrap().0;
+        let proof = prove_decryption_share(15, 99, &c1, &wrong_share);
+        assert!(!verify_decryption_share(&key_shares[0], &c1, &wrong_share, &proof));
+
+        // any three of the proven shares decrypt to the same result
+        for numbers in [[1, 2, 3], [1, 4, 5], [2, 3, 5]] {
+            let picked = numbers.map(|number: i64| (number, shares[number as usize - 1]));
+            assert_eq!(decrypt_hidden_result(&ciphertext, &picked), Some(42));
+        }
+    }
+
+    #[test]
+    fn late_reveal_penalty_window_ignores_deadline_extensions() {
+        let mut game = default_test_game();
+        game.late_reveal_penalty_bps = 1_000;
+        game.late_reveal_threshold_pct = 90;
+        game.submit_result_timestamp = Some(0);
+        game.scheduled_reveal_deadline = 1_000;
+        // a dispute pushed reveals out, the penalty still starts 90% into the scheduled window
+        game.reveal_deadline = Some(10_000);
+        assert_eq!(game.applied_late_reveal_penalty_bps(900), 0);
+        assert_eq!(game.applied_late_reveal_penalty_bps(910), 1_000);
+        assert_eq!(game.applied_late_reveal_penalty_bps(5_000), 1_000);
+    }
+
+    // a lone bet that wins everything it can has to be covered exactly, on the default config
+    #[test]
+    fn worst_case_obligation_covers_a_perfect_early_bird_highest_bet() {
+        let bet_amount = LAMPORTS_PER_SOL;
+        let mut game = default_test_game();
+        game.total_player_pot = bet_amount;
+        let obligation = worst_case_remaining_payout_obligation(&game);
+        // 4x the stake plus the 5% early bird bonus
+        assert_eq!(obligation, bet_amount * 42 / 10);
+
+        game.reward_highest_bet = true;
+        let obligation = worst_case_remaining_payout_obligation(&game);
+        let settlement = settle_reveal_amounts(&RevealAmounts {
+            bet_amount,
+            scaled_multiplier: Some(game.payout_multiplier(0, None).unwrap()),
+            early_bird_bonus_bps: game.early_bird_bonus_bps,
+            highest_bet_bonus: game.highest_bet_bonus_lamports,
+            total_player_pot: game.total_player_pot,
+            treasury_balance: u64::MAX,
+            ..RevealAmounts::default()
+        })
+        .unwrap();
+        assert_eq!(settlement.outcome, RevealOutcome::Paid);
+        assert_eq!(settlement.payout_amount, obligation);
+    }
+
+    // every Option set so the borsh encoding is as long as it gets
+    #[test]
+    fn game_len_matches_borsh_serialization() {
+        let game = Game {
+            result: Some(0),
+            submission_deadline: Some(0),
+            reveal_deadline: Some(0),
+            final_claim_deadline: Some(0),
+            treasury_multisig: Some(Pubkey::default()),
+            bet_merkle_root: Some([0; 32]),
+            nft_gate_mint: Some(Pubkey::default()),
+            highest_bet_player: Some(Pubkey::default()),
+            host_result_signature: Some([0; 64]),
+            co_authority: Some(Pubkey::default()),
+            pending_withdraw_amount: Some(0),
+            pending_authority: Some(Pubkey::default()),
+            charity_wallet: Some(Pubkey::default()),
+            vrf_public_key: Some(Pubkey::default()),
+            step_threshold: Some(0),
+            constant_multiplier: Some(0),
+            callback_program: Some(Pubkey::default()),
+            result_oracle: Some(Pubkey::default()),
+            pending_result: Some(0),
+            max_bet_count: Some(0),
+            submit_result_timestamp: Some(0),
+            biggest_loser: Some(Pubkey::default()),
+            commitment_window_start: Some(0),
+            host_fee_escrow: Some(Pubkey::default()),
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        game.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), GAME_ACCOUNT_LEN);
+        assert_eq!(data.len(), 8 + Game::INIT_SPACE);
+    }
+
+    #[test]
+    fn bet_commitment_len_matches_borsh_serialization() {
+        let mut bet_commitment = crate::test_fixtures::empty_bet_commitment();
+        bet_commitment.revealed_bet_value = Some(0);
+        bet_commitment.proof_of_bet_mint = Some(Pubkey::default());
+        let mut data = Vec::new();
+        bet_commitment.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), BET_COMMITMENT_ACCOUNT_LEN);
+        assert_eq!(data.len(), 8 + BetCommitment::INIT_SPACE);
+    }
+
+    // every curve initialize_payout_curve accepts has to stay within 0.1% of the real exponential
+    #[test]
+    fn compute_payout_curve_matches_f64_exp() {
+        let bases = (0..=MAX_PAYOUT_CURVE_BASE_TIMES_100).step_by(50);
+        for base_times_100 in bases.chain([1, 390, MAX_PAYOUT_CURVE_BASE_TIMES_100 - 1]) {
+            let exponents = (0..=MAX_PAYOUT_CURVE_EXPONENT_TIMES_100).step_by(25);
+            for exponent_times_100 in exponents.chain([1, 14, MAX_PAYOUT_CURVE_EXPONENT_TIMES_100 - 1]) {
+                let multipliers = compute_payout_curve(base_times_100, exponent_times_100);
+                for (x, &multiplier) in multipliers.iter().enumerate() {
+                    let expected = (base_times_100 as f64 / 100.0
+                        * (-(exponent_times_100 as f64) / 100.0 * x as f64).exp()
+                        + 0.1)
+                        * PAYOUT_SCALE as f64;
+                    let relative_error = (multiplier as f64 - expected).abs() / expected;
+                    assert!(
+                        relative_error <= 0.001,
+                        "base {} exponent {} x {}: got {}, expected {}",
+                        base_times_100,
+                        exponent_times_100,
+                        x,
+                        multiplier,
+                        expected
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn suggest_salt_changes_with_every_input_bit() {
+        let player = Pubkey::new_from_array([7; 32]);
+        let blockhash = [42; 32];
+        let salt = suggest_salt(player, blockhash);
+        assert_eq!(salt, suggest_salt(player, blockhash));
+        for byte in 0..32 {
+            for bit in 0..8 {
+                let mut flipped_player = player.to_bytes();
+                flipped_player[byte] ^= 1 << bit;
+                assert_ne!(salt, suggest_salt(Pubkey::new_from_array(flipped_player), blockhash));
+                let mut flipped_blockhash = blockhash;
+                flipped_blockhash[byte] ^= 1 << bit;
+                assert_ne!(salt, suggest_salt(player, flipped_blockhash));
+            }
+        }
+        // player and blockhash aren't interchangeable
+        assert_ne!(salt, suggest_salt(Pubkey::new_from_array(blockhash), player.to_bytes()));
+    }
+
+    #[test]
+    fn suggest_salt_is_roughly_uniform() {
+        const SAMPLES: u64 = 16_384;
+        let player = Pubkey::new_from_array([7; 32]);
+        let mut buckets = [0u64; 16];
+        let mut bit_counts = [0u64; 64];
+        for i in 0..SAMPLES {
+            let mut blockhash = [0u8; 32];
+            blockhash[..8].copy_from_slice(&i.to_le_bytes());
+            let salt = suggest_salt(player, blockhash);
+            buckets[(salt >> 60) as usize] += 1;
+            for (bit, count) in bit_counts.iter_mut().enumerate() {
+                *count += (salt >> bit) & 1;
+            }
+        }
+        // expected 1024 per bucket, a fair hash stays well inside 15%
+        let expected = SAMPLES / 16;
+        for count in buckets {
+            assert!(count.abs_diff(expected) < expected * 15 / 100, "bucket count {}", count);
+        }
+        // every bit should be set about half the time
+        for count in bit_counts {
+            assert!(count.abs_diff(SAMPLES / 2) < SAMPLES / 20, "bit set {} times", count);
+        }
+    }
 }
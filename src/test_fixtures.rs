@@ -0,0 +1,208 @@
+// Account fixtures and syscall stubs for the unit tests. Off chain the system program CPIs and sysvar reads go
+// through solana_program's syscall stubs, TestSyscallStubs applies transfers for real so tests can check lamports
+use crate::instructions::apply_default_game_config;
+use crate::BetCommitment;
+use crate::Game;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use std::cell::Cell;
+use std::sync::Once;
+
+// system program instruction index for Transfer { lamports }
+const SYSTEM_TRANSFER_INDEX: u32 = 2;
+
+thread_local! {
+    // the stubs are shared by every test thread, the clock each test sees is its own
+    static TEST_CLOCK: Cell<(u64, i64)> = const { Cell::new((0, 0)) };
+}
+
+struct TestSyscallStubs;
+
+impl SyscallStubs for TestSyscallStubs {
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        if instruction.program_id != anchor_lang::system_program::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let data = &instruction.data;
+        if data.len() != 12 || data[..4] != SYSTEM_TRANSFER_INDEX.to_le_bytes() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let amount = u64::from_le_bytes(data[4..].try_into().unwrap());
+        let find = |key: &Pubkey| {
+            account_infos
+                .iter()
+                .find(|info| info.key == key)
+                .ok_or(ProgramError::NotEnoughAccountKeys)
+        };
+        let from = find(&instruction.accounts[0].pubkey)?;
+        let to = find(&instruction.accounts[1].pubkey)?;
+        // same rule as the runtime, the sender signs itself or is a PDA of ours signed for with its seeds
+        let pda_signed = signers_seeds.iter().any(|seeds| {
+            Pubkey::create_program_address(seeds, &crate::ID).is_ok_and(|pda| pda == *from.key)
+        });
+        if !from.is_signer && !pda_signed {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let from_lamports = from
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        **from.try_borrow_mut_lamports()? = from_lamports;
+        **to.try_borrow_mut_lamports()? += amount;
+        Ok(())
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let (slot, unix_timestamp) = TEST_CLOCK.get();
+        let clock = Clock {
+            slot,
+            unix_timestamp,
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        0
+    }
+}
+
+// installs TestSyscallStubs and sets the calling test's clock
+pub(crate) fn set_test_clock(slot: u64, unix_timestamp: i64) {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        set_syscall_stubs(Box::new(TestSyscallStubs));
+    });
+    TEST_CLOCK.set((slot, unix_timestamp));
+}
+
+// AccountInfo borrows everything for 'info, leaking is the easy way to get that in a test
+pub(crate) fn leak_account_info(
+    key: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+    is_signer: bool,
+    executable: bool,
+) -> &'static AccountInfo<'static> {
+    Box::leak(Box::new(AccountInfo::new(
+        Box::leak(Box::new(key)),
+        is_signer,
+        true,
+        Box::leak(Box::new(lamports)),
+        Box::leak(data.into_boxed_slice()),
+        Box::leak(Box::new(owner)),
+        executable,
+        0,
+    )))
+}
+
+pub(crate) fn default_test_game() -> Game {
+    let mut game = Game::default();
+    apply_default_game_config(&mut game);
+    game
+}
+
+// game account with treasury_bump matching the treasury test_treasury derives for it
+pub(crate) fn test_game(mut game: Game) -> Account<'static, Game> {
+    set_test_clock(0, 0);
+    let key = Pubkey::new_unique();
+    (_, game.treasury_bump) =
+        Pubkey::find_program_address(&[b"treasury", key.as_ref()], &crate::ID);
+    let mut data = Vec::new();
+    game.try_serialize(&mut data).unwrap();
+    let info = leak_account_info(key, crate::ID, 0, data, false, false);
+    Account::try_from(info).unwrap()
+}
+
+pub(crate) fn test_treasury(game_key: &Pubkey, lamports: u64) -> SystemAccount<'static> {
+    let (treasury, _) = Pubkey::find_program_address(&[b"treasury", game_key.as_ref()], &crate::ID);
+    let info = leak_account_info(
+        treasury,
+        anchor_lang::system_program::ID,
+        lamports,
+        Vec::new(),
+        false,
+        false,
+    );
+    SystemAccount::try_from(info).unwrap()
+}
+
+pub(crate) fn test_wallet(lamports: u64) -> SystemAccount<'static> {
+    let info = leak_account_info(
+        Pubkey::new_unique(),
+        anchor_lang::system_program::ID,
+        lamports,
+        Vec::new(),
+        false,
+        false,
+    );
+    SystemAccount::try_from(info).unwrap()
+}
+
+pub(crate) fn test_signer(lamports: u64) -> Signer<'static> {
+    let info = leak_account_info(
+        Pubkey::new_unique(),
+        anchor_lang::system_program::ID,
+        lamports,
+        Vec::new(),
+        true,
+        false,
+    );
+    Signer::try_from(info).unwrap()
+}
+
+// clock sysvar account matching what set_test_clock serves
+pub(crate) fn test_clock_sysvar() -> Sysvar<'static, Clock> {
+    let (slot, unix_timestamp) = TEST_CLOCK.get();
+    // bincode layout of Clock, every field little endian in declaration order
+    let mut data = Vec::new();
+    data.extend_from_slice(&slot.to_le_bytes());
+    data.extend_from_slice(&0i64.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&unix_timestamp.to_le_bytes());
+    let info = leak_account_info(
+        anchor_lang::solana_program::sysvar::clock::ID,
+        anchor_lang::solana_program::sysvar::ID,
+        0,
+        data,
+        false,
+        false,
+    );
+    Sysvar::from_account_info(info).unwrap()
+}
+
+pub(crate) fn test_system_program() -> Program<'static, System> {
+    let info = leak_account_info(
+        anchor_lang::system_program::ID,
+        Pubkey::default(),
+        0,
+        Vec::new(),
+        false,
+        true,
+    );
+    Program::try_from(info).unwrap()
+}
+
+pub(crate) fn test_bet_commitment(bet_commitment: BetCommitment) -> Account<'static, BetCommitment> {
+    let mut data = Vec::new();
+    bet_commitment.try_serialize(&mut data).unwrap();
+    let info = leak_account_info(Pubkey::new_unique(), crate::ID, 0, data, false, false);
+    Account::try_from(info).unwrap()
+}
+
+// BetCommitment has no Default, an all zero account decodes to the empty one
+pub(crate) fn empty_bet_commitment() -> BetCommitment {
+    let zeroed = [0u8; crate::BET_COMMITMENT_ACCOUNT_LEN];
+    BetCommitment::try_deserialize_unchecked(&mut &zeroed[..]).unwrap()
+}